@@ -1,24 +1,524 @@
-use std::env;
-
-use once_cell::sync::Lazy;
-use serde::Deserialize;
-use tokio::sync::RwLock;
+use std::{env, fmt::Display, fs, io, net::SocketAddr, path::PathBuf, str::FromStr};
 
+use ams_logic::{
+    attendees::MatchStrategy,
+    class_sessions::MAX_GENERATE_DAYS,
+    settings::{FaceRecMode, Settings},
+};
+use chrono_tz::Tz;
+use clap::Args;
+use once_cell::sync::OnceCell;
+use serde::{
+    de::{value::StrDeserializer, DeserializeOwned, Error as _, IntoDeserializer},
+    Deserialize, Deserializer,
+};
+use thiserror::Error;
 use tracing_subscriber::filter::LevelFilter;
-use utoipa::ToSchema;
 
-pub(crate) static DATABASE_URL: Lazy<String> = Lazy::new(|| env::var("DATABASE_URL").unwrap());
-pub(crate) static SECRET: Lazy<String> = Lazy::new(|| env::var("JWT_SECRET").unwrap());
-pub(crate) static FACEREC_URL: Lazy<String> = Lazy::new(|| env::var("FACEREC_URL").unwrap());
-pub(crate) static ASSETS_DIR: Lazy<String> =
-    Lazy::new(|| env::var("ASSETS_DIR").unwrap_or("assets".to_owned()));
-pub(crate) const LOG_LEVEL: LevelFilter = LevelFilter::DEBUG;
+/// the file read when `--config` is not given, it may not exist.
+const DEFAULT_CONFIG_FILE: &str = "ams.toml";
+
+static CONFIG: OnceCell<AppConfig> = OnceCell::new();
+
+/// the configuration the api was started with.
+///
+/// # Panics
+///
+/// Panics if called before [`init`].
+pub(crate) fn config() -> &'static AppConfig {
+    CONFIG
+        .get()
+        .expect("the configuration is loaded at startup")
+}
+
+/// makes `config` the one returned by [`config`].
+pub(crate) fn init(config: AppConfig) {
+    CONFIG
+        .set(config)
+        .expect("the configuration is loaded only once");
+}
+
+/// the command line flags every command takes.
+#[derive(Args, Debug)]
+pub(crate) struct ConfigArgs {
+    /// the configuration file, defaults to `ams.toml` if it exists.
+    #[arg(long, short, global = true, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+    /// validate the configuration and exit without running the command.
+    #[arg(long, global = true)]
+    pub check_config: bool,
+    /// the address to listen on, overrides `server.bind`.
+    #[arg(long, global = true, value_name = "ADDR")]
+    pub bind: Option<SocketAddr>,
+    /// overrides `server.log_level`.
+    #[arg(long, global = true, value_name = "LEVEL")]
+    pub log_level: Option<LevelFilter>,
+    /// overrides `database.url`.
+    #[arg(long, global = true, value_name = "URL")]
+    pub database_url: Option<String>,
+    /// apply the pending migrations before serving, sets `database.auto_migrate`.
+    #[arg(long, global = true)]
+    pub auto_migrate: bool,
+    /// overrides `face_recognition.url`.
+    #[arg(long, global = true, value_name = "URL")]
+    pub facerec_url: Option<String>,
+    /// overrides `storage.assets_dir`.
+    #[arg(long, global = true, value_name = "DIR")]
+    pub assets_dir: Option<String>,
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum ConfigError {
+    #[error("failed to read `{path}`: {source}")]
+    Read { path: PathBuf, source: io::Error },
+    #[error("failed to parse `{path}`: {source}")]
+    Parse {
+        path: PathBuf,
+        source: Box<toml::de::Error>,
+    },
+    #[error("environment variable `{name}` is invalid: {reason}")]
+    Env { name: &'static str, reason: String },
+    #[error("invalid configuration:\n{}", .0.iter().map(|problem| format!("  - {problem}")).collect::<Vec<_>>().join("\n"))]
+    Invalid(Vec<String>),
+}
+
+/// where faces are recognized.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FaceRecBackendKind {
+    /// the python face recognition service at `face_recognition.url`.
+    Http,
+    /// the onnx model at `face_recognition.onnx_model`, in process.
+    Onnx,
+}
+
+/// the configuration of the api, every value is read from the defaults, then
+/// the configuration file, then the environment and then the command line.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct AppConfig {
+    pub server: ServerConfig,
+    pub database: DatabaseConfig,
+    pub auth: AuthConfig,
+    pub face_recognition: FaceRecConfig,
+    pub storage: StorageConfig,
+    pub attendance: AttendanceConfig,
+    pub class_sessions: ClassSessionsConfig,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct ServerConfig {
+    pub bind: SocketAddr,
+    /// the largest request body in bytes.
+    pub body_limit: usize,
+    /// the largest classifier upload in bytes.
+    pub classifier_body_limit: usize,
+    /// the items per page of a list when `limit` is omitted.
+    pub page_size: u64,
+    /// the most items per page of a list.
+    pub max_page_size: u64,
+    #[serde(deserialize_with = "from_str")]
+    pub log_level: LevelFilter,
+}
 
-#[derive(Deserialize, ToSchema)]
-#[serde(rename_all = "camelCase")]
-pub enum FaceRecModeKind {
-    Classify,
-    Embed,
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            bind: SocketAddr::from(([0, 0, 0, 0], 3000)),
+            body_limit: 50 * 1024 * 1024,
+            classifier_body_limit: 1024 * 1024 * 1024,
+            page_size: 50,
+            max_page_size: 500,
+            log_level: LevelFilter::DEBUG,
+        }
+    }
 }
 
-pub static FACE_REC_MODE: RwLock<FaceRecModeKind> = RwLock::const_new(FaceRecModeKind::Classify);
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct DatabaseConfig {
+    pub url: String,
+    /// apply the pending migrations before serving.
+    pub auto_migrate: bool,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct AuthConfig {
+    pub jwt_secret: String,
+    /// in seconds.
+    pub access_token_ttl: i64,
+    /// in seconds.
+    pub refresh_token_ttl: i64,
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self {
+            jwt_secret: String::new(),
+            access_token_ttl: 15 * 60,
+            refresh_token_ttl: 30 * 24 * 60 * 60,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct FaceRecConfig {
+    pub backend: FaceRecBackendKind,
+    /// the face recognition service, required by the `http` backend.
+    pub url: String,
+    /// the model file, required by the `onnx` backend.
+    pub onnx_model: String,
+    /// the side of the square the `onnx` model takes faces at.
+    pub onnx_size: u32,
+    /// the mode until it is changed at runtime.
+    pub mode: FaceRecMode,
+    /// how many candidates an embedding search returns.
+    pub max_matches: u64,
+    pub match_strategy: MatchStrategy,
+    /// from `0` to `1`, face samples of lower quality are rejected.
+    pub sample_min_quality: f64,
+}
+
+impl Default for FaceRecConfig {
+    fn default() -> Self {
+        Self {
+            backend: FaceRecBackendKind::Http,
+            url: String::new(),
+            onnx_model: String::new(),
+            onnx_size: 160,
+            mode: FaceRecMode::Classify,
+            max_matches: 5,
+            match_strategy: MatchStrategy::Best,
+            sample_min_quality: 0.5,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct StorageConfig {
+    /// served under `/assets`.
+    pub assets_dir: String,
+    /// where uploaded classifiers are kept, apart from the served assets.
+    pub classifiers_dir: String,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            assets_dir: "assets".to_owned(),
+            classifiers_dir: "classifiers".to_owned(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct AttendanceConfig {
+    /// the timezone subject dates are scheduled in.
+    #[serde(deserialize_with = "from_str")]
+    pub timezone: Tz,
+    /// minutes before a session starts attendance is accepted.
+    pub early_grace: i64,
+    /// minutes after a session ends attendance is accepted.
+    pub late_grace: i64,
+    /// absence percentage above which reports flag attendees.
+    pub absence_warning_threshold: f64,
+}
+
+impl Default for AttendanceConfig {
+    fn default() -> Self {
+        Self {
+            timezone: chrono_tz::Africa::Cairo,
+            early_grace: 0,
+            late_grace: 0,
+            absence_warning_threshold: 25.0,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct ClassSessionsConfig {
+    /// when the upcoming class sessions are generated.
+    #[serde(deserialize_with = "from_str")]
+    pub cron: cron::Schedule,
+    /// how many days of class sessions are generated ahead.
+    pub ahead: i64,
+}
+
+impl Default for ClassSessionsConfig {
+    fn default() -> Self {
+        Self {
+            cron: "0 0 0 * * *".parse().unwrap(),
+            ahead: 14,
+        }
+    }
+}
+
+fn from_str<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    String::deserialize(deserializer)?
+        .parse()
+        .map_err(D::Error::custom)
+}
+
+fn parse<T>(value: &str) -> Result<T, String>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    value.parse().map_err(|error: T::Err| error.to_string())
+}
+
+fn variant<T: DeserializeOwned>(value: &str) -> Result<T, String> {
+    let deserializer: StrDeserializer<'_, serde::de::value::Error> = value.into_deserializer();
+    T::deserialize(deserializer).map_err(|error| error.to_string())
+}
+
+/// sets `field` from the environment variable `name` if it is set.
+fn layer<T>(
+    field: &mut T,
+    name: &'static str,
+    parse: impl FnOnce(&str) -> Result<T, String>,
+) -> Result<(), ConfigError> {
+    if let Ok(value) = env::var(name) {
+        *field = parse(&value).map_err(|reason| ConfigError::Env { name, reason })?;
+    }
+    Ok(())
+}
+
+impl AppConfig {
+    /// reads and validates the configuration.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the configuration file can't be
+    /// read or parsed, or if any value is invalid.
+    pub(crate) fn load(args: &ConfigArgs) -> Result<Self, ConfigError> {
+        let mut config = Self::from_file(args.config.as_ref())?;
+        config.layer_env()?;
+        config.layer_args(args);
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    fn from_file(path: Option<&PathBuf>) -> Result<Self, ConfigError> {
+        let (path, required) = match path {
+            Some(path) => (path.clone(), true),
+            None => (PathBuf::from(DEFAULT_CONFIG_FILE), false),
+        };
+
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(error) if !required && error.kind() == io::ErrorKind::NotFound => {
+                return Ok(Self::default())
+            }
+            Err(source) => return Err(ConfigError::Read { path, source }),
+        };
+
+        toml::from_str(&content).map_err(|source| ConfigError::Parse {
+            path,
+            source: Box::new(source),
+        })
+    }
+
+    fn layer_env(&mut self) -> Result<(), ConfigError> {
+        let Self {
+            server,
+            database,
+            auth,
+            face_recognition,
+            storage,
+            attendance,
+            class_sessions,
+        } = self;
+
+        layer(&mut server.bind, "BIND_ADDRESS", parse)?;
+        layer(&mut server.body_limit, "BODY_LIMIT", parse)?;
+        layer(
+            &mut server.classifier_body_limit,
+            "CLASSIFIER_BODY_LIMIT",
+            parse,
+        )?;
+        layer(&mut server.page_size, "PAGE_SIZE", parse)?;
+        layer(&mut server.max_page_size, "MAX_PAGE_SIZE", parse)?;
+        layer(&mut server.log_level, "LOG_LEVEL", parse)?;
+        layer(&mut database.url, "DATABASE_URL", parse)?;
+        layer(&mut database.auto_migrate, "AUTO_MIGRATE", parse)?;
+        layer(&mut auth.jwt_secret, "JWT_SECRET", parse)?;
+        layer(&mut auth.access_token_ttl, "ACCESS_TOKEN_TTL", parse)?;
+        layer(&mut auth.refresh_token_ttl, "REFRESH_TOKEN_TTL", parse)?;
+        layer(&mut face_recognition.backend, "FACEREC_BACKEND", variant)?;
+        layer(&mut face_recognition.url, "FACEREC_URL", parse)?;
+        layer(
+            &mut face_recognition.onnx_model,
+            "FACEREC_ONNX_MODEL",
+            parse,
+        )?;
+        layer(&mut face_recognition.onnx_size, "FACEREC_ONNX_SIZE", parse)?;
+        layer(&mut face_recognition.mode, "FACE_REC_MODE", variant)?;
+        layer(
+            &mut face_recognition.max_matches,
+            "FACEREC_MAX_MATCHES",
+            parse,
+        )?;
+        layer(
+            &mut face_recognition.match_strategy,
+            "FACEREC_MATCH_STRATEGY",
+            variant,
+        )?;
+        layer(
+            &mut face_recognition.sample_min_quality,
+            "FACE_SAMPLE_MIN_QUALITY",
+            parse,
+        )?;
+        layer(&mut storage.assets_dir, "ASSETS_DIR", parse)?;
+        layer(&mut storage.classifiers_dir, "CLASSIFIERS_DIR", parse)?;
+        layer(&mut attendance.timezone, "TIMEZONE", parse)?;
+        layer(&mut attendance.early_grace, "ATTENDANCE_EARLY_GRACE", parse)?;
+        layer(&mut attendance.late_grace, "ATTENDANCE_LATE_GRACE", parse)?;
+        layer(
+            &mut attendance.absence_warning_threshold,
+            "ABSENCE_WARNING_THRESHOLD",
+            parse,
+        )?;
+        layer(&mut class_sessions.cron, "CLASS_SESSIONS_CRON", parse)?;
+        layer(&mut class_sessions.ahead, "CLASS_SESSIONS_AHEAD", parse)?;
+
+        Ok(())
+    }
+
+    fn layer_args(&mut self, args: &ConfigArgs) {
+        if let Some(bind) = args.bind {
+            self.server.bind = bind;
+        }
+        if let Some(log_level) = args.log_level {
+            self.server.log_level = log_level;
+        }
+        if let Some(url) = &args.database_url {
+            self.database.url = url.clone();
+        }
+        if args.auto_migrate {
+            self.database.auto_migrate = true;
+        }
+        if let Some(url) = &args.facerec_url {
+            self.face_recognition.url = url.clone();
+        }
+        if let Some(assets_dir) = &args.assets_dir {
+            self.storage.assets_dir = assets_dir.clone();
+        }
+    }
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        let mut problems = Vec::new();
+        let mut check = |valid: bool, problem: &str| {
+            if !valid {
+                problems.push(problem.to_owned());
+            }
+        };
+
+        check(
+            !self.database.url.is_empty(),
+            "`database.url` (DATABASE_URL) is required",
+        );
+        check(
+            !self.auth.jwt_secret.is_empty(),
+            "`auth.jwt_secret` (JWT_SECRET) is required",
+        );
+        check(
+            self.auth.access_token_ttl > 0,
+            "`auth.access_token_ttl` must be positive",
+        );
+        check(
+            self.auth.refresh_token_ttl > 0,
+            "`auth.refresh_token_ttl` must be positive",
+        );
+        check(
+            self.server.body_limit > 0,
+            "`server.body_limit` must be positive",
+        );
+        check(
+            self.server.classifier_body_limit > 0,
+            "`server.classifier_body_limit` must be positive",
+        );
+        check(
+            (1..=self.server.max_page_size).contains(&self.server.page_size),
+            "`server.page_size` must be positive and at most `server.max_page_size`",
+        );
+
+        let face_recognition = &self.face_recognition;
+        match face_recognition.backend {
+            FaceRecBackendKind::Http => check(
+                !face_recognition.url.is_empty(),
+                "`face_recognition.url` (FACEREC_URL) is required by the http backend",
+            ),
+            FaceRecBackendKind::Onnx => {
+                check(
+                    cfg!(feature = "onnx"),
+                    "the onnx backend needs the api built with the `onnx` feature",
+                );
+                check(
+                    !face_recognition.onnx_model.is_empty(),
+                    "`face_recognition.onnx_model` (FACEREC_ONNX_MODEL) is required by the onnx backend",
+                );
+                check(
+                    face_recognition.onnx_size > 0,
+                    "`face_recognition.onnx_size` must be positive",
+                );
+            }
+        }
+        check(
+            face_recognition.max_matches > 0,
+            "`face_recognition.max_matches` must be at least 1",
+        );
+        check(
+            (0.0..=1.0).contains(&face_recognition.sample_min_quality),
+            "`face_recognition.sample_min_quality` must be between 0 and 1",
+        );
+
+        check(
+            self.attendance.early_grace >= 0,
+            "`attendance.early_grace` must not be negative",
+        );
+        check(
+            self.attendance.late_grace >= 0,
+            "`attendance.late_grace` must not be negative",
+        );
+        check(
+            (0.0..=100.0).contains(&self.attendance.absence_warning_threshold),
+            "`attendance.absence_warning_threshold` must be between 0 and 100",
+        );
+        check(
+            (1..=MAX_GENERATE_DAYS).contains(&self.class_sessions.ahead),
+            "`class_sessions.ahead` must be between 1 and 366",
+        );
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError::Invalid(problems))
+        }
+    }
+
+    /// the settings until they are changed at runtime.
+    pub(crate) fn default_settings(&self) -> Settings {
+        Settings {
+            face_rec_mode: self.face_recognition.mode,
+            match_strategy: self.face_recognition.match_strategy,
+            max_matches: self.face_recognition.max_matches,
+            face_sample_min_quality: self.face_recognition.sample_min_quality,
+            absence_warning_threshold: self.attendance.absence_warning_threshold,
+            attendance_early_grace: self.attendance.early_grace,
+            attendance_late_grace: self.attendance.late_grace,
+        }
+    }
+}
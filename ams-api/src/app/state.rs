@@ -1,21 +1,55 @@
 use std::{path::PathBuf, sync::Arc};
 
-use ams_facerec::FaceRecognizer;
+use ams_facerec::{FaceRecognitionBackend, FaceRecognizer};
 use ams_logic::subjects::{
     AdminsRepo, AdminsRepoTrait, AttendancesRepo, AttendancesRepoTrait, AttendeesRepo,
-    AttendeesRepoTrait, InstructorsRepo, InstructorsRepoTrait, SubjectsRepoTrait,
-    SubjectsRepository,
+    AttendeesRepoTrait, AuditLogRepo, AuditLogRepoTrait, ClassSessionsRepo, ClassSessionsRepoTrait,
+    ClassifiersRepo, ClassifiersRepoTrait, FaceSamplesRepo, FaceSamplesRepoTrait, ImportsService,
+    ImportsServiceTrait, InstructorsRepo, InstructorsRepoTrait, MatchSettingsRepo,
+    MatchSettingsRepoTrait, RepoError, ReportsRepo, ReportsRepoTrait, SessionsRepo,
+    SessionsRepoTrait, SettingsService, SettingsServiceTrait, SubjectsRepoTrait,
+    SubjectsRepository, TermsRepo, TermsRepoTrait,
 };
 use axum::extract::FromRef;
 use sea_orm::DatabaseConnection;
 
-use super::config::FACEREC_URL;
+use super::config::{config, FaceRecBackendKind};
 
 pub(crate) type DynAdminsRepo = Arc<dyn AdminsRepoTrait + Send + Sync>;
 pub(crate) type DynInstructorsRepo = Arc<dyn InstructorsRepoTrait + Send + Sync>;
 pub(crate) type DynAttendeesRepo = Arc<dyn AttendeesRepoTrait + Send + Sync>;
 pub(crate) type DynAttendancesRepo = Arc<dyn AttendancesRepoTrait + Send + Sync>;
 pub(crate) type DynSubjectsRepo = Arc<dyn SubjectsRepoTrait + Send + Sync>;
+pub(crate) type DynSessionsRepo = Arc<dyn SessionsRepoTrait + Send + Sync>;
+pub(crate) type DynClassSessionsRepo = Arc<dyn ClassSessionsRepoTrait + Send + Sync>;
+pub(crate) type DynTermsRepo = Arc<dyn TermsRepoTrait + Send + Sync>;
+pub(crate) type DynReportsRepo = Arc<dyn ReportsRepoTrait + Send + Sync>;
+pub(crate) type DynFaceSamplesRepo = Arc<dyn FaceSamplesRepoTrait + Send + Sync>;
+pub(crate) type DynMatchSettingsRepo = Arc<dyn MatchSettingsRepoTrait + Send + Sync>;
+pub(crate) type DynClassifiersRepo = Arc<dyn ClassifiersRepoTrait + Send + Sync>;
+pub(crate) type DynSettingsService = Arc<dyn SettingsServiceTrait + Send + Sync>;
+pub(crate) type DynImportsService = Arc<dyn ImportsServiceTrait + Send + Sync>;
+pub(crate) type DynAuditLogRepo = Arc<dyn AuditLogRepoTrait + Send + Sync>;
+pub(crate) type DynFaceRecognizer = Arc<dyn FaceRecognitionBackend + Send + Sync>;
+
+/// the face recognition backend selected by `face_recognition.backend`.
+fn face_recognizer() -> DynFaceRecognizer {
+    let face_recognition = &config().face_recognition;
+
+    match face_recognition.backend {
+        FaceRecBackendKind::Http => Arc::new(FaceRecognizer::new(&face_recognition.url)),
+        #[cfg(feature = "onnx")]
+        FaceRecBackendKind::Onnx => Arc::new(
+            ams_facerec::OnnxFaceRecognizer::new(
+                face_recognition.onnx_model.as_str(),
+                face_recognition.onnx_size,
+            )
+            .expect("failed to load the face recognition model"),
+        ),
+        #[cfg(not(feature = "onnx"))]
+        FaceRecBackendKind::Onnx => panic!("built without the `onnx` feature"),
+    }
+}
 
 #[derive(FromRef, Clone)]
 pub(crate) struct State {
@@ -24,31 +58,80 @@ pub(crate) struct State {
     admins_repo: DynAdminsRepo,
     subjects_repo: DynSubjectsRepo,
     attendances_repo: DynAttendancesRepo,
-    face_recognizer: Arc<FaceRecognizer>,
+    sessions_repo: DynSessionsRepo,
+    class_sessions_repo: DynClassSessionsRepo,
+    terms_repo: DynTermsRepo,
+    reports_repo: DynReportsRepo,
+    face_samples_repo: DynFaceSamplesRepo,
+    match_settings_repo: DynMatchSettingsRepo,
+    classifiers_repo: DynClassifiersRepo,
+    settings_service: DynSettingsService,
+    imports_service: DynImportsService,
+    audit_log_repo: DynAuditLogRepo,
+    face_recognizer: DynFaceRecognizer,
 }
 
 impl State {
-    pub(crate) fn new(db: DatabaseConnection, assets: impl Into<PathBuf>) -> Self {
+    pub(crate) async fn new(
+        db: DatabaseConnection,
+        assets: impl Into<PathBuf>,
+    ) -> Result<Self, RepoError> {
         let db = Arc::new(db);
         let (instructor_path, attendee_path) = {
             let assets: PathBuf = assets.into();
 
             (assets.join("instructors"), assets.join("attendees"))
         };
+        let face_samples_repo = Arc::new(FaceSamplesRepo::new(db.clone(), attendee_path.clone()));
         let attendees_repo = Arc::new(AttendeesRepo::new(db.clone(), attendee_path));
         let instructors_repo = Arc::new(InstructorsRepo::new(db.clone(), instructor_path));
         let admins_repo = Arc::new(AdminsRepo(db.clone()));
         let subjects_repo = Arc::new(SubjectsRepository(db.clone()));
-        let attendances_repo = Arc::new(AttendancesRepo(db));
-        let face_recognizer = Arc::new(FaceRecognizer::new(&FACEREC_URL));
+        let settings_service =
+            Arc::new(SettingsService::load(db.clone(), config().default_settings()).await?);
+        let attendances_repo = Arc::new(AttendancesRepo::new(
+            db.clone(),
+            config().attendance.timezone,
+            settings_service.subscribe(),
+        ));
+        let class_sessions_repo = Arc::new(ClassSessionsRepo::new(
+            db.clone(),
+            config().attendance.timezone,
+        ));
+        let terms_repo = Arc::new(TermsRepo(db.clone()));
+        let reports_repo = Arc::new(ReportsRepo::new(db.clone(), config().attendance.timezone));
+        let match_settings_repo = Arc::new(MatchSettingsRepo(db.clone()));
+        let classifiers_repo = Arc::new(ClassifiersRepo::new(
+            db.clone(),
+            config().storage.classifiers_dir.as_str(),
+        ));
+        let imports_service = Arc::new(ImportsService(db.clone()));
+        let audit_log_repo = Arc::new(AuditLogRepo(db.clone()));
+        let sessions_repo = Arc::new(SessionsRepo(db));
+        let face_recognizer = face_recognizer();
+        if !face_recognizer.detects_faces() {
+            tracing::warn!(
+                "the face recognition backend can't find faces, attendance can't be taken from photos"
+            );
+        }
 
-        Self {
+        Ok(Self {
             attendees_repo,
             instructors_repo,
             admins_repo,
             subjects_repo,
             attendances_repo,
+            sessions_repo,
+            class_sessions_repo,
+            terms_repo,
+            reports_repo,
+            face_samples_repo,
+            match_settings_repo,
+            classifiers_repo,
+            settings_service,
+            imports_service,
+            audit_log_repo,
             face_recognizer,
-        }
+        })
     }
 }
@@ -0,0 +1,29 @@
+//! the audit log of the changes made through the api.
+//!
+//! [`scope`] wraps the api and lets the repos know which `POST`, `PUT`,
+//! `PATCH` or `DELETE` of a documented, non public route they're handling, the
+//! user behind it is set once [`Claims`](crate::auth::Claims) authenticates the
+//! request. the repos append the entity before and after a change to the log in
+//! the transaction that makes it, so a change isn't kept without its entry.
+
+use ams_logic::prelude::*;
+use axum::{
+    http::{Method, Request},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::policy::{self, Policy};
+
+pub async fn scope<B>(request: Request<B>, next: Next<B>) -> Response {
+    let method = request.method();
+    let path = request.uri().path();
+
+    match policy::route_policy(method, path) {
+        Some((route, policy)) if method != Method::GET && policy != Policy::Public => {
+            let context = AuditContext::new(method.to_string(), route.to_owned(), path.to_owned());
+            context.scope(next.run(request)).await
+        }
+        _ => next.run(request).await,
+    }
+}
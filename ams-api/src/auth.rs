@@ -1,13 +1,20 @@
 use std::fmt;
 
+use ams_logic::{
+    audit_log::AuditContext,
+    sessions::{Actor, CreateSession, IssuedSession, UserKind},
+};
 use axum::{
     async_trait,
-    extract::{FromRequestParts, TypedHeader},
+    extract::{FromRef, FromRequestParts, TypedHeader},
     headers::{authorization::Bearer, Authorization},
     http::request::Parts,
     RequestPartsExt,
 };
-use jsonwebtoken::{decode, DecodingKey, EncodingKey, Validation};
+use chrono::{Duration, Utc};
+use jsonwebtoken::{
+    decode, encode, errors::ErrorKind, DecodingKey, EncodingKey, Header, Validation,
+};
 
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
@@ -15,56 +22,176 @@ use thiserror::Error;
 use utoipa::{ToResponse, ToSchema};
 use uuid::Uuid;
 
-use crate::{app, error::ApiError};
+use crate::{
+    app::{self, DynSessionsRepo},
+    error::ApiError,
+};
 
 pub static KEYS: Lazy<Keys> = Lazy::new(|| {
-    let secret = app::config::SECRET.as_bytes();
+    let secret = app::config::config().auth.jwt_secret.as_bytes();
     Keys::new(secret)
 });
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum User {
     Admin(Uuid),
     Instructor(Uuid),
     Attendee(Uuid),
 }
 
+impl User {
+    pub fn id(&self) -> Uuid {
+        match self {
+            User::Admin(id) | User::Instructor(id) | User::Attendee(id) => *id,
+        }
+    }
+
+    pub fn kind(&self) -> UserKind {
+        match self {
+            User::Admin(_) => UserKind::Admin,
+            User::Instructor(_) => UserKind::Instructor,
+            User::Attendee(_) => UserKind::Attendee,
+        }
+    }
+
+    /// the user as recorded in audit trails.
+    pub fn actor(&self) -> Actor {
+        Actor {
+            kind: self.kind(),
+            id: self.id(),
+        }
+    }
+
+    pub fn from_kind(kind: UserKind, id: Uuid) -> Self {
+        match kind {
+            UserKind::Admin => User::Admin(id),
+            UserKind::Instructor => User::Instructor(id),
+            UserKind::Attendee => User::Attendee(id),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct Claims {
     pub user: User,
+    /// the session this access token was issued for.
+    pub sid: Uuid,
     pub exp: usize,
 }
 
 impl fmt::Debug for Claims {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "user: {:?}, expire period: {}", self.user, self.exp)
+        write!(
+            f,
+            "user: {:?}, session: {}, expire period: {}",
+            self.user, self.sid, self.exp
+        )
     }
 }
 
 #[async_trait]
 impl<S> FromRequestParts<S> for Claims
 where
+    DynSessionsRepo: FromRef<S>,
     S: Send + Sync,
 {
     type Rejection = ApiError;
 
     #[tracing::instrument(level = "debug", ret, err, skip_all)]
-    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
         let TypedHeader(Authorization(bearer)) = parts
             .extract::<TypedHeader<Authorization<Bearer>>>()
             .await
             .map_err(|_| AuthError::InvalidToken)?;
 
         let token_data = decode::<Claims>(bearer.token(), &KEYS.decoding, &Validation::default())
-            .map_err(|_| AuthError::InvalidToken)?;
+            .map_err(|error| match error.kind() {
+            ErrorKind::ExpiredSignature => AuthError::ExpiredToken,
+            _ => AuthError::InvalidToken,
+        })?;
+
+        let sessions_repo = DynSessionsRepo::from_ref(state);
+        if !sessions_repo.is_active(token_data.claims.sid).await? {
+            return Err(AuthError::RevokedSession.into());
+        }
+        AuditContext::authenticated(token_data.claims.user.actor());
 
         Ok(token_data.claims)
     }
 }
 
 #[derive(Debug, Serialize, Deserialize, ToResponse, ToSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct AuthBody {
+    /// short lived access token.
     pub token: String,
+    /// long lived token used to obtain a new access token from `/auth/refresh`.
+    pub refresh_token: String,
+    /// seconds until the access token expires.
+    pub expires_in: i64,
+}
+
+impl AuthBody {
+    fn new(user: User, issued: IssuedSession) -> Result<Self, AuthError> {
+        let expires_in = app::config::config().auth.access_token_ttl;
+
+        let claims = Claims {
+            user,
+            sid: issued.session.id,
+            exp: (Utc::now() + Duration::seconds(expires_in)).timestamp() as usize,
+        };
+
+        let token = encode(&Header::default(), &claims, &KEYS.encoding)
+            .map_err(|_| AuthError::TokenCreation)?;
+
+        Ok(Self {
+            token,
+            refresh_token: issued.refresh_token,
+            expires_in,
+        })
+    }
+
+    /// starts a new session for the user and issues its first token pair.
+    pub(crate) async fn issue(
+        sessions_repo: &DynSessionsRepo,
+        user: User,
+    ) -> Result<Self, ApiError> {
+        let issued = sessions_repo
+            .create(CreateSession {
+                user_kind: user.kind(),
+                user_id: user.id(),
+                expires_at: (Utc::now()
+                    + Duration::seconds(app::config::config().auth.refresh_token_ttl))
+                .into(),
+            })
+            .await?;
+
+        Ok(Self::new(user, issued)?)
+    }
+
+    /// rotates the refresh token of an existing session and issues a new token pair.
+    pub(crate) async fn refresh(
+        sessions_repo: &DynSessionsRepo,
+        refresh_token: String,
+    ) -> Result<Self, ApiError> {
+        let issued = sessions_repo
+            .rotate(
+                refresh_token,
+                (Utc::now() + Duration::seconds(app::config::config().auth.refresh_token_ttl))
+                    .into(),
+            )
+            .await?;
+
+        let user = User::from_kind(issued.session.user_kind, issued.session.user_id);
+
+        Ok(Self::new(user, issued)?)
+    }
+}
+
+#[derive(Deserialize, Serialize, ToSchema, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshPayload {
+    pub refresh_token: String,
 }
 
 #[derive(Deserialize, Serialize, ToSchema, Debug)]
@@ -101,6 +228,10 @@ pub enum AuthError {
     TokenCreation,
     #[error("invalid token")]
     InvalidToken,
+    #[error("token has expired")]
+    ExpiredToken,
+    #[error("session has been revoked")]
+    RevokedSession,
 }
 
 pub struct Keys {
@@ -0,0 +1,42 @@
+use clap::{Parser, Subcommand};
+
+use crate::app::config::ConfigArgs;
+
+#[derive(Parser, Debug)]
+#[command(version, about = "attendance management system api")]
+pub(crate) struct Cli {
+    #[command(flatten)]
+    pub config: ConfigArgs,
+    /// serves the api when omitted.
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum Command {
+    /// serve the api.
+    Serve,
+    /// manage the database schema.
+    #[command(subcommand)]
+    Migrate(MigrateCommand),
+    /// insert the development admin accounts that are missing.
+    Seed,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum MigrateCommand {
+    /// apply the pending migrations.
+    Up {
+        /// how many to apply, all of them by default.
+        #[arg(long, short)]
+        steps: Option<u32>,
+    },
+    /// revert the applied migrations.
+    Down {
+        /// how many to revert.
+        #[arg(long, short, default_value_t = 1)]
+        steps: u32,
+    },
+    /// list the migrations and whether each one is applied.
+    Status,
+}
@@ -0,0 +1,80 @@
+use std::{collections::HashMap, sync::Arc};
+
+use ams_logic::{
+    admins::{AdminsRepo, AdminsRepoTrait, CreateAdmin},
+    error::RepoError,
+};
+use ams_migration::{Migrator, MigratorTrait};
+use chrono::{TimeZone, Utc};
+use sea_orm::{DatabaseConnection, DbErr};
+
+use crate::cli::MigrateCommand;
+
+/// the development admin accounts as `(name, email, password)`.
+const SEED_ADMINS: [(&str, &str, &str); 3] = [
+    ("Mina Saad", "mina@saad.com", "474747"),
+    ("Mina Emil", "mina@emil.com", "393939"),
+    ("Mina Girgs", "mina@girgs.com", "717171"),
+];
+
+/// applies, reverts or lists the migrations.
+///
+/// # Errors
+///
+/// This function will return an error if a migration fails, the migrations
+/// run before it stay applied.
+pub(crate) async fn migrate(db: &DatabaseConnection, command: MigrateCommand) -> Result<(), DbErr> {
+    match command {
+        MigrateCommand::Up { steps } => Migrator::up(db, steps).await?,
+        MigrateCommand::Down { steps } => Migrator::down(db, Some(steps)).await?,
+        MigrateCommand::Status => {
+            let applied: HashMap<_, _> = Migrator::get_migration_models(db)
+                .await?
+                .into_iter()
+                .map(|migration| (migration.version, migration.applied_at))
+                .collect();
+
+            for migration in Migrator::migrations() {
+                let name = migration.name();
+                match applied
+                    .get(name)
+                    .and_then(|applied_at| Utc.timestamp_opt(*applied_at, 0).single())
+                {
+                    Some(applied_at) => println!("applied  {name}  at {applied_at}"),
+                    None => println!("pending  {name}"),
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// inserts the development admin accounts, skipping the ones whose email is
+/// already taken.
+///
+/// # Errors
+///
+/// This function will return an error if an account can't be looked up or
+/// inserted.
+pub(crate) async fn seed(db: DatabaseConnection) -> Result<(), RepoError> {
+    let repo = AdminsRepo(Arc::new(db));
+
+    for (name, email, password) in SEED_ADMINS {
+        match repo.get_by_email(email.to_owned()).await {
+            Ok(_) => println!("skipped  {email}, it already exists"),
+            Err(RepoError::NotFound(_)) => {
+                repo.create(CreateAdmin {
+                    name: name.to_owned(),
+                    email: email.to_owned(),
+                    password: password.to_owned(),
+                })
+                .await?;
+                println!("created  {email}");
+            }
+            Err(error) => return Err(error),
+        }
+    }
+
+    Ok(())
+}
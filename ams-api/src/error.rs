@@ -1,6 +1,7 @@
 use ams_facerec::FaceRecognitionError;
 use ams_logic::prelude::RepoError;
 use axum::{http::StatusCode, response::IntoResponse, Json};
+use chrono::{DateTime, FixedOffset};
 use serde::Serialize;
 use serde_json::json;
 use thiserror::Error;
@@ -17,17 +18,37 @@ pub enum ApiError {
     NotFound { message: String },
     #[error("record already exists")]
     Duplicate { message: String },
+    #[error("request conflicts with the current state")]
+    Conflict { message: String },
     #[error("unauthorized access")]
     Unauthorized { message: String },
+    #[error("forbidden")]
+    Forbidden { message: String },
     #[error("face could not be recogized")]
-    FaceRecogition,
+    FaceRecogition {
+        reason: &'static str,
+        message: String,
+        #[serde(skip)]
+        status: StatusCode,
+    },
+    #[error("face sample quality is too low")]
+    #[serde(rename_all = "camelCase")]
+    LowQualityFaceSample { quality: f64, min_quality: f64 },
     #[error("bad request")]
     BadRequest,
+    #[error("invalid request")]
+    Validation { message: String },
     #[error("internal server error")]
     Internal,
     #[error("attendance already taken")]
     #[serde(rename_all = "camelCase")]
     DuplicateAttendance { subject_id: Uuid, attendee_id: Uuid },
+    #[error("attendance can only be taken during a session")]
+    #[serde(rename_all = "camelCase")]
+    OutsideSessionWindow {
+        subject_id: Uuid,
+        next_session: Option<DateTime<FixedOffset>>,
+    },
 }
 
 impl ApiError {
@@ -35,8 +56,16 @@ impl ApiError {
         #[allow(unused)]
         match self {
             ApiError::NotFound { .. } => StatusCode::NOT_FOUND,
-            ApiError::Duplicate { .. } => StatusCode::CONFLICT,
+            ApiError::Duplicate { .. }
+            | ApiError::Conflict { .. }
+            | ApiError::DuplicateAttendance { .. } => StatusCode::CONFLICT,
             ApiError::Unauthorized { message } => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden { .. } => StatusCode::FORBIDDEN,
+            ApiError::BadRequest | ApiError::Validation { .. } => StatusCode::BAD_REQUEST,
+            ApiError::OutsideSessionWindow { .. } | ApiError::LowQualityFaceSample { .. } => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            ApiError::FaceRecogition { status, .. } => *status,
             _ => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
@@ -45,7 +74,25 @@ impl ApiError {
 impl From<FaceRecognitionError> for ApiError {
     #[tracing::instrument(level = "error")]
     fn from(error: FaceRecognitionError) -> Self {
-        Self::FaceRecogition
+        let status = match &error {
+            FaceRecognitionError::NoFace
+            | FaceRecognitionError::MultipleFaces { .. }
+            | FaceRecognitionError::InvalidImage(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            FaceRecognitionError::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            FaceRecognitionError::Timeout => StatusCode::GATEWAY_TIMEOUT,
+            FaceRecognitionError::Service { .. } | FaceRecognitionError::Decode(_) => {
+                StatusCode::BAD_GATEWAY
+            }
+            FaceRecognitionError::Unsupported(_) => StatusCode::NOT_IMPLEMENTED,
+            FaceRecognitionError::Model(_) | FaceRecognitionError::InvalidModel(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        };
+        Self::FaceRecogition {
+            reason: error.reason(),
+            message: error.to_string(),
+            status,
+        }
     }
 }
 
@@ -71,10 +118,14 @@ impl From<RepoError> for ApiError {
             RepoError::NotFound(message) => Self::NotFound {
                 message: message.clone(),
             },
+            RepoError::NoClassifierToRollBack => Self::NotFound {
+                message: error.to_string(),
+            },
             RepoError::DuplicateSubject
             | RepoError::DuplicateAttendee
             | RepoError::DuplicateInstructor
             | RepoError::DuplicateAdmin
+            | RepoError::DuplicateTerm
             | RepoError::Duplicate(_, _) => Self::Duplicate {
                 message: error.to_string(),
             },
@@ -85,7 +136,34 @@ impl From<RepoError> for ApiError {
                 subject_id: *subject_id,
                 attendee_id: *attendee_id,
             },
-            RepoError::Unknown => Self::Internal,
+            RepoError::OutsideSessionWindow {
+                subject_id,
+                next_session,
+            } => Self::OutsideSessionWindow {
+                subject_id: *subject_id,
+                next_session: *next_session,
+            },
+            RepoError::LastAdmin
+            | RepoError::OverCapacity { .. }
+            | RepoError::ClassSessionCancelled { .. } => Self::Conflict {
+                message: error.to_string(),
+            },
+            RepoError::WrongCredentials | RepoError::InvalidSession => Self::Unauthorized {
+                message: error.to_string(),
+            },
+            RepoError::InvalidClassSessionTimes
+            | RepoError::InvalidDateRange { .. }
+            | RepoError::InvalidRange { .. }
+            | RepoError::InvalidMatchThreshold { .. }
+            | RepoError::InvalidSetting { .. }
+            | RepoError::InvalidListQuery(_)
+            | RepoError::InvalidCsv(_)
+            | RepoError::InvalidReason(_)
+            | RepoError::InvalidCapacity(_)
+            | RepoError::ConstraintViolation(_) => Self::Validation {
+                message: error.to_string(),
+            },
+            RepoError::Export(_) | RepoError::Unknown => Self::Internal,
         }
     }
 }
@@ -94,11 +172,15 @@ impl From<AuthError> for ApiError {
     fn from(value: AuthError) -> Self {
         match value {
             AuthError::WrongCredentials
-            | AuthError::UnauthorizedAccess
-            | AuthError::MissingCredentials => Self::Unauthorized {
+            | AuthError::MissingCredentials
+            | AuthError::InvalidToken
+            | AuthError::ExpiredToken
+            | AuthError::RevokedSession => Self::Unauthorized {
+                message: value.to_string(),
+            },
+            AuthError::UnauthorizedAccess => Self::Forbidden {
                 message: value.to_string(),
             },
-            AuthError::InvalidToken => Self::BadRequest,
             _ => Self::Internal,
         }
     }
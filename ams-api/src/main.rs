@@ -1,16 +1,29 @@
 mod app;
+mod audit;
 mod auth;
+mod cli;
+mod commands;
 mod error;
 mod openapi_docs;
+mod pagination;
+mod policy;
 mod response;
 mod routes;
 mod setup;
 
-use std::net::SocketAddr;
-
-use axum::{extract::DefaultBodyLimit, http::StatusCode, routing::get_service, Router};
+use ams_logic::settings::{FaceRecMode, UpdateSettings};
+use ams_migration::{Migrator, MigratorTrait};
+use app::config::AppConfig;
+use axum::{
+    extract::{DefaultBodyLimit, FromRef},
+    http::StatusCode,
+    routing::get_service,
+    Router,
+};
+use clap::Parser;
 use dotenvy::dotenv;
 use openapi_docs::ApiDocs;
+use sea_orm::DatabaseConnection;
 use tower::ServiceBuilder;
 use tower_http::{
     compression::CompressionLayer,
@@ -21,44 +34,86 @@ use tower_http::{
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
-use crate::routes::{admins, attendances, attendees, config, instructors, subjects};
+use crate::cli::{Cli, Command};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // load envirnment variables from .env
     dotenv().ok();
 
+    // layer the configuration file, the environment and the command line
+    let cli = Cli::parse();
+    let app_config = match AppConfig::load(&cli.config) {
+        Ok(app_config) => app_config,
+        Err(error) => {
+            eprintln!("{error}");
+            std::process::exit(1);
+        }
+    };
+    if cli.config.check_config {
+        println!("configuration is valid");
+        return Ok(());
+    }
+    app::config::init(app_config);
+    let app_config = app::config::config();
+
     // enable tracing
-    setup::tracing();
+    setup::tracing(app_config.server.log_level);
 
     // connect to the database
     let db = setup::database_connection().await?;
     tracing::info!("connected the database successfully");
 
+    match cli.command.unwrap_or(Command::Serve) {
+        Command::Serve => serve(db).await,
+        Command::Migrate(command) => Ok(commands::migrate(&db, command).await?),
+        Command::Seed => Ok(commands::seed(db).await?),
+    }
+}
+
+async fn serve(db: DatabaseConnection) -> Result<(), Box<dyn std::error::Error>> {
+    let app_config = app::config::config();
+
+    if app_config.database.auto_migrate {
+        Migrator::up(&db, None).await?;
+        tracing::info!("applied the pending migrations successfully");
+    }
+
     // construct app state
-    let state = app::State::new(db, app::config::ASSETS_DIR.as_str());
+    let state = app::State::new(db, app_config.storage.assets_dir.as_str()).await?;
+
+    // keep the settings in sync with the other instances
+    let settings: app::DynSettingsService = FromRef::from_ref(&state);
+    setup::settings_listener(settings.clone());
+
+    // the in process backend can only match embeddings
+    if app_config.face_recognition.backend == app::config::FaceRecBackendKind::Onnx
+        && settings.current().face_rec_mode != FaceRecMode::Embed
+    {
+        tracing::warn!("switching to the embed face recognition mode for the onnx backend");
+        settings
+            .update(UpdateSettings {
+                face_rec_mode: Some(FaceRecMode::Embed),
+                ..Default::default()
+            })
+            .await?;
+    }
+
+    // keep the upcoming class sessions generated
+    setup::class_sessions_generator(FromRef::from_ref(&state));
 
-    let assets =
-        get_service(ServeDir::new("assets")).handle_error(|error: std::io::Error| async move {
+    let assets = get_service(ServeDir::new(&app_config.storage.assets_dir)).handle_error(
+        |error: std::io::Error| async move {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 format!("Unhandled internal error: {}", error),
             )
-        });
+        },
+    );
 
     let app = Router::new()
         .merge(SwaggerUi::new("/swagger-ui").url("/api-doc/openapi.json", ApiDocs::openapi()))
-        .nest(
-            "/api",
-            Router::new()
-                .merge(config::routes())
-                .merge(admins::routes())
-                .merge(instructors::routes())
-                .merge(attendances::routes())
-                .merge(attendees::routes())
-                .merge(subjects::routes())
-                .with_state(state),
-        )
+        .nest("/api", routes::api().with_state(state))
         .nest_service("/assets", assets)
         .layer(
             ServiceBuilder::new()
@@ -70,12 +125,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 )
                 .layer(NormalizePathLayer::trim_trailing_slash())
                 .layer(CompressionLayer::new())
-                .layer(DefaultBodyLimit::max(1024 * 1024 * 50)),
+                .layer(DefaultBodyLimit::max(app_config.server.body_limit)),
         );
 
     // start serving
-    let addr = SocketAddr::from(([0, 0, 0, 0], 3000));
-    axum::Server::bind(&addr)
+    axum::Server::bind(&app_config.server.bind)
         .serve(app.into_make_service())
         .await?;
 
@@ -7,8 +7,9 @@ use utoipa::{
     Modify, OpenApi, ToSchema,
 };
 
-use crate::response::*;
+use crate::{policy::PolicyAddon, response::*};
 
+#[allow(dead_code)]
 #[derive(ToSchema)]
 pub struct Image {
     #[schema(value_type = Option<String>, format = Binary)]
@@ -17,19 +18,42 @@ pub struct Image {
     pub any: Option<File>,
 }
 
+#[allow(dead_code)]
 #[derive(ToSchema)]
 pub struct Classifier {
     #[schema(value_type = String, format = Binary)]
     pub any: File,
+    pub notes: Option<String>,
 }
 
 #[derive(OpenApi)]
 #[openapi(
     paths(
         crate::routes::admins::login,
+        crate::routes::admins::get_all,
+        crate::routes::admins::get_one,
+        crate::routes::admins::create_one,
+        crate::routes::admins::update_one,
+        crate::routes::admins::delete_one,
+        crate::routes::admins::change_password,
 
+        crate::routes::auth::refresh,
+        crate::routes::auth::logout,
+
+        crate::routes::config::get_classifier,
         crate::routes::config::upload_classifier,
+        crate::routes::config::get_classifiers,
+        crate::routes::config::activate_classifier,
+        crate::routes::config::rollback_classifier,
+        crate::routes::config::get_face_recognition,
         crate::routes::config::face_recognition,
+        crate::routes::config::get_settings,
+        crate::routes::config::update_settings,
+        crate::routes::config::get_matching,
+        crate::routes::config::update_matching,
+        crate::routes::config::get_subject_matching,
+        crate::routes::config::update_subject_matching,
+        crate::routes::config::delete_subject_matching,
 
         crate::routes::instructors::login_with_creds,
         crate::routes::instructors::login_with_token,
@@ -57,6 +81,7 @@ pub struct Classifier {
         crate::routes::attendees::put_one_subject_to_one,
         crate::routes::attendees::delete_one_subject_from_one,
         crate::routes::attendees::upload_image,
+        crate::routes::attendees::get_all_attendances_with_one_attendee_and_one_subject,
 
         crate::routes::subjects::get_all,
         crate::routes::subjects::get_one,
@@ -64,19 +89,58 @@ pub struct Classifier {
         crate::routes::subjects::update_one,
         crate::routes::subjects::delete_one,
         crate::routes::subjects::get_all_attendees,
+        crate::routes::subjects::update_roster,
+        crate::routes::subjects::copy_roster,
         crate::routes::subjects::add_one_subject_date,
         crate::routes::subjects::delete_one_subject_date,
 
         crate::routes::attendances::get_all_for_one_subject,
         crate::routes::attendances::create_one,
+        crate::routes::attendances::create_many,
+        crate::routes::attendances::create_many_with_photo,
+        crate::routes::attendances::delete_one,
+        crate::routes::attendances::correct_one,
+        crate::routes::attendances::mark_one,
+        crate::routes::attendances::get_audit_for_one_subject,
+
+        crate::routes::class_sessions::get_all_for_one_subject,
+        crate::routes::class_sessions::generate_for_one_subject,
+        crate::routes::class_sessions::get_one,
+        crate::routes::class_sessions::cancel_one,
+        crate::routes::class_sessions::reschedule_one,
+
+        crate::routes::terms::get_all,
+        crate::routes::terms::create_one,
+        crate::routes::terms::get_one,
+        crate::routes::terms::update_one,
+        crate::routes::terms::delete_one,
+        crate::routes::terms::add_one_holiday,
+        crate::routes::terms::delete_one_holiday,
+
+        crate::routes::reports::get_one_for_one_subject,
+
+        crate::routes::imports::import,
+
+        crate::routes::audit_log::get_all,
+
+        crate::routes::face_samples::get_all,
+        crate::routes::face_samples::create_one,
+        crate::routes::face_samples::delete_one,
+        crate::routes::face_samples::embed_one,
     ),
     components(
         schemas(
             crate::routes::config::FaceRecognition,
-            crate::app::config::FaceRecModeKind,
+            ams_logic::settings::FaceRecMode,
+            ams_logic::settings::Settings,
+            ams_logic::settings::UpdateSettings,
             crate::auth::AuthPayload,
             crate::auth::AuthBody,
+            crate::auth::RefreshPayload,
             ams_logic::admins::Admin,
+            ams_logic::admins::CreateAdmin,
+            ams_logic::admins::UpdateAdmin,
+            ams_logic::admins::ChangePassword,
             ams_logic::attendees::Attendee,
             ams_logic::instructors::Instructor,
             ams_logic::instructors::CreateInstructor,
@@ -84,13 +148,56 @@ pub struct Classifier {
             ams_logic::attendees::CreateAttendee,
             ams_logic::attendees::UpdateAttendee,
             ams_logic::attendances::Attendance,
+            ams_logic::attendances::CreateAttendances,
+            ams_logic::attendances::AttendanceStatus,
+            ams_logic::attendances::CorrectAttendance,
+            ams_logic::attendances::AttendanceAuditEntry,
+            ams_logic::sessions::Actor,
+            ams_logic::sessions::UserKind,
+            ams_facerec::BoundingBox,
+            crate::routes::attendances::MatchedFace,
+            crate::routes::attendances::UnmatchedFace,
+            crate::routes::attendances::PhotoAttendance,
             ams_logic::subjects::Subject,
             ams_logic::subjects::CreateSubject,
             ams_logic::subjects::UpdateSubject,
             ams_logic::subjects::SubjectDate,
             ams_logic::subjects::CreateSubjectDate,
+            ams_logic::subjects::RosterMode,
+            ams_logic::subjects::UpdateRoster,
+            ams_logic::subjects::CopyRoster,
+            ams_logic::subjects::RosterDiff,
+            ams_logic::class_sessions::ClassSession,
+            ams_logic::class_sessions::ClassSessionStatus,
+            ams_logic::class_sessions::ClassSessionCancelReason,
+            ams_logic::class_sessions::GenerateClassSessions,
+            ams_logic::class_sessions::CancelClassSession,
+            ams_logic::class_sessions::RescheduleClassSession,
+            ams_logic::terms::Term,
+            ams_logic::terms::TermHoliday,
+            ams_logic::terms::CreateTerm,
+            ams_logic::terms::UpdateTerm,
+            ams_logic::terms::CreateTermHoliday,
+            ams_logic::reports::SubjectReport,
+            ams_logic::reports::ReportSession,
+            ams_logic::reports::ReportRow,
+            ams_logic::imports::ImportKind,
+            ams_logic::imports::ImportErrorKind,
+            ams_logic::imports::ImportRowError,
+            ams_logic::imports::ImportReport,
+            ams_logic::audit_log::AuditEntry,
+            ams_logic::face_samples::FaceSample,
+            ams_logic::attendees::MatchStrategy,
+            ams_logic::match_settings::MatchMetric,
+            ams_logic::match_settings::MatchSettings,
+            ams_logic::match_settings::UpdateMatchSettings,
+            ams_logic::classifiers::ClassifierModel,
+            crate::routes::attendees::AttendeeMatch,
+            crate::routes::reports::ReportFormat,
             AuthResponse,
             AdminResponse,
+            AdminsList,
+            AdminsListResponse,
             InstructorsList,
             AttendeesList,
             SubjectsList,
@@ -103,13 +210,39 @@ pub struct Classifier {
             SubjectsListResponse,
             SubjectDateResponse,
             SubjectDatesListResponse,
+            RosterDiffResponse,
             AttendanceResponse,
             AttendancesListResponse,
+            AttendanceAuditList,
+            AttendanceAuditListResponse,
+            AuditEntriesList,
+            AuditEntriesListResponse,
+            ClassSessionsList,
+            ClassSessionResponse,
+            ClassSessionsListResponse,
+            TermsList,
+            TermResponse,
+            TermsListResponse,
+            TermHolidayResponse,
+            SubjectReportResponse,
+            ImportReportResponse,
+            FaceSamplesList,
+            FaceSampleResponse,
+            FaceSamplesListResponse,
+            AttendeeMatchesList,
+            AttendeeMatchesListResponse,
+            MatchSettingsResponse,
+            PhotoAttendanceResponse,
+            ClassifierModelsList,
+            ClassifierModelResponse,
+            FaceRecModeResponse,
+            SettingsResponse,
+            ClassifierModelsListResponse,
             Image,
             Classifier,
         ),
     ),
-    modifiers(&SecurityAddon)
+    modifiers(&SecurityAddon, &PolicyAddon)
 )]
 pub struct ApiDocs;
 
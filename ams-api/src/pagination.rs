@@ -0,0 +1,38 @@
+use ams_logic::pagination::ListQuery;
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Query},
+    http::request::Parts,
+};
+
+use crate::{app::config, error::ApiError};
+
+/// the [`ListQuery`] of a list route, its page size bounded by
+/// `server.page_size` and `server.max_page_size`.
+pub(crate) struct Paginated(pub ListQuery);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for Paginated
+where
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(mut query) = Query::<ListQuery>::from_request_parts(parts, state)
+            .await
+            .map_err(|rejection| ApiError::Validation {
+                message: rejection.body_text(),
+            })?;
+
+        let server = &config::config().server;
+        query.limit = Some(
+            query
+                .limit
+                .unwrap_or(server.page_size)
+                .clamp(1, server.max_page_size),
+        );
+
+        Ok(Self(query))
+    }
+}
@@ -0,0 +1,606 @@
+//! role based authorization.
+//!
+//! every route declares who may call it by taking one of the `Require*`
+//! extractors. the rules behind them live in [`Policy`], and [`ROUTE_POLICIES`]
+//! maps each documented route to its policy so the api docs can list the
+//! required role per path. a test sends every route a request without a token
+//! and one from each kind of user, and checks that exactly the ones its policy
+//! refuses are turned away, with 401 and 403 respectively.
+
+use std::marker::PhantomData;
+
+use ams_logic::prelude::*;
+use axum::{
+    async_trait,
+    extract::{FromRef, FromRequestParts, Path},
+    http::{request::Parts, Method},
+    RequestPartsExt,
+};
+use utoipa::{
+    openapi::{security::SecurityRequirement, PathItemType},
+    Modify,
+};
+use uuid::Uuid;
+
+use crate::{
+    app::{self, DynAttendancesRepo, DynClassSessionsRepo, DynSubjectsRepo},
+    auth::{AuthError, Claims, User},
+    error::ApiError,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Policy {
+    /// anyone, no token required.
+    Public,
+    /// any logged in user.
+    Authenticated,
+    Admin,
+    /// admins and instructors.
+    Staff,
+    /// only attendees, admins are rejected as well.
+    Attendee,
+    /// only instructors, admins are rejected as well.
+    Instructor,
+    /// admins or the attendee/instructor the path refers to.
+    SelfOrAdmin,
+    /// admins or the instructor of the subject the path refers to.
+    InstructorOf,
+}
+
+impl Policy {
+    /// the roles accepted by this policy, listed as scopes in the api docs.
+    pub fn roles(self) -> &'static [&'static str] {
+        match self {
+            Policy::Public => &[],
+            Policy::Authenticated => &["admin", "instructor", "attendee"],
+            Policy::Admin => &["admin"],
+            Policy::Staff => &["admin", "instructor"],
+            Policy::Attendee => &["attendee"],
+            Policy::Instructor => &["instructor"],
+            Policy::SelfOrAdmin => &["admin", "self"],
+            Policy::InstructorOf => &["admin", "subject instructor"],
+        }
+    }
+
+    /// checks whether `user` may pass, `owner` is the user the route refers to if any.
+    pub fn allows(self, user: &User, owner: Option<&User>) -> bool {
+        match self {
+            Policy::Public | Policy::Authenticated => true,
+            Policy::Admin => matches!(user, User::Admin(_)),
+            Policy::Staff => matches!(user, User::Admin(_) | User::Instructor(_)),
+            Policy::Attendee => matches!(user, User::Attendee(_)),
+            Policy::Instructor => matches!(user, User::Instructor(_)),
+            Policy::SelfOrAdmin | Policy::InstructorOf => {
+                matches!(user, User::Admin(_)) || owner == Some(user)
+            }
+        }
+    }
+
+    fn authorize(self, user: &User, owner: Option<&User>) -> Result<(), ApiError> {
+        if self.allows(user, owner) {
+            Ok(())
+        } else {
+            Err(AuthError::UnauthorizedAccess.into())
+        }
+    }
+}
+
+use PathItemType::{Delete, Get, Patch, Post, Put};
+
+/// the policy of every documented route.
+#[rustfmt::skip]
+pub static ROUTE_POLICIES: &[(PathItemType, &str, Policy)] = &[
+    (Post, "/auth/refresh", Policy::Public),
+    (Post, "/auth/logout", Policy::Authenticated),
+    (Post, "/admins/login", Policy::Public),
+    (Get, "/admins", Policy::Admin),
+    (Post, "/admins", Policy::Admin),
+    (Put, "/admins/me/password", Policy::Admin),
+    (Get, "/admins/{admin_id}", Policy::Admin),
+    (Patch, "/admins/{admin_id}", Policy::Admin),
+    (Delete, "/admins/{admin_id}", Policy::Admin),
+    (Get, "/config/face_recognition", Policy::Staff),
+    (Put, "/config/face_recognition", Policy::Admin),
+    (Get, "/config/settings", Policy::Admin),
+    (Put, "/config/settings", Policy::Admin),
+    (Get, "/config/classifier", Policy::Admin),
+    (Post, "/config/classifier", Policy::Admin),
+    (Post, "/config/classifier/rollback", Policy::Admin),
+    (Get, "/config/classifiers", Policy::Admin),
+    (Post, "/config/classifiers/{classifier_id}/activate", Policy::Admin),
+    (Get, "/config/matching", Policy::Staff),
+    (Put, "/config/matching", Policy::Admin),
+    (Get, "/config/matching/subjects/{subject_id}", Policy::Staff),
+    (Put, "/config/matching/subjects/{subject_id}", Policy::Admin),
+    (Delete, "/config/matching/subjects/{subject_id}", Policy::Admin),
+    (Post, "/instructors/login", Policy::Public),
+    (Get, "/instructors/login", Policy::Instructor),
+    (Get, "/instructors", Policy::Admin),
+    (Post, "/instructors", Policy::Admin),
+    (Get, "/instructors/{instructor_id}", Policy::SelfOrAdmin),
+    (Patch, "/instructors/{instructor_id}", Policy::Admin),
+    (Delete, "/instructors/{instructor_id}", Policy::Admin),
+    (Post, "/instructors/{instructor_id}/image", Policy::Admin),
+    (Get, "/instructors/{instructor_id}/subjects", Policy::SelfOrAdmin),
+    (Get, "/instructors/{instructor_id}/subjects/{subject_id}", Policy::SelfOrAdmin),
+    (Put, "/instructors/{instructor_id}/subjects/{subject_id}", Policy::Admin),
+    (Delete, "/instructors/{instructor_id}/subjects/{subject_id}", Policy::Admin),
+    (Post, "/attendees/login", Policy::Public),
+    (Get, "/attendees/login", Policy::Attendee),
+    (Get, "/attendees", Policy::Admin),
+    (Post, "/attendees", Policy::Admin),
+    (Post, "/attendees/image", Policy::Staff),
+    (Get, "/attendees/{attendee_id}", Policy::SelfOrAdmin),
+    (Patch, "/attendees/{attendee_id}", Policy::Admin),
+    (Delete, "/attendees/{attendee_id}", Policy::Admin),
+    (Post, "/attendees/{attendee_id}/image", Policy::Admin),
+    (Get, "/attendees/{attendee_id}/subjects", Policy::SelfOrAdmin),
+    (Get, "/attendees/{attendee_id}/subjects/{subject_id}", Policy::SelfOrAdmin),
+    (Put, "/attendees/{attendee_id}/subjects/{subject_id}", Policy::Admin),
+    (Delete, "/attendees/{attendee_id}/subjects/{subject_id}", Policy::Admin),
+    (Get, "/attendees/{attendee_id}/subjects/{subject_id}/attendances", Policy::SelfOrAdmin),
+    (Get, "/attendees/{attendee_id}/face_samples", Policy::SelfOrAdmin),
+    (Post, "/attendees/{attendee_id}/face_samples", Policy::Admin),
+    (Delete, "/attendees/{attendee_id}/face_samples/{sample_id}", Policy::Admin),
+    (Post, "/attendees/{attendee_id}/face_samples/{sample_id}/embed", Policy::Admin),
+    (Get, "/subjects", Policy::Admin),
+    (Post, "/subjects", Policy::Admin),
+    (Get, "/subjects/{subject_id}", Policy::Authenticated),
+    (Patch, "/subjects/{subject_id}", Policy::Admin),
+    (Delete, "/subjects/{subject_id}", Policy::Admin),
+    (Get, "/subjects/{subject_id}/attendees", Policy::InstructorOf),
+    (Put, "/subjects/{subject_id}/attendees", Policy::Admin),
+    (Post, "/subjects/{subject_id}/attendees/copy", Policy::Admin),
+    (Post, "/subjects/{subject_id}/subject_dates", Policy::Admin),
+    (Delete, "/subjects/{subject_id}/subject_dates/{subject_date_id}", Policy::Admin),
+    (Get, "/attendances/subjects/{subject_id}", Policy::InstructorOf),
+    (Post, "/attendances/subjects/{subject_id}", Policy::InstructorOf),
+    (Post, "/attendances/subjects/{subject_id}/photo", Policy::InstructorOf),
+    (Put, "/attendances/subjects/{subject_id}/attendees/{attendee_id}", Policy::InstructorOf),
+    (Delete, "/attendances/{attendance_id}", Policy::InstructorOf),
+    (Patch, "/attendances/{attendance_id}", Policy::InstructorOf),
+    (Put, "/class_sessions/{class_session_id}/attendances/{attendee_id}", Policy::InstructorOf),
+    (Get, "/subjects/{subject_id}/attendance_audit", Policy::InstructorOf),
+    (Get, "/subjects/{subject_id}/class_sessions", Policy::Authenticated),
+    (Post, "/subjects/{subject_id}/class_sessions", Policy::Admin),
+    (Get, "/class_sessions/{class_session_id}", Policy::Authenticated),
+    (Post, "/class_sessions/{class_session_id}/cancel", Policy::InstructorOf),
+    (Post, "/class_sessions/{class_session_id}/reschedule", Policy::InstructorOf),
+    (Get, "/terms", Policy::Authenticated),
+    (Post, "/terms", Policy::Admin),
+    (Get, "/terms/{term_id}", Policy::Authenticated),
+    (Patch, "/terms/{term_id}", Policy::Admin),
+    (Delete, "/terms/{term_id}", Policy::Admin),
+    (Post, "/terms/{term_id}/holidays", Policy::Admin),
+    (Delete, "/terms/{term_id}/holidays/{holiday_id}", Policy::Admin),
+    (Get, "/subjects/{subject_id}/report", Policy::InstructorOf),
+    (Post, "/imports/{kind}", Policy::Admin),
+    (Get, "/admin/audit", Policy::Admin),
+];
+
+/// the documented route of a request and its policy, literal segments win
+/// over parameters so `/admins/me/password` isn't taken for an admin id.
+pub fn route_policy(method: &Method, path: &str) -> Option<(&'static str, Policy)> {
+    let method = match *method {
+        Method::GET => Get,
+        Method::POST => Post,
+        Method::PUT => Put,
+        Method::PATCH => Patch,
+        Method::DELETE => Delete,
+        _ => return None,
+    };
+
+    ROUTE_POLICIES
+        .iter()
+        .filter(|(route_method, route, _)| *route_method == method && route_matches(route, path))
+        .max_by_key(|(_, route, _)| {
+            route
+                .split('/')
+                .filter(|segment| !segment.starts_with('{'))
+                .count()
+        })
+        .map(|(_, route, policy)| (*route, *policy))
+}
+
+/// whether `path` is an instance of `route`.
+fn route_matches(route: &str, path: &str) -> bool {
+    let route = route.trim_end_matches('/').split('/');
+    let path = path.trim_end_matches('/').split('/');
+
+    route.clone().count() == path.clone().count()
+        && route
+            .zip(path)
+            .all(|(expected, segment)| expected.starts_with('{') || expected == segment)
+}
+
+/// lists the required roles of every route in the api docs.
+pub struct PolicyAddon;
+
+impl Modify for PolicyAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        for (method, path, policy) in ROUTE_POLICIES {
+            let Some(operation) = openapi
+                .paths
+                .paths
+                .get_mut(*path)
+                .and_then(|item| item.operations.get_mut(method))
+            else {
+                tracing::warn!("policy for undocumented route `{path}`");
+                continue;
+            };
+
+            if *policy == Policy::Public {
+                operation.security = None;
+                continue;
+            }
+
+            let roles = policy.roles();
+            operation.security = Some(vec![SecurityRequirement::new(
+                "api_jwt_token",
+                roles.iter().copied(),
+            )]);
+
+            let required = format!("required role: {}", roles.join(" | "));
+            operation.description = Some(match operation.description.take() {
+                Some(description) => format!("{description}\n\n{required}"),
+                None => required,
+            });
+        }
+    }
+}
+
+/// the first uuid in the request path.
+async fn path_id(parts: &mut Parts) -> Result<Uuid, ApiError> {
+    let Path(params) = parts
+        .extract::<Path<Vec<(String, String)>>>()
+        .await
+        .map_err(|_| ApiError::BadRequest)?;
+
+    params
+        .first()
+        .and_then(|(_, value)| Uuid::parse_str(value).ok())
+        .ok_or(ApiError::BadRequest)
+}
+
+macro_rules! role_guard {
+    ($(#[$meta:meta])* $name:ident, $policy:expr, |$user:ident| $value:expr => $ty:ty) => {
+        $(#[$meta])*
+        #[allow(dead_code)]
+        pub struct $name(pub $ty);
+
+        #[async_trait]
+        impl FromRequestParts<app::State> for $name {
+            type Rejection = ApiError;
+
+            async fn from_request_parts(
+                parts: &mut Parts,
+                state: &app::State,
+            ) -> Result<Self, Self::Rejection> {
+                let Claims { user: $user, .. } = Claims::from_request_parts(parts, state).await?;
+                $policy.authorize(&$user, None)?;
+                Ok(Self($value))
+            }
+        }
+    };
+}
+
+role_guard!(
+    /// passes admins only, holds the admin id.
+    RequireAdmin, Policy::Admin, |user| user.id() => Uuid
+);
+role_guard!(
+    /// passes admins and instructors.
+    RequireStaff, Policy::Staff, |user| user => User
+);
+role_guard!(
+    /// passes attendees only, holds the attendee id.
+    RequireAttendee, Policy::Attendee, |user| user.id() => Uuid
+);
+role_guard!(
+    /// passes instructors only, holds the instructor id.
+    RequireInstructor, Policy::Instructor, |user| user.id() => Uuid
+);
+
+/// a resource that is a user itself.
+pub trait UserResource {
+    fn user(id: Uuid) -> User;
+}
+
+impl UserResource for Attendee {
+    fn user(id: Uuid) -> User {
+        User::Attendee(id)
+    }
+}
+
+impl UserResource for Instructor {
+    fn user(id: Uuid) -> User {
+        User::Instructor(id)
+    }
+}
+
+/// passes admins and the user the first path parameter refers to.
+#[allow(dead_code)]
+pub struct RequireSelfOrAdmin<R>(pub User, PhantomData<R>);
+
+#[async_trait]
+impl<R> FromRequestParts<app::State> for RequireSelfOrAdmin<R>
+where
+    R: UserResource,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &app::State,
+    ) -> Result<Self, Self::Rejection> {
+        let Claims { user, .. } = Claims::from_request_parts(parts, state).await?;
+        let owner = R::user(path_id(parts).await?);
+
+        Policy::SelfOrAdmin.authorize(&user, Some(&owner))?;
+
+        Ok(Self(user, PhantomData))
+    }
+}
+
+/// a resource that belongs to the instructor of a subject.
+#[async_trait]
+pub trait InstructedResource {
+    async fn instructor_id(state: &app::State, id: Uuid) -> Result<Option<Uuid>, ApiError>;
+}
+
+#[async_trait]
+impl InstructedResource for Subject {
+    async fn instructor_id(state: &app::State, id: Uuid) -> Result<Option<Uuid>, ApiError> {
+        let subjects_repo = DynSubjectsRepo::from_ref(state);
+        let subject = subjects_repo.get_by_id(id).await?;
+        Ok(subject.instructor.map(|instructor| instructor.id))
+    }
+}
+
+#[async_trait]
+impl InstructedResource for Attendance {
+    async fn instructor_id(state: &app::State, id: Uuid) -> Result<Option<Uuid>, ApiError> {
+        let attendances_repo = DynAttendancesRepo::from_ref(state);
+        let attendance = attendances_repo.get_by_id(id).await?;
+        Ok(attendance
+            .subject
+            .instructor
+            .map(|instructor| instructor.id))
+    }
+}
+
+#[async_trait]
+impl InstructedResource for ClassSession {
+    async fn instructor_id(state: &app::State, id: Uuid) -> Result<Option<Uuid>, ApiError> {
+        let class_sessions_repo = DynClassSessionsRepo::from_ref(state);
+        let session = class_sessions_repo.get_by_id(id).await?;
+        Subject::instructor_id(state, session.subject_id).await
+    }
+}
+
+/// passes admins and the instructor of the resource the first path parameter refers to.
+pub struct RequireInstructorOf<R>(pub User, pub PhantomData<R>);
+
+#[async_trait]
+impl<R> FromRequestParts<app::State> for RequireInstructorOf<R>
+where
+    R: InstructedResource,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &app::State,
+    ) -> Result<Self, Self::Rejection> {
+        let Claims { user, .. } = Claims::from_request_parts(parts, state).await?;
+
+        // admins don't need the lookup and attendees never instruct.
+        match user {
+            User::Admin(_) => return Ok(Self(user, PhantomData)),
+            User::Attendee(_) => return Err(AuthError::UnauthorizedAccess.into()),
+            User::Instructor(_) => {}
+        }
+
+        let owner = R::instructor_id(state, path_id(parts).await?)
+            .await?
+            .map(User::Instructor);
+
+        Policy::InstructorOf.authorize(&user, owner.as_ref())?;
+
+        Ok(Self(user, PhantomData))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Once;
+
+    use ams_logic::entity::{sea_orm_active_enums::UserKind, sessions, settings};
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use chrono::{Duration, Utc};
+    use jsonwebtoken::{encode, Header};
+    use sea_orm::{DatabaseBackend, MockDatabase};
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::{
+        app::config::{self, AppConfig},
+        auth::KEYS,
+        routes,
+    };
+
+    /// the api with a database that only knows the sessions of the tokens, so
+    /// a request that passes its policy fails in its handler instead.
+    async fn api() -> axum::Router {
+        static CONFIG: Once = Once::new();
+        CONFIG.call_once(|| {
+            let mut app_config = AppConfig::default();
+            app_config.auth.jwt_secret = "secret".to_owned();
+            app_config.face_recognition.url = "http://localhost".to_owned();
+            config::init(app_config);
+        });
+
+        let now = Utc::now();
+        let session = sessions::Model {
+            id: Uuid::new_v4(),
+            user_kind: UserKind::Admin,
+            user_id: Uuid::new_v4(),
+            refresh_token_hash: String::new(),
+            expires_at: (now + Duration::hours(1)).into(),
+            revoked_at: None,
+            create_at: now.into(),
+            updated_at: now.into(),
+        };
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![Vec::<settings::Model>::new()])
+            .append_query_results(vec![vec![session]; 16])
+            .into_connection();
+
+        let state = app::State::new(db, "assets").await.unwrap();
+        routes::api().with_state(state)
+    }
+
+    fn token(user: User) -> String {
+        let claims = Claims {
+            user,
+            sid: Uuid::new_v4(),
+            exp: (Utc::now() + Duration::hours(1)).timestamp() as usize,
+        };
+        encode(&Header::default(), &claims, &KEYS.encoding).unwrap()
+    }
+
+    /// `route` with its first parameter set to `id`.
+    fn path(route: &str, id: Uuid) -> String {
+        let mut first = true;
+        route
+            .split('/')
+            .map(|segment| match segment {
+                "{kind}" => "attendees".to_owned(),
+                _ if segment.starts_with('{') => {
+                    let id = if first { id } else { Uuid::new_v4() };
+                    first = false;
+                    id.to_string()
+                }
+                _ => segment.to_owned(),
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    fn method(method: &PathItemType) -> Method {
+        match method {
+            Get => Method::GET,
+            Post => Method::POST,
+            Put => Method::PUT,
+            Patch => Method::PATCH,
+            Delete => Method::DELETE,
+            _ => unreachable!("no route uses {}", method_name(method)),
+        }
+    }
+
+    fn method_name(method: &PathItemType) -> &'static str {
+        match method {
+            Get => "GET",
+            Post => "POST",
+            Put => "PUT",
+            Patch => "PATCH",
+            Delete => "DELETE",
+            _ => "another method",
+        }
+    }
+
+    /// the user the path of a `SelfOrAdmin` route refers to.
+    fn owner(route: &str, id: Uuid) -> User {
+        if route.starts_with("/attendees/") {
+            User::Attendee(id)
+        } else if route.starts_with("/instructors/") {
+            User::Instructor(id)
+        } else {
+            panic!("`{route}` doesn't refer to an attendee or an instructor")
+        }
+    }
+
+    /// the status of the response and the message of its error if any.
+    async fn send(
+        method: &PathItemType,
+        path: &str,
+        user: Option<User>,
+    ) -> (StatusCode, Option<String>) {
+        let mut request = Request::builder().method(self::method(method)).uri(path);
+        if let Some(user) = user {
+            request = request.header("authorization", format!("Bearer {}", token(user)));
+        }
+
+        let response = api()
+            .await
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let message = serde_json::from_slice::<serde_json::Value>(&body)
+            .ok()
+            .and_then(|body| body["error"]["message"].as_str().map(str::to_owned));
+
+        (status, message)
+    }
+
+    #[tokio::test]
+    async fn routes_enforce_their_policies() {
+        let (admin, instructor, attendee) = (
+            User::Admin(Uuid::new_v4()),
+            User::Instructor(Uuid::new_v4()),
+            User::Attendee(Uuid::new_v4()),
+        );
+
+        for (method, route, policy) in ROUTE_POLICIES {
+            let route_name = format!("{} `{route}`", method_name(method));
+
+            let (status, message) = send(method, &path(route, Uuid::new_v4()), None).await;
+            if *policy == Policy::Public {
+                // the handler may still turn down the credentials in the body.
+                assert!(
+                    status != StatusCode::FORBIDDEN
+                        && message != Some(AuthError::InvalidToken.to_string()),
+                    "{route_name} is public but asked an anonymous request for a token",
+                );
+                continue;
+            }
+            assert_eq!(
+                status,
+                StatusCode::UNAUTHORIZED,
+                "{route_name} answered an anonymous request with {status}",
+            );
+
+            for user in [admin, instructor, attendee] {
+                // whether an instructor teaches the subject needs the database.
+                if *policy == Policy::InstructorOf && matches!(user, User::Instructor(_)) {
+                    continue;
+                }
+
+                // the path refers to the user itself, then to someone else.
+                for id in [user.id(), Uuid::new_v4()] {
+                    let owner = (*policy == Policy::SelfOrAdmin).then(|| owner(route, id));
+                    let allowed = policy.allows(&user, owner.as_ref());
+
+                    let (status, _) = send(method, &path(route, id), Some(user)).await;
+                    if allowed {
+                        assert!(
+                            status != StatusCode::UNAUTHORIZED && status != StatusCode::FORBIDDEN,
+                            "{route_name} declares {policy:?} but refused {user:?} with {status}",
+                        );
+                    } else {
+                        assert_eq!(
+                            status,
+                            StatusCode::FORBIDDEN,
+                            "{route_name} declares {policy:?} but answered {user:?} with {status}",
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
@@ -6,8 +6,13 @@ use utoipa::ToSchema;
 
 use ams_logic::prelude::*;
 
-use crate::auth::AuthBody;
+use crate::{
+    auth::AuthBody,
+    routes::{attendances::PhotoAttendance, attendees::AttendeeMatch},
+};
 
+#[derive(Debug, ToSchema, Serialize)]
+pub struct AdminsList(#[schema(inline)] Vec<Admin>);
 #[derive(Debug, ToSchema, Serialize)]
 pub struct AttendeesList(#[schema(inline)] Vec<Instructor>);
 #[derive(Debug, ToSchema, Serialize)]
@@ -18,12 +23,27 @@ pub struct SubjectsList(#[schema(inline)] Vec<Subject>);
 pub struct SubjectDatesList(#[schema(inline)] Vec<SubjectDate>);
 #[derive(Debug, ToSchema, Serialize)]
 pub struct AttendancesList(#[schema(inline)] Vec<Attendance>);
+#[derive(Debug, ToSchema, Serialize)]
+pub struct AttendanceAuditList(#[schema(inline)] Vec<AttendanceAuditEntry>);
+#[derive(Debug, ToSchema, Serialize)]
+pub struct AuditEntriesList(#[schema(inline)] Vec<AuditEntry>);
+#[derive(Debug, ToSchema, Serialize)]
+pub struct ClassSessionsList(#[schema(inline)] Vec<ClassSession>);
+#[derive(Debug, ToSchema, Serialize)]
+pub struct TermsList(#[schema(inline)] Vec<Term>);
+#[derive(Debug, ToSchema, Serialize)]
+pub struct FaceSamplesList(#[schema(inline)] Vec<FaceSample>);
+#[derive(Debug, ToSchema, Serialize)]
+pub struct AttendeeMatchesList(#[schema(inline)] Vec<AttendeeMatch>);
+#[derive(Debug, ToSchema, Serialize)]
+pub struct ClassifierModelsList(#[schema(inline)] Vec<ClassifierModel>);
 
 #[derive(Debug, ToSchema, Serialize, Deserialize)]
-#[serde(tag = "status", rename = "success")]
+#[serde(tag = "status", rename = "success", rename_all = "camelCase")]
 #[aliases(
     AuthResponse = AppResponse<'a, AuthBody>,
     AdminResponse = AppResponse<'a, Admin>,
+    AdminsListResponse = AppResponse<'a, AdminsList>,
     InstructorResponse = AppResponse<'a, Instructor>,
     InstructorsListResponse = AppResponse<'a, InstructorsList>,
     AttendeeResponse = AppResponse<'a, Attendee>,
@@ -32,14 +52,39 @@ pub struct AttendancesList(#[schema(inline)] Vec<Attendance>);
     SubjectsListResponse = AppResponse<'a, SubjectsList>,
     SubjectDateResponse = AppResponse<'a, SubjectDate>,
     SubjectDatesListResponse = AppResponse<'a, SubjectDatesList>,
+    RosterDiffResponse = AppResponse<'a, RosterDiff>,
     AttendanceResponse = AppResponse<'a, Attendance>,
-    AttendancesListResponse = AppResponse<'a, AttendancesList>
+    AttendancesListResponse = AppResponse<'a, AttendancesList>,
+    AttendanceAuditListResponse = AppResponse<'a, AttendanceAuditList>,
+    AuditEntriesListResponse = AppResponse<'a, AuditEntriesList>,
+    ClassSessionResponse = AppResponse<'a, ClassSession>,
+    ClassSessionsListResponse = AppResponse<'a, ClassSessionsList>,
+    TermResponse = AppResponse<'a, Term>,
+    TermsListResponse = AppResponse<'a, TermsList>,
+    TermHolidayResponse = AppResponse<'a, TermHoliday>,
+    SubjectReportResponse = AppResponse<'a, SubjectReport>,
+    ImportReportResponse = AppResponse<'a, ImportReport>,
+    FaceSampleResponse = AppResponse<'a, FaceSample>,
+    FaceSamplesListResponse = AppResponse<'a, FaceSamplesList>,
+    AttendeeMatchesListResponse = AppResponse<'a, AttendeeMatchesList>,
+    MatchSettingsResponse = AppResponse<'a, MatchSettings>,
+    PhotoAttendanceResponse = AppResponse<'a, PhotoAttendance>,
+    ClassifierModelResponse = AppResponse<'a, ClassifierModel>,
+    FaceRecModeResponse = AppResponse<'a, FaceRecMode>,
+    SettingsResponse = AppResponse<'a, Settings>,
+    ClassifierModelsListResponse = AppResponse<'a, ClassifierModelsList>
 )]
 pub struct AppResponse<'a, Data> {
     #[serde(skip)]
     pub code: StatusCode,
     pub message: Cow<'a, str>,
     pub data: Option<Data>,
+    /// how many items the list has across all pages.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub total: Option<u64>,
+    /// the `cursor` of the next page of the list.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub next_cursor: Option<String>,
 }
 
 pub trait AppResponseMsgExt<'a>
@@ -51,6 +96,8 @@ where
             code: StatusCode::OK,
             message: self.into(),
             data: None,
+            total: None,
+            next_cursor: None,
         }
     }
 }
@@ -67,6 +114,8 @@ where
             code: StatusCode::OK,
             message: value.into(),
             data: None,
+            total: None,
+            next_cursor: None,
         }
     }
 }
@@ -81,6 +130,8 @@ where
             code: StatusCode::CREATED,
             message: message.into(),
             data: Some(self),
+            total: None,
+            next_cursor: None,
         }
     }
     fn ok_response(self, message: Msg) -> AppResponse<'a, Self> {
@@ -88,6 +139,8 @@ where
             code: StatusCode::OK,
             message: message.into(),
             data: Some(self),
+            total: None,
+            next_cursor: None,
         }
     }
 }
@@ -99,6 +152,31 @@ where
 {
 }
 
+/// lists one page, along with the total and the cursor of the next page.
+pub trait AppResponsePageExt<'a, T, Msg>
+where
+    T: Serialize,
+    Msg: Into<Cow<'a, str>>,
+{
+    fn ok_response(self, message: Msg) -> AppResponse<'a, Vec<T>>;
+}
+
+impl<'a, T, Msg> AppResponsePageExt<'a, T, Msg> for Page<T>
+where
+    T: Serialize,
+    Msg: Into<Cow<'a, str>>,
+{
+    fn ok_response(self, message: Msg) -> AppResponse<'a, Vec<T>> {
+        AppResponse {
+            code: StatusCode::OK,
+            message: message.into(),
+            data: Some(self.items),
+            total: Some(self.total),
+            next_cursor: self.next_cursor,
+        }
+    }
+}
+
 impl<'a, Data> IntoResponse for AppResponse<'a, Data>
 where
     Data: Serialize,
@@ -1,16 +1,175 @@
-use axum::{extract::State, routing::post, Json, Router};
-use jsonwebtoken::{encode, Header};
+use axum::{
+    extract::{Path, State},
+    routing::{get, post, put},
+    Json, Router,
+};
+use uuid::Uuid;
+
+use ams_logic::prelude::*;
 
 use crate::{
-    app::{self, DynAdminsRepo},
-    auth::{AuthBody, AuthError, AuthPayload, Claims, User, KEYS},
+    app::{self, DynAdminsRepo, DynSessionsRepo},
+    auth::{AuthBody, AuthError, AuthPayload, User},
     error::ApiError,
-    response::AppResponse,
-    response::AppResponseDataExt,
+    pagination::Paginated,
+    policy::RequireAdmin,
+    response::{AppResponse, AppResponseDataExt, AppResponseMsgExt, AppResponsePageExt},
 };
 
 pub(crate) fn routes() -> Router<app::State> {
-    Router::new().route("/admins/login", post(login))
+    Router::new()
+        .route("/admins", get(get_all).post(create_one))
+        .route("/admins/me/password", put(change_password))
+        .route(
+            "/admins/:id",
+            get(get_one).patch(update_one).delete(delete_one),
+        )
+        .route("/admins/login", post(login))
+}
+
+#[utoipa::path(
+    get,
+    path = "/admins",
+    params(ListQuery),
+    responses(
+        (status = OK, body = AdminsListResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+async fn get_all(
+    State(repo): State<DynAdminsRepo>,
+    _: RequireAdmin,
+    Paginated(query): Paginated,
+) -> Result<AppResponse<'static, Vec<Admin>>, ApiError> {
+    let admins = repo.get_all(query).await?;
+    let response = admins.ok_response("retreived all admins successfully");
+
+    Ok(response)
+}
+
+#[utoipa::path(
+    post,
+    path = "/admins",
+    request_body = CreateAdmin,
+    responses(
+        (status = CREATED, body = AdminResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+async fn create_one(
+    State(repo): State<DynAdminsRepo>,
+    _: RequireAdmin,
+    Json(admin): Json<CreateAdmin>,
+) -> Result<AppResponse<'static, Admin>, ApiError> {
+    let admin = repo.create(admin).await?;
+    let response = admin.create_response("created one admin successfully");
+
+    Ok(response)
+}
+
+#[utoipa::path(
+    get,
+    path = "/admins/{admin_id}",
+    responses(
+        (status = OK, body = AdminResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+async fn get_one(
+    State(repo): State<DynAdminsRepo>,
+    Path(admin_id): Path<Uuid>,
+    _: RequireAdmin,
+) -> Result<AppResponse<'static, Admin>, ApiError> {
+    let admin = repo.get_by_id(admin_id).await?;
+    let response = admin.ok_response("retreived an admin successfully");
+
+    Ok(response)
+}
+
+/// setting the password signs the admin out everywhere.
+#[utoipa::path(
+    patch,
+    path = "/admins/{admin_id}",
+    request_body = UpdateAdmin,
+    responses(
+        (status = OK, body = AdminResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+async fn update_one(
+    State(repo): State<DynAdminsRepo>,
+    State(sessions_repo): State<DynSessionsRepo>,
+    Path(admin_id): Path<Uuid>,
+    _: RequireAdmin,
+    Json(update_admin): Json<UpdateAdmin>,
+) -> Result<AppResponse<'static, Admin>, ApiError> {
+    let password_changed = update_admin.password.is_some();
+
+    let admin = repo.update(admin_id, update_admin).await?;
+    if password_changed {
+        sessions_repo.revoke_all(UserKind::Admin, admin.id).await?;
+    }
+    let response = admin.ok_response("updated the admin successfully");
+
+    Ok(response)
+}
+
+/// the last admin can't be deleted.
+#[utoipa::path(
+    delete,
+    path = "/admins/{admin_id}",
+    responses(
+        (status = OK)
+    ),
+    security(("api_jwt_token" = []))
+)]
+async fn delete_one(
+    State(repo): State<DynAdminsRepo>,
+    State(sessions_repo): State<DynSessionsRepo>,
+    Path(admin_id): Path<Uuid>,
+    _: RequireAdmin,
+) -> Result<AppResponse<'static, ()>, ApiError> {
+    repo.delete_by_id(admin_id).await?;
+    sessions_repo.revoke_all(UserKind::Admin, admin_id).await?;
+    let response = "deleted one admin successfully".response();
+
+    Ok(response)
+}
+
+/// changes the password of the logged in admin, revoking all of its sessions
+/// and starting a new one.
+#[utoipa::path(
+    put,
+    path = "/admins/me/password",
+    request_body = ChangePassword,
+    responses(
+        (status = OK, body = AuthResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+async fn change_password(
+    State(repo): State<DynAdminsRepo>,
+    State(sessions_repo): State<DynSessionsRepo>,
+    RequireAdmin(admin_id): RequireAdmin,
+    Json(change): Json<ChangePassword>,
+) -> Result<AppResponse<'static, AuthBody>, ApiError> {
+    if change.new_password.is_empty() {
+        return Err(ApiError::Validation {
+            message: "the new password can't be empty".to_owned(),
+        });
+    }
+
+    let admin = repo.get_by_id(admin_id).await?;
+    repo.get_by_creds(admin.email, change.current_password)
+        .await?;
+    repo.update_password(admin_id, change.new_password).await?;
+
+    sessions_repo.revoke_all(UserKind::Admin, admin_id).await?;
+    let auth_body = AuthBody::issue(&sessions_repo, User::Admin(admin_id)).await?;
+
+    let response = auth_body.ok_response("changed the password successfully");
+
+    Ok(response)
 }
 
 #[utoipa::path(
@@ -23,26 +182,17 @@ pub(crate) fn routes() -> Router<app::State> {
 )]
 async fn login(
     State(repo): State<DynAdminsRepo>,
+    State(sessions_repo): State<DynSessionsRepo>,
     payload: Option<Json<AuthPayload>>,
 ) -> Result<AppResponse<'static, AuthBody>, ApiError> {
     let Some(Json(payload)) = payload else {
         return Err(AuthError::MissingCredentials.into());
     };
 
-    let admin = repo.get_by_email(payload.email).await?;
-
-    if payload.password != admin.password {
-        return Err(AuthError::WrongCredentials.into());
-    }
-
-    let claims = Claims {
-        exp: usize::max_value(),
-        user: User::Admin(admin.id),
-    };
+    let admin = repo.get_by_creds(payload.email, payload.password).await?;
 
-    let token = encode(&Header::default(), &claims, &KEYS.encoding).unwrap();
+    let auth_body = AuthBody::issue(&sessions_repo, User::Admin(admin.id)).await?;
 
-    let response = AuthBody { token }.create_response("logged in as admin successfully");
+    let response = auth_body.create_response("logged in as admin successfully");
     Ok(response)
 }
-
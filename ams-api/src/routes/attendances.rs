@@ -1,17 +1,26 @@
+use std::collections::HashSet;
+
 use axum::{
-    extract::{Path, State},
-    routing::{delete, get, put},
+    extract::{Multipart, Path, Query, State},
+    routing::{delete, get, post, put},
     Json, Router,
 };
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
+use ams_facerec::{BoundingBox, Embedding, FaceRecognitionError};
 use ams_logic::prelude::*;
 
 use crate::{
-    app::{self, DynAttendancesRepo, DynSubjectsRepo},
-    auth::{AuthError, Claims, User},
+    app::{
+        self, DynAttendancesRepo, DynAttendeesRepo, DynFaceRecognizer, DynMatchSettingsRepo,
+        DynSettingsService, DynSubjectsRepo,
+    },
     error::ApiError,
-    response::{AppResponse, AppResponseDataExt, AppResponseMsgExt},
+    pagination::Paginated,
+    policy::RequireInstructorOf,
+    response::{AppResponse, AppResponseDataExt, AppResponseMsgExt, AppResponsePageExt},
 };
 
 pub(crate) fn routes() -> Router<app::State> {
@@ -20,13 +29,23 @@ pub(crate) fn routes() -> Router<app::State> {
             "/attendances/subjects/:id",
             get(get_all_for_one_subject).post(create_many),
         )
+        .route(
+            "/attendances/subjects/:id/photo",
+            post(create_many_with_photo),
+        )
         .route("/attendances/subjects/:id/attendees/:id", put(create_one))
-        .route("/attendances/:id", delete(delete_one))
+        .route("/attendances/:id", delete(delete_one).patch(correct_one))
+        .route("/class_sessions/:id/attendances/:id", put(mark_one))
+        .route(
+            "/subjects/:id/attendance_audit",
+            get(get_audit_for_one_subject),
+        )
 }
 
 #[utoipa::path(
     get,
     path = "/attendances/subjects/{subject_id}",
+    params(ListQuery),
     responses(
         (status = OK, body = AttendancesListResponse)
     ),
@@ -35,30 +54,18 @@ pub(crate) fn routes() -> Router<app::State> {
 #[tracing::instrument(level = "trace", skip_all, ret)]
 pub async fn get_all_for_one_subject(
     State(attendances_repo): State<DynAttendancesRepo>,
-    State(subjects_repo): State<DynSubjectsRepo>,
     Path(subject_id): Path<Uuid>,
-    claimes: Claims,
+    _: RequireInstructorOf<Subject>,
+    Paginated(query): Paginated,
 ) -> Result<AppResponse<'static, Vec<Attendance>>, ApiError> {
-    match claimes.user {
-        User::Admin(_) => {}
-        User::Instructor(id)
-            if Some(id) == {
-                subjects_repo
-                    .get_by_id(subject_id)
-                    .await?
-                    .instructor
-                    .map(|instructor| instructor.id)
-            } => {}
-        _ => {
-            return Err(AuthError::UnauthorizedAccess.into());
-        }
-    };
-
     let attendances = attendances_repo
-        .get(AttendancesFilter {
-            subject_id: Some(subject_id),
-            ..Default::default()
-        })
+        .get(
+            AttendancesFilter {
+                subject_id: Some(subject_id),
+                ..Default::default()
+            },
+            query,
+        )
         .await?;
 
     let response = attendances.ok_response("retreived all attendances for a subject successfully");
@@ -76,29 +83,17 @@ pub async fn get_all_for_one_subject(
 )]
 pub async fn create_one(
     State(attendances_repo): State<DynAttendancesRepo>,
-    State(subjects_repo): State<DynSubjectsRepo>,
     Path((subject_id, attendee_id)): Path<(Uuid, Uuid)>,
-    claimes: Claims,
+    RequireInstructorOf(user, _): RequireInstructorOf<Subject>,
 ) -> Result<AppResponse<'static, Attendance>, ApiError> {
-    match claimes.user {
-        User::Admin(_) => {}
-        User::Instructor(id)
-            if Some(id)
-                == subjects_repo
-                    .get_by_id(subject_id)
-                    .await?
-                    .instructor
-                    .map(|instructor| instructor.id) => {}
-        _ => {
-            return Err(AuthError::UnauthorizedAccess.into());
-        }
-    };
-
     let attendance = attendances_repo
-        .create_one(CreateAttendance {
-            subject_id,
-            attendee_id,
-        })
+        .create_one(
+            CreateAttendance {
+                subject_id,
+                attendee_id,
+            },
+            user.actor(),
+        )
         .await?;
 
     let respone = attendance.create_response("attendance was taken successfully");
@@ -106,59 +101,312 @@ pub async fn create_one(
     Ok(respone)
 }
 
+#[utoipa::path(
+    post,
+    path = "/attendances/subjects/{subject_id}",
+    request_body = CreateAttendances,
+    responses(
+        (status = CREATED, body = AttendancesListResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
 pub async fn create_many(
     State(attendances_repo): State<DynAttendancesRepo>,
-    State(subjects_repo): State<DynSubjectsRepo>,
     Path(subject_id): Path<Uuid>,
-    claimes: Claims,
+    RequireInstructorOf(user, _): RequireInstructorOf<Subject>,
     Json(CreateAttendances {
         attendee_ids: attendees,
     }): Json<CreateAttendances>,
 ) -> Result<AppResponse<'static, Vec<Attendance>>, ApiError> {
-    match claimes.user {
-        User::Admin(_) => {}
-        User::Instructor(id)
-            if Some(id)
-                == subjects_repo
-                    .get_by_id(subject_id)
-                    .await?
-                    .instructor
-                    .map(|instructor| instructor.id) => {}
-        _ => {
-            return Err(AuthError::UnauthorizedAccess.into());
-        }
-    };
-
-    let attendances = attendances_repo.create_many(subject_id, attendees).await?;
+    let attendances = attendances_repo
+        .create_many(subject_id, attendees, user.actor())
+        .await?;
 
     let respone = attendances.create_response("attendance was taken successfully");
 
     Ok(respone)
 }
 
+/// a face in the photo recognized as an enrolled attendee.
+#[derive(Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchedFace {
+    pub attendee: Attendee,
+    pub bounding_box: BoundingBox,
+    pub metric: MatchMetric,
+    /// the distance to the attendee's embedding, lower is nearer.
+    pub score: f64,
+    /// whether the attendance was already taken before the photo.
+    pub already_attended: bool,
+}
+
+/// a face in the photo that matched none of the enrolled attendees.
+#[derive(Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UnmatchedFace {
+    pub bounding_box: BoundingBox,
+    /// how sure the detector is that this is a face.
+    pub confidence: f64,
+}
+
+#[derive(Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PhotoAttendance {
+    /// the attendances taken from the photo.
+    pub attendances: Vec<Attendance>,
+    pub matched: Vec<MatchedFace>,
+    pub unmatched: Vec<UnmatchedFace>,
+    /// the enrolled attendees that were not found in the photo.
+    pub missing: Vec<Attendee>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/attendances/subjects/{subject_id}/photo",
+    request_body(content = Image, content_type = "multipart/form-data"),
+    responses(
+        (status = CREATED, body = PhotoAttendanceResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+#[allow(clippy::too_many_arguments)]
+pub async fn create_many_with_photo(
+    State(attendances_repo): State<DynAttendancesRepo>,
+    State(attendees_repo): State<DynAttendeesRepo>,
+    State(subjects_repo): State<DynSubjectsRepo>,
+    State(settings_repo): State<DynMatchSettingsRepo>,
+    State(settings_service): State<DynSettingsService>,
+    State(fr): State<DynFaceRecognizer>,
+    Path(subject_id): Path<Uuid>,
+    RequireInstructorOf(user, _): RequireInstructorOf<Subject>,
+    mut multipart: Multipart,
+) -> Result<AppResponse<'static, PhotoAttendance>, ApiError> {
+    if !fr.detects_faces() {
+        return Err(FaceRecognitionError::Unsupported("taking attendance from a photo").into());
+    }
+
+    let Some(field) = multipart.next_field().await.ok().flatten() else {
+        return Err(ApiError::BadRequest);
+    };
+    let image = field.bytes().await.map_err(|_| ApiError::Internal)?;
+
+    // fail before recognizing anything when no session is open
+    attendances_repo.current_session(subject_id).await?;
+    let settings = settings_repo.get_for_subject(subject_id).await?;
+    let current = settings_service.current();
+
+    let faces = fr.detect(&image).await?;
+
+    let mut candidates = Vec::new();
+    for (index, face) in faces.iter().enumerate() {
+        let nearest = attendees_repo
+            .find_nearest(FindNearest {
+                embedding: face.embedding.clone().normalized(),
+                k: current.max_matches,
+                metric: settings.metric,
+                threshold: settings.threshold,
+                subject_id: Some(subject_id),
+                strategy: current.match_strategy,
+            })
+            .await?;
+        candidates.extend(nearest.into_iter().map(|nearest| (index, nearest)));
+    }
+
+    // the nearest pairs claim their face and attendee first, so one attendee
+    // is never matched to two faces.
+    candidates.sort_by(|(_, first), (_, second)| first.score.total_cmp(&second.score));
+
+    let mut matched_faces = HashSet::new();
+    let mut matched_attendees = HashSet::new();
+    let mut matches = Vec::new();
+    for (index, nearest) in candidates {
+        if matched_faces.contains(&index) || matched_attendees.contains(&nearest.attendee.id) {
+            continue;
+        }
+        matched_faces.insert(index);
+        matched_attendees.insert(nearest.attendee.id);
+        matches.push((index, nearest));
+    }
+
+    let TakenAttendances {
+        created: attendances,
+        already_attended,
+    } = attendances_repo
+        .create_many_missing(
+            subject_id,
+            matches
+                .iter()
+                .map(|(_, nearest)| nearest.attendee.id)
+                .collect(),
+            user.actor(),
+        )
+        .await?;
+    let already_attended: HashSet<Uuid> = already_attended.into_iter().collect();
+
+    let missing = subjects_repo
+        .get_missing_attendees(subject_id, matched_attendees.into_iter().collect())
+        .await?;
+
+    let unmatched = faces
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| !matched_faces.contains(index))
+        .map(|(_, face)| UnmatchedFace {
+            bounding_box: face.bounding_box,
+            confidence: face.confidence,
+        })
+        .collect();
+
+    let matched = matches
+        .into_iter()
+        .map(
+            |(
+                index,
+                NearestAttendee {
+                    attendee,
+                    metric,
+                    score,
+                },
+            )| MatchedFace {
+                already_attended: already_attended.contains(&attendee.id),
+                attendee,
+                bounding_box: faces[index].bounding_box,
+                metric,
+                score,
+            },
+        )
+        .collect();
+
+    let response = PhotoAttendance {
+        attendances,
+        matched,
+        unmatched,
+        missing,
+    }
+    .create_response("attendance was taken from the photo successfully");
+
+    Ok(response)
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct DeleteAttendanceQuery {
+    /// kept in the audit trail, at most 256 characters.
+    reason: Option<String>,
+}
+
+#[utoipa::path(
+    delete,
+    path = "/attendances/{attendance_id}",
+    params(DeleteAttendanceQuery),
+    responses(
+        (status = OK)
+    ),
+    security(("api_jwt_token" = []))
+)]
 async fn delete_one(
     State(attendances_repo): State<DynAttendancesRepo>,
     Path(attendance_id): Path<Uuid>,
-    claimes: Claims,
+    RequireInstructorOf(user, _): RequireInstructorOf<Attendance>,
+    Query(DeleteAttendanceQuery { reason }): Query<DeleteAttendanceQuery>,
 ) -> Result<AppResponse<'static, ()>, ApiError> {
     let response = "deleted one attendee successfully".response();
 
-    match claimes.user {
-        User::Admin(_) => {}
-        User::Instructor(id)
-            if Some(id)
-                == attendances_repo
-                    .get_by_id(attendance_id)
-                    .await?
-                    .subject
-                    .instructor
-                    .map(|instuctor| instuctor.id) => {}
-        _ => {
-            return Err(AuthError::UnauthorizedAccess.into());
-        }
-    };
+    attendances_repo
+        .delete_by_id(attendance_id, user.actor(), reason)
+        .await?;
+
+    Ok(response)
+}
+
+/// corrects the status of a taken attendance.
+#[utoipa::path(
+    patch,
+    path = "/attendances/{attendance_id}",
+    request_body = CorrectAttendance,
+    responses(
+        (status = OK, body = AttendanceResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+pub async fn correct_one(
+    State(attendances_repo): State<DynAttendancesRepo>,
+    Path(attendance_id): Path<Uuid>,
+    RequireInstructorOf(user, _): RequireInstructorOf<Attendance>,
+    Json(correction): Json<CorrectAttendance>,
+) -> Result<AppResponse<'static, Attendance>, ApiError> {
+    let attendance = attendances_repo
+        .correct(attendance_id, correction, user.actor())
+        .await?;
+
+    let response = attendance.ok_response("corrected the attendance successfully");
+
+    Ok(response)
+}
+
+/// sets the status of an enrolled attendee in a class session, taking the
+/// attendance when it wasn't, outside of the session window too.
+#[utoipa::path(
+    put,
+    path = "/class_sessions/{class_session_id}/attendances/{attendee_id}",
+    request_body = CorrectAttendance,
+    responses(
+        (status = OK, body = AttendanceResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+pub async fn mark_one(
+    State(attendances_repo): State<DynAttendancesRepo>,
+    Path((class_session_id, attendee_id)): Path<(Uuid, Uuid)>,
+    RequireInstructorOf(user, _): RequireInstructorOf<ClassSession>,
+    Json(correction): Json<CorrectAttendance>,
+) -> Result<AppResponse<'static, Attendance>, ApiError> {
+    let attendance = attendances_repo
+        .mark(class_session_id, attendee_id, correction, user.actor())
+        .await?;
+
+    let response = attendance.ok_response("marked the attendance successfully");
+
+    Ok(response)
+}
+
+#[derive(Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct AttendanceAuditQuery {
+    attendee_id: Option<Uuid>,
+    attendance_id: Option<Uuid>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/subjects/{subject_id}/attendance_audit",
+    params(AttendanceAuditQuery, ListQuery),
+    responses(
+        (status = OK, body = AttendanceAuditListResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+pub async fn get_audit_for_one_subject(
+    State(attendances_repo): State<DynAttendancesRepo>,
+    Path(subject_id): Path<Uuid>,
+    _: RequireInstructorOf<Subject>,
+    Query(AttendanceAuditQuery {
+        attendee_id,
+        attendance_id,
+    }): Query<AttendanceAuditQuery>,
+    Paginated(query): Paginated,
+) -> Result<AppResponse<'static, Vec<AttendanceAuditEntry>>, ApiError> {
+    let entries = attendances_repo
+        .get_audit(
+            AttendanceAuditFilter {
+                subject_id: Some(subject_id),
+                attendee_id,
+                attendance_id,
+            },
+            query,
+        )
+        .await?;
 
-    attendances_repo.delete_by_id(attendance_id).await?;
+    let response = entries.ok_response("retreived the attendance audit trail successfully");
 
     Ok(response)
 }
@@ -1,27 +1,28 @@
-use std::sync::Arc;
-
 use tracing;
 
 use axum::{
-    extract::{Multipart, Path, State},
+    extract::{Multipart, Path, Query, State},
     routing::{get, post},
     Json, Router,
 };
-use jsonwebtoken::{encode, Header};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
-use ams_facerec::{Embedding, FaceRecognizer};
+use ams_facerec::{EmbeddedFace, Embedding};
 use ams_logic::prelude::*;
 
 use crate::{
     app::{
-        self,
-        config::{FaceRecModeKind, FACE_REC_MODE},
-        DynAttendancesRepo, DynAttendeesRepo, DynSubjectsRepo,
+        self, DynAttendancesRepo, DynAttendeesRepo, DynFaceRecognizer, DynFaceSamplesRepo,
+        DynMatchSettingsRepo, DynSessionsRepo, DynSettingsService, DynSubjectsRepo,
     },
-    auth::{AuthBody, AuthError, AuthPayload, Claims, User, KEYS},
+    auth::{AuthBody, AuthError, AuthPayload, User},
     error::ApiError,
-    response::{AppResponse, AppResponseDataExt, AppResponseMsgExt},
+    pagination::Paginated,
+    policy::{RequireAdmin, RequireAttendee, RequireSelfOrAdmin, RequireStaff},
+    response::{AppResponse, AppResponseDataExt, AppResponseMsgExt, AppResponsePageExt},
+    routes::face_samples,
 };
 
 pub(crate) fn routes() -> Router<app::State> {
@@ -57,6 +58,7 @@ pub(crate) fn routes() -> Router<app::State> {
 #[utoipa::path(
     get,
     path = "/attendees",
+    params(ListQuery),
     responses(
         (status = OK, body = AttendeesListResponse)
     ),
@@ -64,37 +66,70 @@ pub(crate) fn routes() -> Router<app::State> {
 )]
 async fn get_all(
     State(repo): State<DynAttendeesRepo>,
-    claimes: Claims,
+    _: RequireAdmin,
+    Paginated(query): Paginated,
 ) -> Result<AppResponse<'static, Vec<Attendee>>, ApiError> {
-    let User::Admin(_) = claimes.user else {
-        return Err(AuthError::UnauthorizedAccess.into());
-    };
-
-    let attendees = repo.get_all().await?;
+    let attendees = repo.get_all(query).await?;
     let response = attendees.ok_response("retreived all attendees successfully");
 
     Ok(response)
 }
 
+/// narrows the attendees matched against an image down.
+#[derive(Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct AttendeesImageQuery {
+    /// only attendees enrolled in this subject, when matching embeddings.
+    subject_id: Option<Uuid>,
+}
+
+/// an attendee recognized in an image.
+#[derive(Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AttendeeMatch {
+    #[serde(flatten)]
+    pub attendee: Attendee,
+    /// the metric `score` is measured in, none when classified.
+    pub metric: Option<MatchMetric>,
+    /// the distance to the attendee's embedding, lower is nearer.
+    pub score: Option<f64>,
+}
+
+impl From<NearestAttendee> for AttendeeMatch {
+    fn from(
+        NearestAttendee {
+            attendee,
+            metric,
+            score,
+        }: NearestAttendee,
+    ) -> Self {
+        Self {
+            attendee,
+            metric: Some(metric),
+            score: Some(score),
+        }
+    }
+}
+
 #[utoipa::path(
     post,
     path = "/attendees/image",
+    params(AttendeesImageQuery),
     request_body(content = Image, content_type = "multipart/form-data"),
     responses(
-        (status = OK, body = AttendeesListResponse)
+        (status = OK, body = AttendeeMatchesListResponse)
     ),
     security(("api_jwt_token" = []))
 )]
 async fn get_all_with_image(
     State(repo): State<DynAttendeesRepo>,
-    State(fr): State<Arc<FaceRecognizer>>,
-    claimes: Claims,
+    State(settings_repo): State<DynMatchSettingsRepo>,
+    State(settings_service): State<DynSettingsService>,
+    State(fr): State<DynFaceRecognizer>,
+    _: RequireStaff,
+    Query(AttendeesImageQuery { subject_id }): Query<AttendeesImageQuery>,
     multipart: Option<Multipart>,
-) -> Result<AppResponse<'static, Vec<Attendee>>, ApiError> {
-    if let User::Attendee(_) = claimes.user {
-        return Err(AuthError::UnauthorizedAccess.into());
-    };
-
+) -> Result<AppResponse<'static, Vec<AttendeeMatch>>, ApiError> {
     let Some(mut multipart) = multipart else {
         return Err(ApiError::Internal);
     };
@@ -103,42 +138,41 @@ async fn get_all_with_image(
         return Err(ApiError::Internal);
     };
 
-    let attendees = repo.get_all().await?;
-
     let image = field.bytes().await.map_err(|_| ApiError::Internal)?;
 
-    let attendees: Vec<_> = match *FACE_REC_MODE.read().await {
-        FaceRecModeKind::Embed => {
-            let embedding = fr.embed(&image).await?;
-
-            let mut attendees_embeddings: Vec<(Attendee, f64)> = attendees
-                .into_iter()
-                .filter(|attendee| attendee.embedding.is_some())
-                .map(|attendee| {
-                    let distance = attendee
-                        .embedding
-                        .as_ref()
-                        .expect("filtered attendees without embedding")
-                        .distance(&embedding);
-                    (attendee, distance)
-                })
-                .filter(|(_, distance)| distance < &0.6)
-                .collect();
-
-            attendees_embeddings
-                .sort_by(|attendee1, attendee2| attendee1.1.total_cmp(&attendee2.1));
-
-            attendees_embeddings
-                .into_iter()
-                .map(|(attendee, _)| attendee)
-                .collect()
+    let current = settings_service.current();
+    let attendees: Vec<_> = match current.face_rec_mode {
+        FaceRecMode::Embed => {
+            let embedding = fr.embed(&image).await?.normalized();
+            let settings = match subject_id {
+                Some(subject_id) => settings_repo.get_for_subject(subject_id).await?,
+                None => settings_repo.get_default().await?,
+            };
+
+            repo.find_nearest(FindNearest {
+                embedding,
+                k: current.max_matches,
+                metric: settings.metric,
+                threshold: settings.threshold,
+                subject_id,
+                strategy: current.match_strategy,
+            })
+            .await?
+            .into_iter()
+            .map(AttendeeMatch::from)
+            .collect()
         }
-        FaceRecModeKind::Classify => {
+        FaceRecMode::Classify => {
             let class = fr.classify(&image).await?;
-            attendees
-                .into_iter()
-                .filter(|attendee| attendee.id == class)
-                .collect()
+            match repo.get_by_id(class).await {
+                Ok(attendee) => vec![AttendeeMatch {
+                    attendee,
+                    metric: None,
+                    score: None,
+                }],
+                Err(RepoError::NotFound(_)) => Vec::new(),
+                Err(error) => return Err(error.into()),
+            }
         }
     };
 
@@ -158,13 +192,9 @@ async fn get_all_with_image(
 )]
 async fn create_one(
     State(repo): State<DynAttendeesRepo>,
-    claimes: Claims,
+    _: RequireAdmin,
     Json(attendee): Json<CreateAttendee>,
 ) -> Result<AppResponse<'static, Attendee>, ApiError> {
-    let User::Admin(_) = claimes.user else {
-        return Err(AuthError::UnauthorizedAccess.into());
-    };
-
     let attendee = repo.create(attendee).await?;
     let response = attendee.create_response("create on attendee successfully");
 
@@ -181,11 +211,8 @@ async fn create_one(
 )]
 async fn login_with_token(
     State(repo): State<DynAttendeesRepo>,
-    claimes: Claims,
+    RequireAttendee(attendee_id): RequireAttendee,
 ) -> Result<AppResponse<'static, Attendee>, ApiError> {
-    let User::Attendee(attendee_id) = claimes.user else {
-        return Err(AuthError::UnauthorizedAccess.into());
-    };
     let attendee = repo.get_by_id(attendee_id).await?;
     let response = attendee.ok_response("logged in as attendee successfully");
 
@@ -203,15 +230,8 @@ async fn login_with_token(
 async fn get_one(
     State(repo): State<DynAttendeesRepo>,
     Path(attendee_id): Path<Uuid>,
-    claimes: Claims,
+    _: RequireSelfOrAdmin<Attendee>,
 ) -> Result<AppResponse<'static, Attendee>, ApiError> {
-    match claimes.user {
-        User::Admin(_) => {}
-        User::Attendee(id) if id == attendee_id => {}
-        _ => {
-            return Err(AuthError::UnauthorizedAccess.into());
-        }
-    };
     let attendee = repo.get_by_id(attendee_id).await?;
     let response = attendee.ok_response("retreived an attendee successfully");
 
@@ -230,13 +250,9 @@ async fn get_one(
 async fn update_one(
     State(repo): State<DynAttendeesRepo>,
     Path(attendee_id): Path<Uuid>,
-    claimes: Claims,
+    _: RequireAdmin,
     Json(update_attendee): Json<UpdateAttendee>,
 ) -> Result<AppResponse<'static, Attendee>, ApiError> {
-    let User::Admin(_) = claimes.user else {
-        return Err(AuthError::UnauthorizedAccess.into());
-    };
-
     let attendee = repo.update(attendee_id, update_attendee).await?;
     let response = attendee.ok_response("update the attendee successfully");
 
@@ -254,12 +270,8 @@ async fn update_one(
 async fn delete_one(
     State(repo): State<DynAttendeesRepo>,
     Path(attendee_id): Path<Uuid>,
-    claimes: Claims,
+    _: RequireAdmin,
 ) -> Result<AppResponse<'static, ()>, ApiError> {
-    let User::Admin(_) = claimes.user else {
-        return Err(AuthError::UnauthorizedAccess.into());
-    };
-
     repo.delete_by_id(attendee_id).await?;
     let response = "deleted one attendee successfully".response();
 
@@ -277,26 +289,18 @@ async fn delete_one(
 )]
 async fn login_with_creds(
     State(repo): State<DynAttendeesRepo>,
+    State(sessions_repo): State<DynSessionsRepo>,
     payload: Option<Json<AuthPayload>>,
 ) -> Result<AppResponse<'static, AuthBody>, ApiError> {
     let Some(Json(payload)) = payload else {
         return Err(AuthError::MissingCredentials.into());
     };
 
-    let attendee = repo.get_by_email(payload.email).await?;
-
-    if payload.password != attendee.password {
-        return Err(AuthError::WrongCredentials.into());
-    }
-
-    let claims = Claims {
-        exp: usize::max_value(),
-        user: User::Attendee(attendee.id),
-    };
+    let attendee = repo.get_by_creds(payload.email, payload.password).await?;
 
-    let token = encode(&Header::default(), &claims, &KEYS.encoding).unwrap();
+    let auth_body = AuthBody::issue(&sessions_repo, User::Attendee(attendee.id)).await?;
 
-    let response = AuthBody { token }.ok_response("logged in as attendee successfully");
+    let response = auth_body.ok_response("logged in as attendee successfully");
 
     Ok(response)
 }
@@ -304,6 +308,7 @@ async fn login_with_creds(
 #[utoipa::path(
     get,
     path = "/attendees/{attendee_id}/subjects",
+    params(ListQuery),
     responses(
         (status = OK, body = SubjectsListResponse)
     ),
@@ -312,19 +317,17 @@ async fn login_with_creds(
 async fn get_all_subjects_for_one(
     State(repo): State<DynSubjectsRepo>,
     Path(attendee_id): Path<Uuid>,
-    claimes: Claims,
+    _: RequireSelfOrAdmin<Attendee>,
+    Paginated(query): Paginated,
 ) -> Result<AppResponse<'static, Vec<Subject>>, ApiError> {
-    if let User::Attendee(id) = claimes.user {
-        if id != attendee_id {
-            return Err(AuthError::UnauthorizedAccess.into());
-        }
-    };
-
     let subjects = repo
-        .get(SubjectsFilter {
-            attendee_id: Some(attendee_id),
-            ..Default::default()
-        })
+        .get(
+            SubjectsFilter {
+                attendee_id: Some(attendee_id),
+                ..Default::default()
+            },
+            query,
+        )
         .await?;
     let response = subjects.ok_response("retreived associated subjects successfully");
 
@@ -342,25 +345,20 @@ async fn get_all_subjects_for_one(
 async fn get_one_subject_for_one(
     State(repo): State<DynSubjectsRepo>,
     Path((attendee_id, subject_id)): Path<(Uuid, Uuid)>,
-    claimes: Claims,
+    _: RequireSelfOrAdmin<Attendee>,
 ) -> Result<AppResponse<'static, Subject>, ApiError> {
-    match claimes.user {
-        User::Admin(_) => {}
-        User::Instructor(id) if id == attendee_id => {}
-        _ => {
-            return Err(AuthError::UnauthorizedAccess.into());
-        }
-    };
-
     let subjects = repo
-        .get(SubjectsFilter {
-            id: Some(subject_id),
-            attendee_id: Some(attendee_id),
-            ..Default::default()
-        })
+        .get(
+            SubjectsFilter {
+                id: Some(subject_id),
+                attendee_id: Some(attendee_id),
+                ..Default::default()
+            },
+            ListQuery::default(),
+        )
         .await?;
 
-    let Some(subject) = subjects.into_iter().next() else {
+    let Some(subject) = subjects.items.into_iter().next() else {
         return Err(RepoError::NotFound("subject".to_owned()).into());
     };
 
@@ -380,12 +378,8 @@ async fn get_one_subject_for_one(
 async fn put_one_subject_to_one(
     State(repo): State<DynSubjectsRepo>,
     Path((attendee_id, subject_id)): Path<(Uuid, Uuid)>,
-    claimes: Claims,
+    _: RequireAdmin,
 ) -> Result<AppResponse<'static, ()>, ApiError> {
-    let User::Admin(_) = claimes.user else {
-        return Err(AuthError::UnauthorizedAccess.into());
-    };
-
     repo.add_attendee(subject_id, attendee_id).await?;
     let response = "a subject was added to an attendee successfully".response();
 
@@ -403,29 +397,38 @@ async fn put_one_subject_to_one(
 async fn delete_one_subject_from_one(
     State(repo): State<DynSubjectsRepo>,
     Path((attendee_id, subject_id)): Path<(Uuid, Uuid)>,
-    claimes: Claims,
+    _: RequireAdmin,
 ) -> Result<AppResponse<'static, ()>, ApiError> {
-    let User::Admin(_) = claimes.user else {
-        return Err(AuthError::UnauthorizedAccess.into());
-    };
-
     repo.remove_attendee(subject_id, attendee_id).await?;
     let response = "a subject was removed from an attendee successfully".response();
 
     Ok(response)
 }
 
-// TODO: unauthorize other attendees
+#[utoipa::path(
+    get,
+    path = "/attendees/{attendee_id}/subjects/{subject_id}/attendances",
+    params(ListQuery),
+    responses(
+        (status = OK, body = AttendancesListResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
 async fn get_all_attendances_with_one_attendee_and_one_subject(
     State(repo): State<DynAttendancesRepo>,
     Path((attendee_id, subject_id)): Path<(Uuid, Uuid)>,
-    _: Claims,
+    _: RequireSelfOrAdmin<Attendee>,
+    Paginated(query): Paginated,
 ) -> Result<AppResponse<'static, Vec<Attendance>>, ApiError> {
     let attendances = repo
-        .get(AttendancesFilter {
-            subject_id: Some(subject_id),
-            attendee_id: Some(attendee_id),
-        })
+        .get(
+            AttendancesFilter {
+                subject_id: Some(subject_id),
+                attendee_id: Some(attendee_id),
+                ..Default::default()
+            },
+            query,
+        )
         .await?;
 
     let response = attendances.ok_response("retreived all subject attendances for an attendee");
@@ -444,15 +447,13 @@ async fn get_all_attendances_with_one_attendee_and_one_subject(
 )]
 async fn upload_image(
     State(repo): State<DynAttendeesRepo>,
-    State(fr): State<Arc<FaceRecognizer>>,
+    State(samples_repo): State<DynFaceSamplesRepo>,
+    State(settings_service): State<DynSettingsService>,
+    State(fr): State<DynFaceRecognizer>,
     Path(attendee_id): Path<Uuid>,
-    claimes: Claims,
+    _: RequireAdmin,
     mut multipart: Multipart,
 ) -> Result<AppResponse<'static, Attendee>, ApiError> {
-    let User::Admin(_) = claimes.user else {
-        return Err(AuthError::UnauthorizedAccess.into());
-    };
-
     while let Ok(Some(item)) = multipart.next_field().await {
         tracing::info!("{:#?}", item.content_type());
 
@@ -465,11 +466,26 @@ async fn upload_image(
         if let Some("image") = name {
             tracing::info!(target: "adding profile image", image=?file_name);
             let image = item.bytes().await.map_err(|_| ApiError::Internal)?.to_vec();
-            let embedding = fr.embed(&image).await?;
+            let EmbeddedFace { embedding, quality } = face_samples::enroll(
+                &fr,
+                &image,
+                settings_service.current().face_sample_min_quality,
+            )
+            .await?;
+            samples_repo
+                .create(
+                    attendee_id,
+                    CreateFaceSample {
+                        embedding,
+                        quality,
+                        image: image.clone().into(),
+                        extension: "png".into(),
+                    },
+                )
+                .await?;
             repo.update(
                 attendee_id,
                 UpdateAttendee {
-                    embedding: Some(Some(embedding)),
                     image: Some((image.into(), "image.png".into())),
                     ..Default::default()
                 },
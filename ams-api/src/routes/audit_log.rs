@@ -0,0 +1,76 @@
+use axum::{
+    extract::{Query, State},
+    routing::get,
+    Router,
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use ams_logic::prelude::*;
+
+use crate::{
+    app::{self, DynAuditLogRepo},
+    error::ApiError,
+    pagination::Paginated,
+    policy::RequireAdmin,
+    response::{AppResponse, AppResponsePageExt},
+};
+
+pub(crate) fn routes() -> Router<app::State> {
+    Router::new().route("/admin/audit", get(get_all))
+}
+
+#[derive(Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditQuery {
+    actor_id: Option<Uuid>,
+    #[param(value_type = Option<UserKind>)]
+    actor_kind: Option<UserKind>,
+    /// `POST`, `PUT`, `PATCH` or `DELETE`.
+    method: Option<String>,
+    /// a documented route, e.g. `/subjects/{subject_id}`.
+    route: Option<String>,
+    entity_id: Option<Uuid>,
+}
+
+/// the creates, updates and deletes made through the api or by the server
+/// itself, newest first by default.
+#[utoipa::path(
+    get,
+    path = "/admin/audit",
+    params(AuditQuery, ListQuery),
+    responses(
+        (status = OK, body = AuditEntriesListResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+pub async fn get_all(
+    State(repo): State<DynAuditLogRepo>,
+    _: RequireAdmin,
+    Query(AuditQuery {
+        actor_id,
+        actor_kind,
+        method,
+        route,
+        entity_id,
+    }): Query<AuditQuery>,
+    Paginated(query): Paginated,
+) -> Result<AppResponse<'static, Vec<AuditEntry>>, ApiError> {
+    let entries = repo
+        .get(
+            AuditFilter {
+                actor_id,
+                actor_kind,
+                method,
+                route,
+                entity_id,
+            },
+            query,
+        )
+        .await?;
+
+    let response = entries.ok_response("retreived the audit log successfully");
+
+    Ok(response)
+}
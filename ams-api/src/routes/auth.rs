@@ -0,0 +1,56 @@
+use axum::{extract::State, routing::post, Json, Router};
+
+use crate::{
+    app::{self, DynSessionsRepo},
+    auth::{AuthBody, AuthError, Claims, RefreshPayload},
+    error::ApiError,
+    response::{AppResponse, AppResponseDataExt, AppResponseMsgExt},
+};
+
+pub(crate) fn routes() -> Router<app::State> {
+    Router::new()
+        .route("/auth/refresh", post(refresh))
+        .route("/auth/logout", post(logout))
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/refresh",
+    request_body = RefreshPayload,
+    responses(
+        (status = OK, body = AuthResponse)
+    ),
+)]
+async fn refresh(
+    State(sessions_repo): State<DynSessionsRepo>,
+    payload: Option<Json<RefreshPayload>>,
+) -> Result<AppResponse<'static, AuthBody>, ApiError> {
+    let Some(Json(payload)) = payload else {
+        return Err(AuthError::MissingCredentials.into());
+    };
+
+    let auth_body = AuthBody::refresh(&sessions_repo, payload.refresh_token).await?;
+
+    let response = auth_body.ok_response("refreshed the session successfully");
+
+    Ok(response)
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/logout",
+    responses(
+        (status = OK)
+    ),
+    security(("api_jwt_token" = []))
+)]
+async fn logout(
+    State(sessions_repo): State<DynSessionsRepo>,
+    claims: Claims,
+) -> Result<AppResponse<'static, ()>, ApiError> {
+    sessions_repo.revoke(claims.sid).await?;
+
+    let response = "logged out successfully".response();
+
+    Ok(response)
+}
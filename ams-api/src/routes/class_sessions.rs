@@ -0,0 +1,158 @@
+use axum::{
+    extract::{Path, Query, State},
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use ams_logic::prelude::*;
+
+use crate::{
+    app::{self, DynClassSessionsRepo},
+    auth::Claims,
+    error::ApiError,
+    pagination::Paginated,
+    policy::{RequireAdmin, RequireInstructorOf},
+    response::{AppResponse, AppResponseDataExt, AppResponsePageExt},
+};
+
+pub(crate) fn routes() -> Router<app::State> {
+    Router::new()
+        .route(
+            "/subjects/:id/class_sessions",
+            get(get_all_for_one_subject).post(generate_for_one_subject),
+        )
+        .route("/class_sessions/:id", get(get_one))
+        .route("/class_sessions/:id/cancel", post(cancel_one))
+        .route("/class_sessions/:id/reschedule", post(reschedule_one))
+}
+
+/// local dates to list the sessions between, both inclusive.
+#[derive(Deserialize, IntoParams)]
+pub struct ClassSessionsRange {
+    #[param(value_type = Option<String>, format = Date)]
+    from: Option<NaiveDate>,
+    #[param(value_type = Option<String>, format = Date)]
+    to: Option<NaiveDate>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/subjects/{subject_id}/class_sessions",
+    params(ClassSessionsRange, ListQuery),
+    responses(
+        (status = OK, body = ClassSessionsListResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+pub async fn get_all_for_one_subject(
+    State(repo): State<DynClassSessionsRepo>,
+    _: Claims,
+    Path(subject_id): Path<Uuid>,
+    Query(ClassSessionsRange { from, to }): Query<ClassSessionsRange>,
+    Paginated(query): Paginated,
+) -> Result<AppResponse<'static, Vec<ClassSession>>, ApiError> {
+    let sessions = repo
+        .get(
+            ClassSessionsFilter {
+                subject_id: Some(subject_id),
+                from,
+                to,
+            },
+            query,
+        )
+        .await?;
+
+    let response = sessions.ok_response("retreived all class sessions for a subject successfully");
+
+    Ok(response)
+}
+
+#[utoipa::path(
+    post,
+    path = "/subjects/{subject_id}/class_sessions",
+    request_body = GenerateClassSessions,
+    responses(
+        (status = CREATED, body = ClassSessionsListResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+pub async fn generate_for_one_subject(
+    State(repo): State<DynClassSessionsRepo>,
+    _: RequireAdmin,
+    Path(subject_id): Path<Uuid>,
+    Json(range): Json<GenerateClassSessions>,
+) -> Result<AppResponse<'static, Vec<ClassSession>>, ApiError> {
+    let sessions = repo.generate(subject_id, range).await?;
+
+    let response = sessions.create_response("generated class sessions successfully");
+
+    Ok(response)
+}
+
+#[utoipa::path(
+    get,
+    path = "/class_sessions/{class_session_id}",
+    responses(
+        (status = OK, body = ClassSessionResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+pub async fn get_one(
+    State(repo): State<DynClassSessionsRepo>,
+    _: Claims,
+    Path(class_session_id): Path<Uuid>,
+) -> Result<AppResponse<'static, ClassSession>, ApiError> {
+    let session = repo.get_by_id(class_session_id).await?;
+
+    let response = session.ok_response("retreived one class session successfully");
+
+    Ok(response)
+}
+
+#[utoipa::path(
+    post,
+    path = "/class_sessions/{class_session_id}/cancel",
+    request_body = CancelClassSession,
+    responses(
+        (status = OK, body = ClassSessionResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+pub async fn cancel_one(
+    State(repo): State<DynClassSessionsRepo>,
+    _: RequireInstructorOf<ClassSession>,
+    Path(class_session_id): Path<Uuid>,
+    Json(cancel): Json<CancelClassSession>,
+) -> Result<AppResponse<'static, ClassSession>, ApiError> {
+    let session = repo.cancel(class_session_id, cancel).await?;
+
+    let response = session.ok_response("cancelled one class session successfully");
+
+    Ok(response)
+}
+
+#[utoipa::path(
+    post,
+    path = "/class_sessions/{class_session_id}/reschedule",
+    request_body = RescheduleClassSession,
+    responses(
+        (status = OK, body = ClassSessionResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+pub async fn reschedule_one(
+    State(repo): State<DynClassSessionsRepo>,
+    _: RequireInstructorOf<ClassSession>,
+    Path(class_session_id): Path<Uuid>,
+    Json(reschedule): Json<RescheduleClassSession>,
+) -> Result<AppResponse<'static, ClassSession>, ApiError> {
+    let session = repo.reschedule(class_session_id, reschedule).await?;
+
+    let response = session.ok_response("rescheduled one class session successfully");
+
+    Ok(response)
+}
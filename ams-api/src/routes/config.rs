@@ -1,34 +1,73 @@
-use std::sync::Arc;
-
-use ams_facerec::FaceRecognizer;
 use axum::{
-    extract::{DefaultBodyLimit, Multipart, Query, State},
-    routing::{post, put},
-    Router,
+    extract::{DefaultBodyLimit, Multipart, Path, Query, State},
+    routing::{get, post},
+    Json, Router,
 };
 use serde::Deserialize;
 use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use ams_facerec::FaceRecognitionBackend;
+use ams_logic::prelude::*;
 
 use crate::{
-    app::{
-        self,
-        config::{FaceRecModeKind, FACE_REC_MODE},
-    },
-    auth::{AuthError, Claims, User},
+    app::{self, DynClassifiersRepo, DynFaceRecognizer, DynMatchSettingsRepo, DynSettingsService},
     error::ApiError,
-    response::{AppResponse, AppResponseMsgExt},
+    pagination::Paginated,
+    policy::{RequireAdmin, RequireStaff},
+    response::{AppResponse, AppResponseDataExt, AppResponseMsgExt, AppResponsePageExt},
 };
 
 pub(crate) fn routes() -> Router<app::State> {
     Router::new()
-        .route("/config/face_recognition", put(face_recognition))
-        .route("/config/classifier", post(upload_classifier))
-        .layer(DefaultBodyLimit::max(1024 * 1024 * 1024))
+        .route(
+            "/config/face_recognition",
+            get(get_face_recognition).put(face_recognition),
+        )
+        .route("/config/settings", get(get_settings).put(update_settings))
+        .route(
+            "/config/classifier",
+            get(get_classifier).post(upload_classifier),
+        )
+        .route("/config/classifier/rollback", post(rollback_classifier))
+        .route("/config/classifiers", get(get_classifiers))
+        .route(
+            "/config/classifiers/:id/activate",
+            post(activate_classifier),
+        )
+        .route("/config/matching", get(get_matching).put(update_matching))
+        .route(
+            "/config/matching/subjects/:id",
+            get(get_subject_matching)
+                .put(update_subject_matching)
+                .delete(delete_subject_matching),
+        )
+        .layer(DefaultBodyLimit::max(
+            app::config::config().server.classifier_body_limit,
+        ))
 }
 
 #[derive(Deserialize, IntoParams, ToSchema)]
 pub struct FaceRecognition {
-    mode: FaceRecModeKind,
+    mode: FaceRecMode,
+}
+
+#[utoipa::path(
+    get,
+    path = "/config/face_recognition",
+    responses(
+        (status = OK, body = FaceRecModeResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+async fn get_face_recognition(
+    State(settings_service): State<DynSettingsService>,
+    _: RequireStaff,
+) -> Result<AppResponse<'static, FaceRecMode>, ApiError> {
+    let mode = settings_service.current().face_rec_mode;
+    let response = mode.ok_response("retreived the face recognition mode successfully");
+
+    Ok(response)
 }
 
 #[utoipa::path(
@@ -41,55 +80,300 @@ pub struct FaceRecognition {
     security(("api_jwt_token" = []))
 )]
 async fn face_recognition(
+    State(settings_service): State<DynSettingsService>,
+    _: RequireAdmin,
     Query(face_recogintion): Query<FaceRecognition>,
-    claims: Claims,
 ) -> Result<AppResponse<'static, ()>, ApiError> {
-    let User::Admin(_) = claims.user else {
-        return Err(AuthError::UnauthorizedAccess.into());
-    };
-
-    *FACE_REC_MODE.write().await = face_recogintion.mode;
+    settings_service
+        .update(UpdateSettings {
+            face_rec_mode: Some(face_recogintion.mode),
+            ..Default::default()
+        })
+        .await?;
 
     let respone = "updated the face recognition mode successfulty".response();
 
     Ok(respone)
 }
 
+#[utoipa::path(
+    get,
+    path = "/config/settings",
+    responses(
+        (status = OK, body = SettingsResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+async fn get_settings(
+    State(settings_service): State<DynSettingsService>,
+    _: RequireAdmin,
+) -> Result<AppResponse<'static, Settings>, ApiError> {
+    let settings = settings_service.current();
+    let response = settings.ok_response("retreived the settings successfully");
+
+    Ok(response)
+}
+
+#[utoipa::path(
+    put,
+    path = "/config/settings",
+    request_body = UpdateSettings,
+    responses(
+        (status = OK, body = SettingsResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+async fn update_settings(
+    State(settings_service): State<DynSettingsService>,
+    _: RequireAdmin,
+    Json(settings): Json<UpdateSettings>,
+) -> Result<AppResponse<'static, Settings>, ApiError> {
+    let settings = settings_service.update(settings).await?;
+    let response = settings.ok_response("updated the settings successfully");
+
+    Ok(response)
+}
+
+#[utoipa::path(
+    get,
+    path = "/config/classifier",
+    responses(
+        (status = OK, body = ClassifierModelResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+async fn get_classifier(
+    State(repo): State<DynClassifiersRepo>,
+    _: RequireAdmin,
+) -> Result<AppResponse<'static, ClassifierModel>, ApiError> {
+    let classifier = repo.get_active().await?.ok_or(ApiError::NotFound {
+        message: "no classifier is active".to_owned(),
+    })?;
+    let response = classifier.ok_response("retreived the active classifier successfully");
+
+    Ok(response)
+}
+
 #[utoipa::path(
     post,
     path = "/config/classifier",
     request_body(content = Classifier, content_type = "multipart/form-data"),
     responses(
-        (status = OK)
+        (status = OK, body = ClassifierModelResponse)
     ),
     security(("api_jwt_token" = []))
 )]
 async fn upload_classifier(
-    State(face_recogition): State<Arc<FaceRecognizer>>,
-    claims: Claims,
+    State(face_recogition): State<DynFaceRecognizer>,
+    State(repo): State<DynClassifiersRepo>,
+    RequireAdmin(admin_id): RequireAdmin,
     mut multipart: Multipart,
-) -> Result<AppResponse<'static, ()>, ApiError> {
-    let User::Admin(_) = claims.user else {
-        return Err(AuthError::UnauthorizedAccess.into());
-    };
+) -> Result<AppResponse<'static, ClassifierModel>, ApiError> {
+    let mut data = None;
+    let mut notes = None;
 
-    let Ok(Some(item)) = multipart.next_field().await else {
-        return Err(ApiError::BadRequest);
-    };
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|_| ApiError::BadRequest)?
+    {
+        if field.name() == Some("notes") {
+            notes = Some(field.text().await.map_err(|_| ApiError::BadRequest)?);
+        } else if field.file_name().is_some() {
+            data = Some(
+                field
+                    .bytes()
+                    .await
+                    .map_err(|_| ApiError::BadRequest)?
+                    .to_vec(),
+            );
+        }
+    }
 
-    let Some(_) = item.file_name() else {
+    let Some(data) = data else {
         return Err(ApiError::BadRequest);
     };
 
-    let classifier = item
-        .bytes()
-        .await
-        .map_err(|_| ApiError::BadRequest)?
-        .to_vec();
+    let classifier = repo
+        .create(CreateClassifier {
+            data: data.into(),
+            uploaded_by: Some(admin_id),
+            notes,
+        })
+        .await?;
 
-    face_recogition.upload_classifier(&classifier).await?;
+    let classifier = activate(&*face_recogition, &*repo, classifier.id).await?;
+    let response = classifier.ok_response("updated the classifier successfulty");
 
-    let respone = "updated the classifier successfulty".response();
+    Ok(response)
+}
 
-    Ok(respone)
+#[utoipa::path(
+    get,
+    path = "/config/classifiers",
+    params(ListQuery),
+    responses(
+        (status = OK, body = ClassifierModelsListResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+async fn get_classifiers(
+    State(repo): State<DynClassifiersRepo>,
+    _: RequireAdmin,
+    Paginated(query): Paginated,
+) -> Result<AppResponse<'static, Vec<ClassifierModel>>, ApiError> {
+    let classifiers = repo.get_all(query).await?;
+    let response = classifiers.ok_response("retreived the classifiers successfully");
+
+    Ok(response)
+}
+
+#[utoipa::path(
+    post,
+    path = "/config/classifiers/{classifier_id}/activate",
+    responses(
+        (status = OK, body = ClassifierModelResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+async fn activate_classifier(
+    State(face_recogition): State<DynFaceRecognizer>,
+    State(repo): State<DynClassifiersRepo>,
+    _: RequireAdmin,
+    Path(classifier_id): Path<Uuid>,
+) -> Result<AppResponse<'static, ClassifierModel>, ApiError> {
+    let classifier = activate(&*face_recogition, &*repo, classifier_id).await?;
+    let response = classifier.ok_response("activated the classifier successfully");
+
+    Ok(response)
+}
+
+#[utoipa::path(
+    post,
+    path = "/config/classifier/rollback",
+    responses(
+        (status = OK, body = ClassifierModelResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+async fn rollback_classifier(
+    State(face_recogition): State<DynFaceRecognizer>,
+    State(repo): State<DynClassifiersRepo>,
+    _: RequireAdmin,
+) -> Result<AppResponse<'static, ClassifierModel>, ApiError> {
+    let previous = repo.previous().await?;
+    let classifier = activate(&*face_recogition, &*repo, previous.id).await?;
+    let response = classifier.ok_response("rolled back the classifier successfully");
+
+    Ok(response)
+}
+
+/// pushes the classifier to the face recognition backend, then records it as
+/// the active one.
+async fn activate(
+    face_recogition: &(dyn FaceRecognitionBackend + Send + Sync),
+    repo: &(dyn ClassifiersRepoTrait + Send + Sync),
+    classifier_id: Uuid,
+) -> Result<ClassifierModel, ApiError> {
+    let data = repo.data(classifier_id).await?;
+    face_recogition.upload_classifier(&data).await?;
+
+    Ok(repo.activate(classifier_id).await?)
+}
+
+#[utoipa::path(
+    get,
+    path = "/config/matching",
+    responses(
+        (status = OK, body = MatchSettingsResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+async fn get_matching(
+    State(repo): State<DynMatchSettingsRepo>,
+    _: RequireStaff,
+) -> Result<AppResponse<'static, MatchSettings>, ApiError> {
+    let settings = repo.get_default().await?;
+    let response = settings.ok_response("retreived the match settings successfully");
+
+    Ok(response)
+}
+
+#[utoipa::path(
+    put,
+    path = "/config/matching",
+    request_body = UpdateMatchSettings,
+    responses(
+        (status = OK, body = MatchSettingsResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+async fn update_matching(
+    State(repo): State<DynMatchSettingsRepo>,
+    _: RequireAdmin,
+    Json(settings): Json<UpdateMatchSettings>,
+) -> Result<AppResponse<'static, MatchSettings>, ApiError> {
+    let settings = repo.update_default(settings).await?;
+    let response = settings.ok_response("updated the match settings successfully");
+
+    Ok(response)
+}
+
+#[utoipa::path(
+    get,
+    path = "/config/matching/subjects/{subject_id}",
+    responses(
+        (status = OK, body = MatchSettingsResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+async fn get_subject_matching(
+    State(repo): State<DynMatchSettingsRepo>,
+    _: RequireStaff,
+    Path(subject_id): Path<Uuid>,
+) -> Result<AppResponse<'static, MatchSettings>, ApiError> {
+    let settings = repo.get_for_subject(subject_id).await?;
+    let response = settings.ok_response("retreived the subject match settings successfully");
+
+    Ok(response)
+}
+
+#[utoipa::path(
+    put,
+    path = "/config/matching/subjects/{subject_id}",
+    request_body = UpdateMatchSettings,
+    responses(
+        (status = OK, body = MatchSettingsResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+async fn update_subject_matching(
+    State(repo): State<DynMatchSettingsRepo>,
+    _: RequireAdmin,
+    Path(subject_id): Path<Uuid>,
+    Json(settings): Json<UpdateMatchSettings>,
+) -> Result<AppResponse<'static, MatchSettings>, ApiError> {
+    let settings = repo.set_for_subject(subject_id, settings).await?;
+    let response = settings.ok_response("overrode the subject match settings successfully");
+
+    Ok(response)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/config/matching/subjects/{subject_id}",
+    responses(
+        (status = OK)
+    ),
+    security(("api_jwt_token" = []))
+)]
+async fn delete_subject_matching(
+    State(repo): State<DynMatchSettingsRepo>,
+    _: RequireAdmin,
+    Path(subject_id): Path<Uuid>,
+) -> Result<AppResponse<'static, ()>, ApiError> {
+    repo.clear_for_subject(subject_id).await?;
+    let response = "reset the subject match settings to the default successfully".response();
+
+    Ok(response)
 }
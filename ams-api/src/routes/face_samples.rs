@@ -0,0 +1,174 @@
+use std::path::Path as FilePath;
+
+use axum::{
+    extract::{Multipart, Path, State},
+    routing::{delete, get, post},
+    Router,
+};
+use uuid::Uuid;
+
+use ams_facerec::{EmbeddedFace, Embedding};
+use ams_logic::prelude::*;
+
+use crate::{
+    app::{self, DynFaceRecognizer, DynFaceSamplesRepo, DynSettingsService},
+    error::ApiError,
+    pagination::Paginated,
+    policy::{RequireAdmin, RequireSelfOrAdmin},
+    response::{AppResponse, AppResponseDataExt, AppResponseMsgExt, AppResponsePageExt},
+};
+
+pub(crate) fn routes() -> Router<app::State> {
+    Router::new()
+        .route("/attendees/:id/face_samples", get(get_all).post(create_one))
+        .route("/attendees/:id/face_samples/:id", delete(delete_one))
+        .route("/attendees/:id/face_samples/:id/embed", post(embed_one))
+}
+
+/// embeds `image` into a unit embedding, rejecting it when its quality is
+/// below `min_quality`.
+pub(crate) async fn enroll(
+    fr: &DynFaceRecognizer,
+    image: &[u8],
+    min_quality: f64,
+) -> Result<EmbeddedFace, ApiError> {
+    let mut face = fr.enroll(image).await?;
+    face.embedding = face.embedding.normalized();
+
+    if face.quality < min_quality {
+        return Err(ApiError::LowQualityFaceSample {
+            quality: face.quality,
+            min_quality,
+        });
+    }
+
+    Ok(face)
+}
+
+#[utoipa::path(
+    get,
+    path = "/attendees/{attendee_id}/face_samples",
+    params(ListQuery),
+    responses(
+        (status = OK, body = FaceSamplesListResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+pub async fn get_all(
+    State(repo): State<DynFaceSamplesRepo>,
+    _: RequireSelfOrAdmin<Attendee>,
+    Path(attendee_id): Path<Uuid>,
+    Paginated(query): Paginated,
+) -> Result<AppResponse<'static, Vec<FaceSample>>, ApiError> {
+    let samples = repo.get_all(attendee_id, query).await?;
+    let response = samples.ok_response("retreived all face samples successfully");
+
+    Ok(response)
+}
+
+#[utoipa::path(
+    post,
+    path = "/attendees/{attendee_id}/face_samples",
+    request_body(content = Image, content_type = "multipart/form-data"),
+    responses(
+        (status = CREATED, body = FaceSampleResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+pub async fn create_one(
+    State(repo): State<DynFaceSamplesRepo>,
+    State(settings_service): State<DynSettingsService>,
+    State(fr): State<DynFaceRecognizer>,
+    _: RequireAdmin,
+    Path(attendee_id): Path<Uuid>,
+    mut multipart: Multipart,
+) -> Result<AppResponse<'static, FaceSample>, ApiError> {
+    let Some(field) = multipart.next_field().await.ok().flatten() else {
+        return Err(ApiError::BadRequest);
+    };
+
+    let extension = field
+        .file_name()
+        .and_then(|file_name| FilePath::new(file_name).extension())
+        .and_then(|extension| extension.to_str())
+        .unwrap_or("png")
+        .to_owned();
+    let image = field
+        .bytes()
+        .await
+        .map_err(|_| ApiError::Internal)?
+        .to_vec();
+
+    let EmbeddedFace { embedding, quality } = enroll(
+        &fr,
+        &image,
+        settings_service.current().face_sample_min_quality,
+    )
+    .await?;
+
+    let sample = repo
+        .create(
+            attendee_id,
+            CreateFaceSample {
+                embedding,
+                quality,
+                image: image.into(),
+                extension: extension.into(),
+            },
+        )
+        .await?;
+    let response = sample.create_response("enrolled one face sample successfully");
+
+    Ok(response)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/attendees/{attendee_id}/face_samples/{sample_id}",
+    responses(
+        (status = OK)
+    ),
+    security(("api_jwt_token" = []))
+)]
+pub async fn delete_one(
+    State(repo): State<DynFaceSamplesRepo>,
+    _: RequireAdmin,
+    Path((attendee_id, sample_id)): Path<(Uuid, Uuid)>,
+) -> Result<AppResponse<'static, ()>, ApiError> {
+    repo.delete_by_id(attendee_id, sample_id).await?;
+    let response = "deleted one face sample successfully".response();
+
+    Ok(response)
+}
+
+#[utoipa::path(
+    post,
+    path = "/attendees/{attendee_id}/face_samples/{sample_id}/embed",
+    responses(
+        (status = OK, body = FaceSampleResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+pub async fn embed_one(
+    State(repo): State<DynFaceSamplesRepo>,
+    State(fr): State<DynFaceRecognizer>,
+    _: RequireAdmin,
+    Path((attendee_id, sample_id)): Path<(Uuid, Uuid)>,
+) -> Result<AppResponse<'static, FaceSample>, ApiError> {
+    let image = repo.image(attendee_id, sample_id).await?;
+    let EmbeddedFace { embedding, quality } = fr.enroll(&image).await?;
+
+    let sample = repo
+        .update_embedding(
+            attendee_id,
+            sample_id,
+            UpdateFaceSampleEmbedding {
+                embedding: embedding.normalized(),
+                quality,
+            },
+        )
+        .await?;
+    let response = sample.ok_response("re-embedded one face sample successfully");
+
+    Ok(response)
+}
@@ -0,0 +1,66 @@
+use axum::{
+    body::Bytes,
+    extract::{Path, Query, State},
+    routing::post,
+    Router,
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use ams_logic::prelude::*;
+
+use crate::{
+    app::{self, DynImportsService},
+    error::ApiError,
+    policy::RequireAdmin,
+    response::{AppResponse, AppResponseDataExt},
+};
+
+pub(crate) fn routes() -> Router<app::State> {
+    Router::new().route("/imports/:kind", post(import))
+}
+
+#[derive(Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportQuery {
+    /// only reports what would be imported, set to `false` to write the rows.
+    dry_run: Option<bool>,
+}
+
+/// imports a csv file all at once, a failing row leaves the database as it
+/// was and is reported with its line.
+#[utoipa::path(
+    post,
+    path = "/imports/{kind}",
+    params(
+        ("kind" = ImportKind, Path, description = "what the rows of the file are"),
+        ImportQuery,
+    ),
+    request_body(content = String, content_type = "text/csv"),
+    responses(
+        (status = OK, description = "a dry run or a failed import", body = ImportReportResponse),
+        (status = CREATED, description = "the rows were imported", body = ImportReportResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+async fn import(
+    State(service): State<DynImportsService>,
+    _: RequireAdmin,
+    Path(kind): Path<ImportKind>,
+    Query(ImportQuery { dry_run }): Query<ImportQuery>,
+    csv: Bytes,
+) -> Result<AppResponse<'static, ImportReport>, ApiError> {
+    let report = service
+        .import(kind, csv.to_vec(), dry_run.unwrap_or(true))
+        .await?;
+
+    let response = if report.committed {
+        report.create_response("imported the file successfully")
+    } else if report.errors.is_empty() {
+        report.ok_response("the file can be imported")
+    } else {
+        report.ok_response("nothing was imported, some rows failed")
+    };
+
+    Ok(response)
+}
@@ -3,16 +3,17 @@ use axum::{
     routing::{get, post},
     Json, Router,
 };
-use jsonwebtoken::{encode, Header};
 use uuid::Uuid;
 
 use ams_logic::prelude::*;
 
 use crate::{
-    app::{self, DynInstructorsRepo, DynSubjectsRepo},
-    auth::{AuthBody, AuthError, AuthPayload, Claims, User, KEYS},
+    app::{self, DynInstructorsRepo, DynSessionsRepo, DynSubjectsRepo},
+    auth::{AuthBody, AuthError, AuthPayload, User},
     error::ApiError,
-    response::{AppResponse, AppResponseDataExt, AppResponseMsgExt},
+    pagination::Paginated,
+    policy::{RequireAdmin, RequireInstructor, RequireSelfOrAdmin},
+    response::{AppResponse, AppResponseDataExt, AppResponseMsgExt, AppResponsePageExt},
 };
 
 pub(crate) fn routes() -> Router<app::State> {
@@ -43,6 +44,7 @@ pub(crate) fn routes() -> Router<app::State> {
 #[utoipa::path(
     get,
     path = "/instructors",
+    params(ListQuery),
     responses(
         (status = OK, body = InstructorsListResponse)
     ),
@@ -50,13 +52,10 @@ pub(crate) fn routes() -> Router<app::State> {
 )]
 async fn get_all(
     State(repo): State<DynInstructorsRepo>,
-    claimes: Claims,
+    _: RequireAdmin,
+    Paginated(query): Paginated,
 ) -> Result<AppResponse<'static, Vec<Instructor>>, ApiError> {
-    let User::Admin(_) = claimes.user else {
-        return Err(AuthError::UnauthorizedAccess.into());
-    };
-
-    let instructors = repo.get_all().await?;
+    let instructors = repo.get_all(query).await?;
     let response = instructors.ok_response("retreived all instructors successfully");
     Ok(response)
 }
@@ -72,13 +71,9 @@ async fn get_all(
 )]
 async fn create_one(
     State(repo): State<DynInstructorsRepo>,
-    claimes: Claims,
+    _: RequireAdmin,
     Json(instructor): Json<CreateInstructor>,
 ) -> Result<AppResponse<'static, Instructor>, ApiError> {
-    let User::Admin(_) = claimes.user else {
-        return Err(AuthError::UnauthorizedAccess.into());
-    };
-
     let instructor = repo.create(instructor).await?;
     let response = instructor.create_response("create on instructor successfully");
 
@@ -96,16 +91,8 @@ async fn create_one(
 async fn get_one(
     State(repo): State<DynInstructorsRepo>,
     Path(instructor_id): Path<Uuid>,
-    claimes: Claims,
+    _: RequireSelfOrAdmin<Instructor>,
 ) -> Result<AppResponse<'static, Instructor>, ApiError> {
-    match claimes.user {
-        User::Admin(_) => {}
-        User::Instructor(id) if id == instructor_id => {}
-        _ => {
-            return Err(AuthError::UnauthorizedAccess.into());
-        }
-    };
-
     let instructor = repo.get_by_id(instructor_id).await?;
     let response = instructor.ok_response("retreived an instructor successfully");
 
@@ -115,9 +102,6 @@ async fn get_one(
 #[utoipa::path(
     patch,
     path = "/instructors/{instructor_id}",
-    params(
-        ("id" = Uuid, Path, description = "instructor id"),
-    ),
     request_body = UpdateInstructor,
     responses(
         (status = OK, body = InstructorResponse)
@@ -127,13 +111,9 @@ async fn get_one(
 async fn update_one(
     State(repo): State<DynInstructorsRepo>,
     Path(instructor_id): Path<Uuid>,
-    claimes: Claims,
+    _: RequireAdmin,
     Json(update_instructor): Json<UpdateInstructor>,
 ) -> Result<AppResponse<'static, Instructor>, ApiError> {
-    let User::Admin(_) = claimes.user else {
-        return Err(AuthError::UnauthorizedAccess.into());
-    };
-
     let instructor = repo.update(instructor_id, update_instructor).await?;
     let response = instructor.ok_response("update the instructor successfully");
 
@@ -142,7 +122,7 @@ async fn update_one(
 
 #[utoipa::path(
     delete,
-    path = "/instructors/{id}",
+    path = "/instructors/{instructor_id}",
     responses(
         (status = OK)
     ),
@@ -151,12 +131,8 @@ async fn update_one(
 async fn delete_one(
     State(repo): State<DynInstructorsRepo>,
     Path(instructor_id): Path<Uuid>,
-    claimes: Claims,
+    _: RequireAdmin,
 ) -> Result<AppResponse<'static, ()>, ApiError> {
-    let User::Admin(_) = claimes.user else {
-        return Err(AuthError::UnauthorizedAccess.into());
-    };
-
     repo.delete_by_id(instructor_id).await?;
     let response = "deleted one instructor successfully".response();
 
@@ -174,26 +150,18 @@ async fn delete_one(
 )]
 async fn login_with_creds(
     State(repo): State<DynInstructorsRepo>,
+    State(sessions_repo): State<DynSessionsRepo>,
     payload: Option<Json<AuthPayload>>,
 ) -> Result<AppResponse<'static, AuthBody>, ApiError> {
     let Some(Json(payload)) = payload else {
         return Err(AuthError::MissingCredentials.into());
     };
 
-    let instructor = repo.get_by_email(payload.email).await?;
+    let instructor = repo.get_by_creds(payload.email, payload.password).await?;
 
-    if payload.password != instructor.password {
-        return Err(AuthError::WrongCredentials.into());
-    }
+    let auth_body = AuthBody::issue(&sessions_repo, User::Instructor(instructor.id)).await?;
 
-    let claims = Claims {
-        exp: usize::max_value(),
-        user: User::Instructor(instructor.id),
-    };
-
-    let token = encode(&Header::default(), &claims, &KEYS.encoding).unwrap();
-
-    let response = AuthBody { token }.ok_response("logged in as instructor successfully");
+    let response = auth_body.ok_response("logged in as instructor successfully");
 
     Ok(response)
 }
@@ -208,12 +176,8 @@ async fn login_with_creds(
 )]
 async fn login_with_token(
     State(repo): State<DynInstructorsRepo>,
-    claimes: Claims,
+    RequireInstructor(instructor_id): RequireInstructor,
 ) -> Result<AppResponse<'static, Instructor>, ApiError> {
-    let User::Instructor(instructor_id) = claimes.user else {
-        return Err(AuthError::UnauthorizedAccess.into());
-    } ;
-
     let instructor = repo.get_by_id(instructor_id).await?;
     let response = instructor.ok_response("logged in as instructor successfully");
 
@@ -223,6 +187,7 @@ async fn login_with_token(
 #[utoipa::path(
     get,
     path = "/instructors/{instructor_id}/subjects",
+    params(ListQuery),
     responses(
         (status = OK, body = SubjectsListResponse)
     ),
@@ -231,21 +196,17 @@ async fn login_with_token(
 async fn get_all_subjects_for_one(
     State(repo): State<DynSubjectsRepo>,
     Path(instructor_id): Path<Uuid>,
-    claimes: Claims,
+    _: RequireSelfOrAdmin<Instructor>,
+    Paginated(query): Paginated,
 ) -> Result<AppResponse<'static, Vec<Subject>>, ApiError> {
-    match claimes.user {
-        User::Admin(_) => {}
-        User::Instructor(id) if id == instructor_id => {}
-        _ => {
-            return Err(AuthError::UnauthorizedAccess.into());
-        }
-    };
-
     let subjects = repo
-        .get(SubjectsFilter {
-            instructor_id: Some(instructor_id),
-            ..Default::default()
-        })
+        .get(
+            SubjectsFilter {
+                instructor_id: Some(instructor_id),
+                ..Default::default()
+            },
+            query,
+        )
         .await?;
     let response = subjects.ok_response("retreived associated subjects successfully");
 
@@ -263,25 +224,20 @@ async fn get_all_subjects_for_one(
 async fn get_one_subject_for_one(
     State(repo): State<DynSubjectsRepo>,
     Path((instructor_id, subject_id)): Path<(Uuid, Uuid)>,
-    claimes: Claims,
+    _: RequireSelfOrAdmin<Instructor>,
 ) -> Result<AppResponse<'static, Subject>, ApiError> {
-    match claimes.user {
-        User::Admin(_) => {}
-        User::Instructor(id) if id == instructor_id => {}
-        _ => {
-            return Err(AuthError::UnauthorizedAccess.into());
-        }
-    };
-
     let subjects = repo
-        .get(SubjectsFilter {
-            id: Some(subject_id),
-            instructor_id: Some(instructor_id),
-            ..Default::default()
-        })
+        .get(
+            SubjectsFilter {
+                id: Some(subject_id),
+                instructor_id: Some(instructor_id),
+                ..Default::default()
+            },
+            ListQuery::default(),
+        )
         .await?;
 
-    let Some(subject) = subjects.into_iter().next() else {
+    let Some(subject) = subjects.items.into_iter().next() else {
         return Err(RepoError::NotFound("subject".to_owned()).into());
     };
 
@@ -301,12 +257,8 @@ async fn get_one_subject_for_one(
 async fn put_one_subject_to_one(
     State(repo): State<DynSubjectsRepo>,
     Path((instructor_id, subject_id)): Path<(Uuid, Uuid)>,
-    claimes: Claims,
+    _: RequireAdmin,
 ) -> Result<AppResponse<'static, Subject>, ApiError> {
-    let User::Admin(_) = claimes.user else {
-        return Err(AuthError::UnauthorizedAccess.into());
-    };
-
     let subject = repo
         .update(
             subject_id,
@@ -333,12 +285,8 @@ async fn put_one_subject_to_one(
 async fn delete_one_subject_from_one(
     State(repo): State<DynSubjectsRepo>,
     Path((instructor_id, subject_id)): Path<(Uuid, Uuid)>,
-    claimes: Claims,
+    _: RequireAdmin,
 ) -> Result<AppResponse<'static, Subject>, ApiError> {
-    let User::Admin(_) = claimes.user else {
-        return Err(AuthError::UnauthorizedAccess.into());
-    };
-
     let subject = repo.get_by_id(subject_id).await?;
 
     if let Some(instructor) = subject.instructor {
@@ -376,13 +324,9 @@ async fn delete_one_subject_from_one(
 async fn upload_image(
     State(repo): State<DynInstructorsRepo>,
     Path(instructor_id): Path<Uuid>,
-    claimes: Claims,
+    _: RequireAdmin,
     mut multipart: Multipart,
 ) -> Result<AppResponse<'static, Instructor>, ApiError> {
-    let User::Admin(_) = claimes.user else {
-        return Err(AuthError::UnauthorizedAccess.into());
-    };
-
     let Ok(Some(field)) = multipart.next_field().await else {
         return Err(ApiError::Internal);
     };
@@ -1,6 +1,37 @@
 pub mod admins;
 pub mod attendances;
 pub mod attendees;
+pub mod audit_log;
+pub mod auth;
+pub mod class_sessions;
 pub mod config;
+pub mod face_samples;
+pub mod imports;
 pub mod instructors;
+pub mod reports;
 pub mod subjects;
+pub mod terms;
+
+use axum::{middleware, Router};
+
+use crate::{app, audit};
+
+/// every route of the api, served under `/api`.
+pub(crate) fn api() -> Router<app::State> {
+    Router::new()
+        .merge(config::routes())
+        .merge(auth::routes())
+        .merge(admins::routes())
+        .merge(instructors::routes())
+        .merge(attendances::routes())
+        .merge(attendees::routes())
+        .merge(face_samples::routes())
+        .merge(subjects::routes())
+        .merge(class_sessions::routes())
+        .merge(terms::routes())
+        .merge(reports::routes())
+        .merge(imports::routes())
+        .merge(audit_log::routes())
+        // the repos record the changes made by the request in the audit log
+        .layer(middleware::from_fn(audit::scope))
+}
@@ -0,0 +1,120 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use ams_logic::prelude::*;
+
+use crate::{
+    app::{self, DynReportsRepo, DynSettingsService},
+    error::ApiError,
+    policy::RequireInstructorOf,
+    response::AppResponseDataExt,
+};
+
+pub(crate) fn routes() -> Router<app::State> {
+    Router::new().route("/subjects/:id/report", get(get_one_for_one_subject))
+}
+
+#[derive(Deserialize, ToSchema, Default, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum ReportFormat {
+    #[default]
+    Json,
+    Csv,
+    Xlsx,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportQuery {
+    #[serde(default)]
+    format: ReportFormat,
+    /// first local session date, inclusive.
+    #[param(value_type = Option<String>, format = Date)]
+    from: Option<NaiveDate>,
+    /// last local session date, inclusive.
+    #[param(value_type = Option<String>, format = Date)]
+    to: Option<NaiveDate>,
+    /// absence percentage between 0 and 100 above which attendees are flagged, defaults to the `absenceWarningThreshold` setting.
+    threshold: Option<f64>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/subjects/{subject_id}/report",
+    params(ReportQuery),
+    responses(
+        (status = OK, description = "the report in the requested format", content(
+            ("application/json" = SubjectReportResponse),
+            ("text/csv" = String),
+            ("application/vnd.openxmlformats-officedocument.spreadsheetml.sheet" = Vec<u8>)
+        ))
+    ),
+    security(("api_jwt_token" = []))
+)]
+pub async fn get_one_for_one_subject(
+    State(repo): State<DynReportsRepo>,
+    State(settings_service): State<DynSettingsService>,
+    Path(subject_id): Path<Uuid>,
+    _: RequireInstructorOf<Subject>,
+    Query(ReportQuery {
+        format,
+        from,
+        to,
+        threshold,
+    }): Query<ReportQuery>,
+) -> Result<Response, ApiError> {
+    if threshold.is_some_and(|threshold| !(0.0..=100.0).contains(&threshold)) {
+        return Err(ApiError::Validation {
+            message: "the threshold must be between 0 and 100".to_owned(),
+        });
+    }
+
+    let report = repo
+        .subject_report(
+            subject_id,
+            ReportOptions {
+                from,
+                to,
+                absence_threshold: threshold
+                    .unwrap_or(settings_service.current().absence_warning_threshold),
+            },
+        )
+        .await?;
+
+    let (content_type, extension, body) = match format {
+        ReportFormat::Json => {
+            return Ok(report
+                .ok_response("generated the subject report successfully")
+                .into_response())
+        }
+        ReportFormat::Csv => ("text/csv", "csv", report.to_csv()?),
+        ReportFormat::Xlsx => (
+            "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+            "xlsx",
+            report.to_xlsx()?,
+        ),
+    };
+
+    let disposition = format!(
+        "attachment; filename=\"{}-report.{extension}\"",
+        report.subject_name.replace(['"', '\\'], "")
+    );
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_owned()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    )
+        .into_response())
+}
@@ -1,19 +1,23 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     routing::{delete, get, post},
     Json, Router,
 };
+use serde::Deserialize;
+use utoipa::IntoParams;
 use uuid::Uuid;
 
 use ams_logic::prelude::*;
 
 use crate::{
     app::{self, DynSubjectsRepo},
-    response::AppResponseDataExt,
+    response::{AppResponseDataExt, AppResponsePageExt},
 };
 use crate::{
-    auth::{AuthError, Claims, User},
+    auth::Claims,
     error::ApiError,
+    pagination::Paginated,
+    policy::{RequireAdmin, RequireInstructorOf},
     response::{AppResponse, AppResponseMsgExt},
 };
 
@@ -24,7 +28,11 @@ pub(crate) fn routes() -> Router<app::State> {
             "/subjects/:id",
             get(get_one).patch(update_one).delete(delete_one),
         )
-        .route("/subjects/:id/attendees", get(get_all_attendees))
+        .route(
+            "/subjects/:id/attendees",
+            get(get_all_attendees).put(update_roster),
+        )
+        .route("/subjects/:id/attendees/copy", post(copy_roster))
         .route("/subjects/:id/subject_dates", post(add_one_subject_date))
         .route(
             "/subjects/:id/subject_dates/:id",
@@ -32,9 +40,18 @@ pub(crate) fn routes() -> Router<app::State> {
         )
 }
 
+/// narrows the listed subjects down.
+#[derive(Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct SubjectsQuery {
+    /// only subjects of this term.
+    term_id: Option<Uuid>,
+}
+
 #[utoipa::path(
     get,
     path = "/subjects",
+    params(SubjectsQuery, ListQuery),
     responses(
         (status = OK, body = SubjectsListResponse)
     ),
@@ -42,13 +59,19 @@ pub(crate) fn routes() -> Router<app::State> {
 )]
 async fn get_all(
     State(repo): State<DynSubjectsRepo>,
-    claims: Claims,
+    _: RequireAdmin,
+    Query(SubjectsQuery { term_id }): Query<SubjectsQuery>,
+    Paginated(query): Paginated,
 ) -> Result<AppResponse<'static, Vec<Subject>>, ApiError> {
-    let User::Admin(_) = claims.user else {
-        return Err(AuthError::UnauthorizedAccess.into());
-    };
-
-    let subjects = repo.get(SubjectsFilter::default()).await?;
+    let subjects = repo
+        .get(
+            SubjectsFilter {
+                term_id,
+                ..Default::default()
+            },
+            query,
+        )
+        .await?;
     let response = subjects.ok_response("retreived all subjects successfully");
 
     Ok(response)
@@ -65,13 +88,9 @@ async fn get_all(
 )]
 async fn create_one(
     State(repo): State<DynSubjectsRepo>,
-    claims: Claims,
+    _: RequireAdmin,
     Json(subject): Json<CreateSubject>,
 ) -> Result<AppResponse<'static, Subject>, ApiError> {
-    let User::Admin(_) = claims.user else {
-        return Err(AuthError::UnauthorizedAccess.into());
-    };
-
     let subjects = repo.create(subject).await?;
     let response = subjects.ok_response("retreived all subjects successfully");
 
@@ -108,14 +127,10 @@ async fn get_one(
 )]
 async fn update_one(
     State(repo): State<DynSubjectsRepo>,
-    claims: Claims,
+    _: RequireAdmin,
     Path(subject_id): Path<Uuid>,
     Json(subject): Json<UpdateSubject>,
 ) -> Result<AppResponse<'static, Subject>, ApiError> {
-    let User::Admin(_) = claims.user else {
-        return Err(AuthError::UnauthorizedAccess.into());
-    };
-
     let subjects = repo.update(subject_id, subject).await?;
     let response = subjects.ok_response("updated one subject successfully");
 
@@ -132,13 +147,9 @@ async fn update_one(
 )]
 async fn delete_one(
     State(repo): State<DynSubjectsRepo>,
-    claims: Claims,
+    _: RequireAdmin,
     Path(subject_id): Path<Uuid>,
 ) -> Result<AppResponse<'static, ()>, ApiError> {
-    let User::Admin(_) = claims.user else {
-        return Err(AuthError::UnauthorizedAccess.into());
-    };
-
     repo.delete_by_id(subject_id).await?;
     let response = "deleted one subject successfully".response();
 
@@ -148,6 +159,7 @@ async fn delete_one(
 #[utoipa::path(
     get,
     path = "/subjects/{subject_id}/attendees",
+    params(ListQuery),
     responses(
         (status = OK, body = AttendeesListResponse)
     ),
@@ -156,19 +168,61 @@ async fn delete_one(
 async fn get_all_attendees(
     State(repo): State<DynSubjectsRepo>,
     Path(subject_id): Path<Uuid>,
-    claims: Claims,
+    _: RequireInstructorOf<Subject>,
+    Paginated(query): Paginated,
 ) -> Result<AppResponse<'static, Vec<Attendee>>, ApiError> {
-    if let User::Attendee(_) = claims.user {
-        return Err(AuthError::UnauthorizedAccess.into());
-    };
-
-    let subjects = repo.get_all_attendees(subject_id).await?;
+    let subjects = repo.get_all_attendees(subject_id, query).await?;
 
     let response = subjects.ok_response("retreived all attendees successfully");
 
     Ok(response)
 }
 
+/// replaces or merges into the roster all at once, either every attendee is
+/// enrolled or none is.
+#[utoipa::path(
+    put,
+    path = "/subjects/{subject_id}/attendees",
+    request_body = UpdateRoster,
+    responses(
+        (status = OK, body = RosterDiffResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+async fn update_roster(
+    State(repo): State<DynSubjectsRepo>,
+    _: RequireAdmin,
+    Path(subject_id): Path<Uuid>,
+    Json(roster): Json<UpdateRoster>,
+) -> Result<AppResponse<'static, RosterDiff>, ApiError> {
+    let diff = repo.update_roster(subject_id, roster).await?;
+    let response = diff.ok_response("updated the roster successfully");
+
+    Ok(response)
+}
+
+/// applies the roster of another subject, like the roster update.
+#[utoipa::path(
+    post,
+    path = "/subjects/{subject_id}/attendees/copy",
+    request_body = CopyRoster,
+    responses(
+        (status = OK, body = RosterDiffResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+async fn copy_roster(
+    State(repo): State<DynSubjectsRepo>,
+    _: RequireAdmin,
+    Path(subject_id): Path<Uuid>,
+    Json(copy): Json<CopyRoster>,
+) -> Result<AppResponse<'static, RosterDiff>, ApiError> {
+    let diff = repo.copy_roster(subject_id, copy).await?;
+    let response = diff.ok_response("copied the roster successfully");
+
+    Ok(response)
+}
+
 #[utoipa::path(
     post,
     path = "/subjects/{subject_id}/subject_dates",
@@ -180,14 +234,10 @@ async fn get_all_attendees(
 )]
 async fn add_one_subject_date(
     State(repo): State<DynSubjectsRepo>,
-    claims: Claims,
+    _: RequireAdmin,
     Path(subject_id): Path<Uuid>,
     Json(subject_date): Json<CreateSubjectDate>,
 ) -> Result<AppResponse<'static, SubjectDate>, ApiError> {
-    let User::Admin(_) = claims.user else {
-        return Err(AuthError::UnauthorizedAccess.into());
-    };
-
     let subject_date = repo.add_subject_date(subject_id, subject_date).await?;
     let response = subject_date.ok_response("added one subject date successfully");
 
@@ -204,13 +254,9 @@ async fn add_one_subject_date(
 )]
 async fn delete_one_subject_date(
     State(repo): State<DynSubjectsRepo>,
-    claims: Claims,
+    _: RequireAdmin,
     Path((subject_id, subject_date_id)): Path<(Uuid, Uuid)>,
 ) -> Result<AppResponse<'static, ()>, ApiError> {
-    let User::Admin(_) = claims.user else {
-        return Err(AuthError::UnauthorizedAccess.into());
-    };
-
     repo.remove_subject_date(subject_id, subject_date_id)
         .await?;
 
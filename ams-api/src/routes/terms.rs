@@ -0,0 +1,167 @@
+use axum::{
+    extract::{Path, State},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use uuid::Uuid;
+
+use ams_logic::prelude::*;
+
+use crate::{
+    app::{self, DynTermsRepo},
+    auth::Claims,
+    error::ApiError,
+    pagination::Paginated,
+    policy::RequireAdmin,
+    response::{AppResponse, AppResponseDataExt, AppResponseMsgExt, AppResponsePageExt},
+};
+
+pub(crate) fn routes() -> Router<app::State> {
+    Router::new()
+        .route("/terms", get(get_all).post(create_one))
+        .route(
+            "/terms/:id",
+            get(get_one).patch(update_one).delete(delete_one),
+        )
+        .route("/terms/:id/holidays", post(add_one_holiday))
+        .route("/terms/:id/holidays/:id", delete(delete_one_holiday))
+}
+
+#[utoipa::path(
+    get,
+    path = "/terms",
+    params(ListQuery),
+    responses(
+        (status = OK, body = TermsListResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+pub async fn get_all(
+    State(repo): State<DynTermsRepo>,
+    _: Claims,
+    Paginated(query): Paginated,
+) -> Result<AppResponse<'static, Vec<Term>>, ApiError> {
+    let terms = repo.get(query).await?;
+    let response = terms.ok_response("retreived all terms successfully");
+
+    Ok(response)
+}
+
+#[utoipa::path(
+    post,
+    path = "/terms",
+    request_body = CreateTerm,
+    responses(
+        (status = CREATED, body = TermResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+pub async fn create_one(
+    State(repo): State<DynTermsRepo>,
+    _: RequireAdmin,
+    Json(term): Json<CreateTerm>,
+) -> Result<AppResponse<'static, Term>, ApiError> {
+    let term = repo.create(term).await?;
+    let response = term.create_response("created one term successfully");
+
+    Ok(response)
+}
+
+#[utoipa::path(
+    get,
+    path = "/terms/{term_id}",
+    responses(
+        (status = OK, body = TermResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+pub async fn get_one(
+    State(repo): State<DynTermsRepo>,
+    _: Claims,
+    Path(term_id): Path<Uuid>,
+) -> Result<AppResponse<'static, Term>, ApiError> {
+    let term = repo.get_by_id(term_id).await?;
+    let response = term.ok_response("retreived one term successfully");
+
+    Ok(response)
+}
+
+#[utoipa::path(
+    patch,
+    path = "/terms/{term_id}",
+    request_body = UpdateTerm,
+    responses(
+        (status = OK, body = TermResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+pub async fn update_one(
+    State(repo): State<DynTermsRepo>,
+    _: RequireAdmin,
+    Path(term_id): Path<Uuid>,
+    Json(term): Json<UpdateTerm>,
+) -> Result<AppResponse<'static, Term>, ApiError> {
+    let term = repo.update(term_id, term).await?;
+    let response = term.ok_response("updated one term successfully");
+
+    Ok(response)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/terms/{term_id}",
+    responses(
+        (status = OK)
+    ),
+    security(("api_jwt_token" = []))
+)]
+pub async fn delete_one(
+    State(repo): State<DynTermsRepo>,
+    _: RequireAdmin,
+    Path(term_id): Path<Uuid>,
+) -> Result<AppResponse<'static, ()>, ApiError> {
+    repo.delete_by_id(term_id).await?;
+    let response = "deleted one term successfully".response();
+
+    Ok(response)
+}
+
+#[utoipa::path(
+    post,
+    path = "/terms/{term_id}/holidays",
+    request_body = CreateTermHoliday,
+    responses(
+        (status = CREATED, body = TermHolidayResponse)
+    ),
+    security(("api_jwt_token" = []))
+)]
+pub async fn add_one_holiday(
+    State(repo): State<DynTermsRepo>,
+    _: RequireAdmin,
+    Path(term_id): Path<Uuid>,
+    Json(holiday): Json<CreateTermHoliday>,
+) -> Result<AppResponse<'static, TermHoliday>, ApiError> {
+    let holiday = repo.add_holiday(term_id, holiday).await?;
+    let response = holiday.create_response("added one holiday successfully");
+
+    Ok(response)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/terms/{term_id}/holidays/{holiday_id}",
+    responses(
+        (status = OK)
+    ),
+    security(("api_jwt_token" = []))
+)]
+pub async fn delete_one_holiday(
+    State(repo): State<DynTermsRepo>,
+    _: RequireAdmin,
+    Path((term_id, holiday_id)): Path<(Uuid, Uuid)>,
+) -> Result<AppResponse<'static, ()>, ApiError> {
+    repo.remove_holiday(term_id, holiday_id).await?;
+    let response = "deleted one holiday successfully".response();
+
+    Ok(response)
+}
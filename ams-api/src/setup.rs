@@ -1,15 +1,18 @@
 use tracing::log;
-use tracing_subscriber::{filter, prelude::*};
+use tracing_subscriber::{
+    filter::{self, LevelFilter},
+    prelude::*,
+};
 
 /// setup tracing for monitoring events.
-pub(crate) fn tracing() {
+pub(crate) fn tracing(level: LevelFilter) {
     let layer1 = tracing_subscriber::fmt::layer()
         .pretty()
         .with_test_writer()
         .with_filter(filter::filter_fn(|meta| {
             meta.target().contains("api") || meta.target().contains("logic")
         }))
-        .with_filter(config::LOG_LEVEL);
+        .with_filter(level);
 
     let layer2 = tracing_subscriber::fmt::layer()
         .pretty()
@@ -23,7 +26,7 @@ pub(crate) fn tracing() {
                 || meta.target().contains("sqlx")
                 || meta.target().contains("tower_http")
         }))
-        .with_filter(config::LOG_LEVEL);
+        .with_filter(level);
 
     tracing_subscriber::registry()
         .with(layer1)
@@ -33,7 +36,10 @@ pub(crate) fn tracing() {
 
 use sea_orm::{ConnectOptions, Database, DatabaseConnection, DbErr};
 
-use crate::app::config;
+use ams_logic::audit_log::AuditContext;
+use chrono::{Duration, Utc};
+
+use crate::app::{config, DynClassSessionsRepo, DynSettingsService};
 
 /// connects to postgres database.
 ///
@@ -42,7 +48,7 @@ use crate::app::config;
 /// This function will return an error if the connection can't be established.
 #[tracing::instrument(err)]
 pub(crate) async fn database_connection() -> Result<DatabaseConnection, DbErr> {
-    let mut opt = ConnectOptions::new(config::DATABASE_URL.to_owned());
+    let mut opt = ConnectOptions::new(config::config().database.url.to_owned());
     opt.sqlx_logging_level(log::LevelFilter::Debug);
 
     tracing::info!("establishing database connection");
@@ -50,3 +56,46 @@ pub(crate) async fn database_connection() -> Result<DatabaseConnection, DbErr> {
 
     Database::connect(opt).await
 }
+
+/// spawns a task that generates the upcoming class sessions of every subject
+/// on the `class_sessions.cron` schedule, once right away and then on every tick.
+pub(crate) fn class_sessions_generator(repo: DynClassSessionsRepo) {
+    tokio::spawn(async move {
+        let config = config::config();
+        let timezone = config.attendance.timezone;
+        let mut upcoming = config.class_sessions.cron.upcoming(timezone);
+
+        loop {
+            let today = Utc::now().with_timezone(&timezone).date_naive();
+            let until = today + Duration::days(config.class_sessions.ahead);
+
+            match AuditContext::system()
+                .scope(repo.generate_all(today, until))
+                .await
+            {
+                Ok(generated) => {
+                    tracing::info!("generated {generated} class sessions until {until}")
+                }
+                Err(error) => tracing::error!("failed to generate class sessions: {error}"),
+            }
+
+            let Some(next) = upcoming.next() else {
+                break;
+            };
+            let wait = (next.with_timezone(&Utc) - Utc::now())
+                .to_std()
+                .unwrap_or_default();
+            tokio::time::sleep(wait).await;
+        }
+    });
+}
+
+/// spawns a task that reloads the cached settings whenever they are changed,
+/// by this or any other instance.
+pub(crate) fn settings_listener(settings: DynSettingsService) {
+    tokio::spawn(async move {
+        if let Err(error) = settings.listen().await {
+            tracing::error!("failed to listen for settings changes: {error}");
+        }
+    });
+}
@@ -0,0 +1,108 @@
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+use chrono_tz::Tz;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use uuid::Uuid;
+
+#[derive(Parser, Debug)]
+#[command(version, about = "attendance management system maintenance")]
+pub(crate) struct Cli {
+    /// the postgres connection url.
+    #[arg(long, env = "DATABASE_URL", hide_env_values = true)]
+    pub database_url: String,
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum Command {
+    /// manage the admin accounts.
+    #[command(subcommand)]
+    Admins(AdminsCommand),
+    /// manage the attendees.
+    #[command(subcommand)]
+    Attendees(AttendeesCommand),
+    /// manage the class sessions.
+    #[command(subcommand)]
+    Sessions(SessionsCommand),
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum AdminsCommand {
+    /// create an admin, prompting for the password when omitted.
+    Create {
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        email: String,
+        #[arg(long)]
+        password: Option<String>,
+    },
+    /// list the admins.
+    List,
+    /// set a new password and revoke the admin's sessions.
+    ResetPassword {
+        email: String,
+        /// prompted for when omitted.
+        #[arg(long)]
+        password: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum AttendeesCommand {
+    /// create the attendees of a csv file with the `name`, `email`,
+    /// `password` and `number` columns, skipping the rows that fail.
+    Import { file: PathBuf },
+    /// embed every face sample again, after the face recognition model
+    /// changed.
+    Reembed {
+        /// only the samples of this attendee.
+        #[arg(long)]
+        attendee: Option<Uuid>,
+        #[command(flatten)]
+        face_recognition: FaceRecArgs,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum SessionsCommand {
+    /// generate the class sessions of every subject in a date range, the
+    /// existing ones are kept.
+    Regenerate {
+        /// the first day, today by default.
+        #[arg(long)]
+        from: Option<NaiveDate>,
+        /// how many days from the first day.
+        #[arg(long, env = "CLASS_SESSIONS_AHEAD", default_value_t = 14)]
+        days: i64,
+        /// the timezone subject dates are scheduled in.
+        #[arg(long, env = "TIMEZONE", default_value = "Africa/Cairo")]
+        timezone: Tz,
+    },
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct FaceRecArgs {
+    /// where the face sample images are stored.
+    #[arg(long, env = "ASSETS_DIR", default_value = "assets")]
+    pub assets_dir: PathBuf,
+    #[arg(long, env = "FACEREC_BACKEND", value_enum, default_value_t = FaceRecBackendKind::Http)]
+    pub facerec_backend: FaceRecBackendKind,
+    /// the face recognition service, required by the `http` backend.
+    #[arg(long, env = "FACEREC_URL", default_value = "")]
+    pub facerec_url: String,
+    /// the onnx model, required by the `onnx` backend.
+    #[arg(long, env = "FACEREC_ONNX_MODEL", default_value = "")]
+    pub facerec_onnx_model: String,
+    /// the square input size of the onnx model.
+    #[arg(long, env = "FACEREC_ONNX_SIZE", default_value_t = 160)]
+    pub facerec_onnx_size: u32,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum FaceRecBackendKind {
+    Http,
+    Onnx,
+}
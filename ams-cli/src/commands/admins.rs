@@ -0,0 +1,66 @@
+use std::sync::Arc;
+
+use ams_logic::{
+    admins::{AdminsRepo, AdminsRepoTrait, CreateAdmin},
+    pagination::ListQuery,
+    sessions::{SessionsRepo, SessionsRepoTrait, UserKind},
+};
+use sea_orm::DatabaseConnection;
+
+use super::prompt_password;
+use crate::cli::AdminsCommand;
+
+pub(crate) async fn run(
+    db: DatabaseConnection,
+    command: AdminsCommand,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let db = Arc::new(db);
+    let repo = AdminsRepo(db.clone());
+
+    match command {
+        AdminsCommand::Create {
+            name,
+            email,
+            password,
+        } => {
+            let password = match password {
+                Some(password) => password,
+                None => prompt_password()?,
+            };
+            let admin = repo
+                .create(CreateAdmin {
+                    name,
+                    email,
+                    password,
+                })
+                .await?;
+            println!("created  {}  {}", admin.id, admin.email);
+        }
+        AdminsCommand::List => {
+            let admins = repo.get_all(ListQuery::default()).await?;
+            for admin in admins.items {
+                println!(
+                    "{}  {}  {}  created at {}",
+                    admin.id, admin.name, admin.email, admin.create_at
+                );
+            }
+        }
+        AdminsCommand::ResetPassword { email, password } => {
+            let admin = repo.get_by_email(email).await?;
+            let password = match password {
+                Some(password) => password,
+                None => prompt_password()?,
+            };
+            repo.update_password(admin.id, password).await?;
+            let revoked = SessionsRepo(db)
+                .revoke_all(UserKind::Admin, admin.id)
+                .await?;
+            println!(
+                "reset the password of {}, revoked {revoked} sessions",
+                admin.email
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use std::{path::PathBuf, sync::Arc};
+
+use ams_facerec::{EmbeddedFace, Embedding, FaceRecognitionBackend, FaceRecognizer};
+use ams_logic::{
+    attendees::{AttendeesRepo, AttendeesRepoTrait, CreateAttendee},
+    face_samples::{FaceSamplesRepo, FaceSamplesRepoTrait, UpdateFaceSampleEmbedding},
+    pagination::ListQuery,
+};
+use sea_orm::DatabaseConnection;
+
+use crate::cli::{AttendeesCommand, FaceRecArgs, FaceRecBackendKind};
+
+pub(crate) async fn run(
+    db: DatabaseConnection,
+    command: AttendeesCommand,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let db = Arc::new(db);
+
+    match command {
+        AttendeesCommand::Import { file } => import(db, file).await,
+        AttendeesCommand::Reembed {
+            attendee,
+            face_recognition,
+        } => {
+            let attendees_repo = AttendeesRepo::new(db.clone(), &face_recognition.assets_dir);
+            let attendee_ids = match attendee {
+                Some(id) => vec![attendees_repo.get_by_id(id).await?.id],
+                None => attendees_repo
+                    .get_all(ListQuery::default())
+                    .await?
+                    .items
+                    .into_iter()
+                    .map(|attendee| attendee.id)
+                    .collect(),
+            };
+            let samples_repo = FaceSamplesRepo::new(db, &face_recognition.assets_dir);
+            let fr = face_recognizer(&face_recognition)?;
+
+            let (mut embedded, mut failed) = (0, 0);
+            for attendee_id in attendee_ids {
+                for sample in samples_repo
+                    .get_all(attendee_id, ListQuery::default())
+                    .await?
+                    .items
+                {
+                    let result = async {
+                        let image = samples_repo.image(attendee_id, sample.id).await?;
+                        let EmbeddedFace { embedding, quality } = fr.enroll(&image).await?;
+                        samples_repo
+                            .update_embedding(
+                                attendee_id,
+                                sample.id,
+                                UpdateFaceSampleEmbedding {
+                                    embedding: embedding.normalized(),
+                                    quality,
+                                },
+                            )
+                            .await?;
+                        Ok::<_, Box<dyn std::error::Error>>(())
+                    }
+                    .await;
+
+                    match result {
+                        Ok(()) => embedded += 1,
+                        Err(error) => {
+                            failed += 1;
+                            eprintln!("failed   sample {} of {attendee_id}: {error}", sample.id);
+                        }
+                    }
+                }
+            }
+            println!("re-embedded {embedded} face samples, {failed} failed");
+
+            Ok(())
+        }
+    }
+}
+
+/// creates the attendees row by row, a failing row is reported and skipped.
+async fn import(
+    db: Arc<DatabaseConnection>,
+    file: PathBuf,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // the assets directory is only used for face recognition
+    let repo = AttendeesRepo::new(db, "assets");
+    let mut reader = csv::Reader::from_path(file)?;
+
+    let (mut created, mut skipped) = (0, 0);
+    for (index, row) in reader.deserialize::<CreateAttendee>().enumerate() {
+        // the header is the first line
+        let line = index + 2;
+        let result = match row {
+            Ok(attendee) => repo
+                .create(attendee)
+                .await
+                .map_err(|error| error.to_string()),
+            Err(error) => Err(error.to_string()),
+        };
+
+        match result {
+            Ok(attendee) => {
+                created += 1;
+                println!("created  {}  {}", attendee.id, attendee.email);
+            }
+            Err(error) => {
+                skipped += 1;
+                eprintln!("skipped  line {line}: {error}");
+            }
+        }
+    }
+    println!("imported {created} attendees, skipped {skipped}");
+
+    Ok(())
+}
+
+fn face_recognizer(
+    args: &FaceRecArgs,
+) -> Result<Box<dyn FaceRecognitionBackend + Send + Sync>, Box<dyn std::error::Error>> {
+    match args.facerec_backend {
+        FaceRecBackendKind::Http => {
+            if args.facerec_url.is_empty() {
+                return Err("`--facerec-url` is required by the `http` backend".into());
+            }
+            Ok(Box::new(FaceRecognizer::new(&args.facerec_url)))
+        }
+        #[cfg(feature = "onnx")]
+        FaceRecBackendKind::Onnx => {
+            if args.facerec_onnx_model.is_empty() {
+                return Err("`--facerec-onnx-model` is required by the `onnx` backend".into());
+            }
+            Ok(Box::new(ams_facerec::OnnxFaceRecognizer::new(
+                &args.facerec_onnx_model,
+                args.facerec_onnx_size,
+            )?))
+        }
+        #[cfg(not(feature = "onnx"))]
+        FaceRecBackendKind::Onnx => Err("built without the `onnx` feature".into()),
+    }
+}
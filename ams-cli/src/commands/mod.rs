@@ -0,0 +1,17 @@
+pub(crate) mod admins;
+pub(crate) mod attendees;
+pub(crate) mod sessions;
+
+/// reads a password from the terminal without echoing it, asking twice so a
+/// typo isn't saved.
+fn prompt_password() -> Result<String, Box<dyn std::error::Error>> {
+    let password = rpassword::prompt_password("password: ")?;
+    if password.is_empty() {
+        return Err("the password can't be empty".into());
+    }
+    if rpassword::prompt_password("confirm password: ")? != password {
+        return Err("the passwords don't match".into());
+    }
+
+    Ok(password)
+}
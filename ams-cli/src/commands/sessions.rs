@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use ams_logic::class_sessions::{ClassSessionsRepo, ClassSessionsRepoTrait};
+use chrono::{Duration, Utc};
+use sea_orm::DatabaseConnection;
+
+use crate::cli::SessionsCommand;
+
+pub(crate) async fn run(
+    db: DatabaseConnection,
+    command: SessionsCommand,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match command {
+        SessionsCommand::Regenerate {
+            from,
+            days,
+            timezone,
+        } => {
+            if days <= 0 {
+                return Err("`--days` must be positive".into());
+            }
+            let from = from.unwrap_or_else(|| Utc::now().with_timezone(&timezone).date_naive());
+            let to = from + Duration::days(days);
+
+            let generated = ClassSessionsRepo::new(Arc::new(db), timezone)
+                .generate_all(from, to)
+                .await?;
+            println!("generated {generated} class sessions from {from} to {to}");
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+mod cli;
+mod commands;
+
+use clap::Parser;
+use dotenvy::dotenv;
+use sea_orm::Database;
+
+use crate::cli::{Cli, Command};
+
+#[tokio::main]
+async fn main() {
+    // load envirnment variables from .env
+    dotenv().ok();
+
+    let cli = Cli::parse();
+
+    if let Err(error) = run(cli).await {
+        eprintln!("error: {error}");
+        std::process::exit(1);
+    }
+}
+
+async fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
+    let db = Database::connect(cli.database_url).await?;
+
+    match cli.command {
+        Command::Admins(command) => commands::admins::run(db, command).await,
+        Command::Attendees(command) => commands::attendees::run(db, command).await,
+        Command::Sessions(command) => commands::sessions::run(db, command).await,
+    }
+}
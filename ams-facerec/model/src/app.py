@@ -3,9 +3,9 @@
 import torch
 from facenet_pytorch import MTCNN, InceptionResnetV1
 
-from PIL import Image
+from PIL import Image, UnidentifiedImageError
 
-from fastapi import FastAPI, UploadFile, File
+from fastapi import FastAPI, UploadFile, File, HTTPException
 import uvicorn
 
 import os, argparse, shutil, io
@@ -34,13 +34,14 @@ parent_exe_dir = Path(__file__).parent.absolute()
 device: torch.device
 
 mtcnn: MTCNN
+mtcnn_all: MTCNN
 resnet: InceptionResnetV1
 
 classifier_path: str
-classifier: Classifier
+classifier: Classifier | None = None
 
 def initialize_variables():
-    global mtcnn, resnet, classifier, classifier_path, device
+    global mtcnn, mtcnn_all, resnet, classifier, classifier_path, device
     device_env = os.environ.get('COMPUTING_DEVICE', 'cpu')
     if device_env == 'cuda':
         if not torch.cuda.is_available():
@@ -52,6 +53,7 @@ def initialize_variables():
         device = torch.device('cpu')
     print(f'using device: {device}')
     mtcnn = MTCNN(post_process=False).to(device).eval()
+    mtcnn_all = MTCNN(post_process=False, keep_all=True).to(device).eval()
     print(f'loaded MTCNN')
     resnet = InceptionResnetV1(pretrained='vggface2').to(device).eval()
     print(f'loaded InceptionResnetV1')
@@ -64,17 +66,62 @@ def initialize_variables():
 
 app = FastAPI()
 
+def open_image(image_path) -> Image.Image:
+    try:
+        return Image.open(image_path).convert('RGB')
+    except (UnidentifiedImageError, OSError) as error:
+        raise HTTPException(422, detail={'error': 'invalid_image', 'message': str(error)})
+
+def detect_single_face(image: Image.Image):
+    global mtcnn
+    face, probability = mtcnn(image, return_prob=True)
+    boxes, _ = mtcnn.detect(image)
+    count = 0 if boxes is None else len(boxes)
+    if count == 0 or face is None:
+        raise HTTPException(422, detail={'error': 'no_face'})
+    if count > 1:
+        raise HTTPException(422, detail={'error': 'multiple_faces', 'count': count})
+    return face, float(probability)
+
 def embed_image(image_path) -> List[float]:
+    return enroll_image(image_path)['embedding']
+
+def enroll_image(image_path) -> dict:
     global mtcnn, resnet
-    image = Image.open(image_path).convert('RGB')
-    image = mtcnn(image)
-    embeddings = resnet(image.unsqueeze(0)).detach().cpu().numpy().squeeze()
-    return embeddings.tolist()
+    image = open_image(image_path)
+    face, probability = detect_single_face(image)
+    embeddings = resnet(face.unsqueeze(0)).detach().cpu().numpy().squeeze()
+    return {'embedding': embeddings.tolist(), 'quality': probability}
+
+def detect_faces(image_path) -> List[dict]:
+    global mtcnn_all, resnet, device
+    image = open_image(image_path)
+    boxes, probabilities = mtcnn_all.detect(image)
+    if boxes is None:
+        return []
+    faces = mtcnn_all.extract(image, boxes, None).to(device)
+    embeddings = resnet(faces).detach().cpu().numpy()
+    return [
+        {
+            'bounding_box': {
+                'x': float(x1),
+                'y': float(y1),
+                'width': float(x2 - x1),
+                'height': float(y2 - y1),
+            },
+            'confidence': float(probability),
+            'embedding': embedding.tolist(),
+        }
+        for (x1, y1, x2, y2), probability, embedding in zip(boxes, probabilities, embeddings)
+    ]
 
 def classify_image(image_path) -> str:
     global mtcnn, classifier
-    image = Image.open(image_path).convert('RGB')
-    image = mtcnn(image).to(torch.uint8).permute(1, 2, 0).detach().cpu().numpy()
+    if classifier is None:
+        raise HTTPException(503, detail={'error': 'classifier_unavailable'})
+    image = open_image(image_path)
+    face, _ = detect_single_face(image)
+    image = face.to(torch.uint8).permute(1, 2, 0).detach().cpu().numpy()
     image = Image.fromarray(image)
     identity = classifier.classify(image)
     return identity
@@ -89,6 +136,14 @@ async def classifiy(image: UploadFile = File(...)) -> str:
 async def embbed(image: UploadFile) -> List[float]:
     return list(embed_image(io.BytesIO(await image.read())))
 
+@app.post('/enroll')
+async def enroll(image: UploadFile) -> dict:
+    return enroll_image(io.BytesIO(await image.read()))
+
+@app.post('/detect')
+async def detect(image: UploadFile) -> List[dict]:
+    return detect_faces(io.BytesIO(await image.read()))
+
 @app.post('/upload_classifier')
 async def upload_classifier(model: UploadFile) -> str:
     global classifier, classifier_path, device
@@ -0,0 +1,191 @@
+use std::{str::FromStr, time::Duration};
+
+use async_trait::async_trait;
+use reqwest::{multipart, Client, Method, RequestBuilder, Response, StatusCode, Url};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{DetectedFace, EmbeddedFace, FaceRecognitionBackend, FaceRecognitionError};
+
+/// how long recognizing a single image may take.
+const RECOGNITION_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// client of the python face recognition service.
+pub struct FaceRecognizer {
+    client: Client,
+    classify_url: Url,
+    embed_url: Url,
+    enroll_url: Url,
+    detect_url: Url,
+    upload_classifier_url: Url,
+}
+
+impl FaceRecognizer {
+    pub fn new(base_url: &str) -> Self {
+        let client = Client::new();
+
+        Self {
+            client,
+            classify_url: Url::from_str(base_url)
+                .and_then(|url| url.join("classify"))
+                .unwrap(),
+            embed_url: Url::from_str(base_url)
+                .and_then(|url| url.join("embed"))
+                .unwrap(),
+            enroll_url: Url::from_str(base_url)
+                .and_then(|url| url.join("enroll"))
+                .unwrap(),
+            detect_url: Url::from_str(base_url)
+                .and_then(|url| url.join("detect"))
+                .unwrap(),
+            upload_classifier_url: Url::from_str(base_url)
+                .and_then(|url| url.join("upload_classifier"))
+                .unwrap(),
+        }
+    }
+}
+
+/// the `detail` the service sends along with a `422` or `503`.
+#[derive(Deserialize)]
+struct ErrorBody {
+    detail: ErrorDetail,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "error", rename_all = "snake_case")]
+enum ErrorDetail {
+    NoFace,
+    MultipleFaces { count: usize },
+    InvalidImage { message: String },
+    ClassifierUnavailable,
+}
+
+impl From<reqwest::Error> for FaceRecognitionError {
+    fn from(error: reqwest::Error) -> Self {
+        if error.is_timeout() {
+            Self::Timeout
+        } else if error.is_decode() {
+            Self::Decode(error.to_string())
+        } else {
+            Self::Unavailable(error.to_string())
+        }
+    }
+}
+
+/// sends the request and turns non success responses into errors, keeping
+/// the status and body of the ones the service doesn't describe.
+async fn send(request: RequestBuilder) -> Result<Response, FaceRecognitionError> {
+    let response = request.send().await?;
+    let status = response.status();
+
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let body = response.text().await?;
+
+    Err(match serde_json::from_str::<ErrorBody>(&body) {
+        Ok(ErrorBody { detail }) => match detail {
+            ErrorDetail::NoFace => FaceRecognitionError::NoFace,
+            ErrorDetail::MultipleFaces { count } => FaceRecognitionError::MultipleFaces { count },
+            ErrorDetail::InvalidImage { message } => FaceRecognitionError::InvalidImage(message),
+            ErrorDetail::ClassifierUnavailable => {
+                FaceRecognitionError::Unavailable("no classifier was uploaded".to_owned())
+            }
+        },
+        Err(_) if status == StatusCode::SERVICE_UNAVAILABLE => {
+            FaceRecognitionError::Unavailable(body)
+        }
+        Err(_) => FaceRecognitionError::Service {
+            status: status.as_u16(),
+            body,
+        },
+    })
+}
+
+#[async_trait]
+impl FaceRecognitionBackend for FaceRecognizer {
+    async fn embed(&self, image: &[u8]) -> Result<Vec<f64>, FaceRecognitionError> {
+        let part = multipart::Part::bytes(image.to_owned()).file_name("image");
+        let multipart = multipart::Form::new().part("image", part);
+
+        let embedding = send(
+            self.client
+                .request(Method::POST, self.embed_url.as_ref())
+                .timeout(RECOGNITION_TIMEOUT)
+                .multipart(multipart),
+        )
+        .await?
+        .json()
+        .await?;
+
+        Ok(embedding)
+    }
+
+    async fn enroll(&self, image: &[u8]) -> Result<EmbeddedFace, FaceRecognitionError> {
+        let part = multipart::Part::bytes(image.to_owned()).file_name("image");
+        let multipart = multipart::Form::new().part("image", part);
+
+        let face = send(
+            self.client
+                .request(Method::POST, self.enroll_url.as_ref())
+                .timeout(RECOGNITION_TIMEOUT)
+                .multipart(multipart),
+        )
+        .await?
+        .json()
+        .await?;
+
+        Ok(face)
+    }
+
+    async fn detect(&self, image: &[u8]) -> Result<Vec<DetectedFace>, FaceRecognitionError> {
+        let part = multipart::Part::bytes(image.to_owned()).file_name("image");
+        let multipart = multipart::Form::new().part("image", part);
+
+        let faces = send(
+            self.client
+                .request(Method::POST, self.detect_url.as_ref())
+                .timeout(RECOGNITION_TIMEOUT)
+                .multipart(multipart),
+        )
+        .await?
+        .json()
+        .await?;
+
+        Ok(faces)
+    }
+
+    async fn classify(&self, image: &[u8]) -> Result<Uuid, FaceRecognitionError> {
+        let part = multipart::Part::bytes(image.to_owned()).file_name("image");
+        let multipart = multipart::Form::new().part("image", part);
+
+        let class: Uuid = send(
+            self.client
+                .request(Method::POST, self.classify_url.as_ref())
+                .timeout(RECOGNITION_TIMEOUT)
+                .multipart(multipart),
+        )
+        .await?
+        .json()
+        .await?;
+
+        Ok(class)
+    }
+
+    async fn upload_classifier(&self, classifier: &[u8]) -> Result<String, FaceRecognitionError> {
+        let part = multipart::Part::bytes(classifier.to_owned()).file_name("classifier");
+        let multipart = multipart::Form::new().part("model", part);
+
+        let message = send(
+            self.client
+                .request(Method::POST, self.upload_classifier_url.as_ref())
+                .multipart(multipart),
+        )
+        .await?
+        .text()
+        .await?;
+
+        Ok(message)
+    }
+}
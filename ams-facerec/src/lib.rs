@@ -1,119 +1,164 @@
 #![allow(unused)]
 
-use async_trait::async_trait;
-use reqwest::{multipart, Client, Method, Url};
+mod http;
+#[cfg(feature = "onnx")]
+mod onnx;
+
+pub use http::*;
+#[cfg(feature = "onnx")]
+pub use onnx::*;
 
-use serde::{self, de, Deserialize, Serialize};
+use async_trait::async_trait;
 
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-use std::{borrow::Cow, collections::VecDeque, io::Bytes, str::FromStr};
+#[derive(Error, Debug)]
+pub enum FaceRecognitionError {
+    #[error("no face was detected in the image")]
+    NoFace,
+    #[error("{count} faces were detected in the image, expected one")]
+    MultipleFaces { count: usize },
+    #[error("the image could not be read: {0}")]
+    InvalidImage(String),
+    #[error("the face recognition service is unavailable: {0}")]
+    Unavailable(String),
+    #[error("the face recognition service timed out")]
+    Timeout,
+    #[error("the face recognition service responded with {status}: {body}")]
+    Service { status: u16, body: String },
+    #[error("unexpected response from the face recognition service: {0}")]
+    Decode(String),
+    #[error("{0} is not supported by this backend")]
+    Unsupported(&'static str),
+    #[error("the face recognition model failed: {0}")]
+    Model(String),
+    #[error("the face recognition model can't be used: {0}")]
+    InvalidModel(String),
+}
 
-use tokio::fs;
+impl FaceRecognitionError {
+    /// a stable identifier of the variant for clients to branch on.
+    pub fn reason(&self) -> &'static str {
+        match self {
+            Self::NoFace => "noFace",
+            Self::MultipleFaces { .. } => "multipleFaces",
+            Self::InvalidImage(_) => "invalidImage",
+            Self::Unavailable(_) => "unavailable",
+            Self::Timeout => "timeout",
+            Self::Service { .. } => "service",
+            Self::Decode(_) => "decode",
+            Self::Unsupported(_) => "unsupported",
+            Self::Model(_) => "model",
+            Self::InvalidModel(_) => "invalidModel",
+        }
+    }
+}
 
-#[derive(Error, Debug)]
-#[error("face recognition error")]
-pub struct FaceRecognitionError;
+/// the length of the embeddings the database stores.
+pub const EMBEDDING_DIMENSION: usize = 512;
+
+/// how far apart two embeddings are, lower is always nearer.
+///
+/// the distances match the ones of pgvector's `<->`, `<=>` and `<#>`
+/// operators, so thresholds carry over between rust and sql.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Metric {
+    /// the euclidean distance.
+    L2,
+    /// one minus the cosine similarity.
+    Cosine,
+    /// the negative inner product.
+    Dot,
+}
 
 #[async_trait]
 pub trait Embedding
 where
     Self: IntoIterator<Item = f64> + Sized,
 {
-    fn distance(&self, other: &Self) -> f64;
+    fn dot(&self, other: &Self) -> f64;
+    fn norm(&self) -> f64;
+    /// scaled to a unit norm, so all metrics rank embeddings alike.
+    fn normalized(self) -> Self;
+    fn distance(&self, other: &Self, metric: Metric) -> f64;
 }
 
 #[async_trait]
 impl Embedding for Vec<f64> {
-    fn distance(&self, other: &Self) -> f64 {
-        self.iter()
-            .zip(other.iter())
-            .map(|(x1, x2)| (x1 - x2).powi(2))
-            .sum::<f64>()
-            .sqrt()
+    fn dot(&self, other: &Self) -> f64 {
+        self.iter().zip(other.iter()).map(|(x1, x2)| x1 * x2).sum()
     }
-}
 
-pub struct FaceRecognizer {
-    client: Client,
-    classify_url: Url,
-    embed_url: Url,
-    upload_classifier_url: Url,
-}
-
-impl FaceRecognizer {
-    pub fn new(base_url: &str) -> Self {
-        let client = Client::new();
-
-        Self {
-            client,
-            classify_url: Url::from_str(base_url)
-                .and_then(|url| url.join("classify"))
-                .unwrap(),
-            embed_url: Url::from_str(base_url)
-                .and_then(|url| url.join("embed"))
-                .unwrap(),
-            upload_classifier_url: Url::from_str(base_url)
-                .and_then(|url| url.join("upload_classifier"))
-                .unwrap(),
-        }
+    fn norm(&self) -> f64 {
+        self.dot(self).sqrt()
     }
 
-    pub async fn embed(&self, image: &[u8]) -> Result<Vec<f64>, FaceRecognitionError> {
-        let part = multipart::Part::bytes(image.to_owned()).file_name("image");
-        let multipart = multipart::Form::new().part("image", part);
-
-        let embedding = self
-            .client
-            .request(Method::POST, self.embed_url.as_ref())
-            .multipart(multipart)
-            .send()
-            .await
-            .map_err(|_| FaceRecognitionError)?
-            .json()
-            .await
-            .map_err(|_| FaceRecognitionError)?;
-
-        Ok(embedding)
+    fn normalized(self) -> Self {
+        let norm = self.norm().max(1e-10);
+        self.into_iter().map(|x| x / norm).collect()
     }
 
-    pub async fn classify(&self, image: &[u8]) -> Result<Uuid, FaceRecognitionError> {
-        let part = multipart::Part::bytes(image.to_owned()).file_name("image");
-        let multipart = multipart::Form::new().part("image", part);
-
-        let class: Uuid = self
-            .client
-            .request(Method::POST, self.classify_url.as_ref())
-            .multipart(multipart)
-            .send()
-            .await
-            .map_err(|_| FaceRecognitionError)?
-            .json()
-            .await
-            .map_err(|_| FaceRecognitionError)?;
-
-        Ok(class)
+    fn distance(&self, other: &Self, metric: Metric) -> f64 {
+        match metric {
+            Metric::L2 => self
+                .iter()
+                .zip(other.iter())
+                .map(|(x1, x2)| (x1 - x2).powi(2))
+                .sum::<f64>()
+                .sqrt(),
+            Metric::Cosine => 1.0 - self.dot(other) / (self.norm() * other.norm()).max(1e-10),
+            Metric::Dot => -self.dot(other),
+        }
     }
+}
+
+/// the embedding of a face along with how fit the image is for enrollment.
+#[derive(Debug, Clone, Deserialize)]
+pub struct EmbeddedFace {
+    pub embedding: Vec<f64>,
+    /// from `0` for unusable to `1` for ideal.
+    pub quality: f64,
+}
 
-    pub async fn upload_classifier(
-        &self,
-        classifier: &[u8],
-    ) -> Result<String, FaceRecognitionError> {
-        let part = multipart::Part::bytes(classifier.to_owned()).file_name("classifier");
-        let multipart = multipart::Form::new().part("model", part);
-
-        let embedding = self
-            .client
-            .request(Method::POST, self.upload_classifier_url.as_ref())
-            .multipart(multipart)
-            .send()
-            .await
-            .map_err(|_| FaceRecognitionError)?
-            .text()
-            .await
-            .map_err(|_| FaceRecognitionError)?;
-
-        Ok(embedding)
+/// where a face is in an image, in pixels from the top left corner.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BoundingBox {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// one of the faces found in an image.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DetectedFace {
+    pub bounding_box: BoundingBox,
+    /// how sure the detector is that this is a face.
+    pub confidence: f64,
+    pub embedding: Vec<f64>,
+}
+
+/// something that can turn face images into embeddings or classes.
+#[async_trait]
+pub trait FaceRecognitionBackend {
+    /// the embedding of the face in `image`.
+    async fn embed(&self, image: &[u8]) -> Result<Vec<f64>, FaceRecognitionError>;
+    /// the embedding of the face in `image` and its quality, for enrolling it.
+    async fn enroll(&self, image: &[u8]) -> Result<EmbeddedFace, FaceRecognitionError>;
+    /// whether [`FaceRecognitionBackend::detect`] is supported, finding the
+    /// faces in a group photo takes a face detector.
+    fn detects_faces(&self) -> bool {
+        true
     }
+    /// every face in `image` with its embedding, none when there are no faces.
+    async fn detect(&self, image: &[u8]) -> Result<Vec<DetectedFace>, FaceRecognitionError>;
+    /// the id of the attendee the classifier recognizes in `image`.
+    async fn classify(&self, image: &[u8]) -> Result<Uuid, FaceRecognitionError>;
+    /// replaces the classifier used by [`FaceRecognitionBackend::classify`].
+    async fn upload_classifier(&self, classifier: &[u8]) -> Result<String, FaceRecognitionError>;
 }
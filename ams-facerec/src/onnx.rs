@@ -0,0 +1,171 @@
+use std::{path::Path, sync::Arc};
+
+use async_trait::async_trait;
+use image::imageops::FilterType;
+use tract_onnx::prelude::*;
+use uuid::Uuid;
+
+use crate::{
+    DetectedFace, EmbeddedFace, Embedding, FaceRecognitionBackend, FaceRecognitionError,
+    EMBEDDING_DIMENSION,
+};
+
+type Model = TypedRunnableModel<TypedModel>;
+
+/// the variance of the laplacian at which an image is considered half sharp.
+const SHARPNESS_SCALE: f64 = 100.0;
+
+/// runs a face embedding onnx model on the cpu.
+///
+/// the model is expected to take a `1x3xSIZExSIZE` rgb tensor normalized to
+/// `(pixel - 127.5) / 128` and to output a single embedding of
+/// [`EMBEDDING_DIMENSION`] values, as facenet style models do.
+///
+/// there is no face detector, every image is taken as one face and resized as
+/// a whole, so images must already be cropped to the face. finding the faces
+/// in a group photo and classification are not supported, match embeddings
+/// instead, and the quality of an enrolled image is judged by its sharpness
+/// alone.
+pub struct OnnxFaceRecognizer {
+    model: Arc<Model>,
+    size: u32,
+}
+
+impl OnnxFaceRecognizer {
+    /// loads and optimizes the model at `path` for `size`x`size` input images,
+    /// failing if its embeddings don't have [`EMBEDDING_DIMENSION`] values.
+    pub fn new(path: impl AsRef<Path>, size: u32) -> Result<Self, FaceRecognitionError> {
+        let model = tract_onnx::onnx()
+            .model_for_path(path)
+            .and_then(|model| {
+                model
+                    .with_input_fact(0, f32::fact([1, 3, size as usize, size as usize]).into())?
+                    .into_optimized()?
+                    .into_runnable()
+            })
+            .map_err(|error| FaceRecognitionError::InvalidModel(error.to_string()))?;
+
+        let shape = model
+            .model()
+            .output_fact(0)
+            .map_err(|error| FaceRecognitionError::InvalidModel(error.to_string()))?
+            .shape
+            .clone();
+        let dimension = shape.as_concrete().map(|shape| shape.iter().product());
+        if dimension != Some(EMBEDDING_DIMENSION) {
+            return Err(FaceRecognitionError::InvalidModel(format!(
+                "it outputs a `{shape:?}` tensor, expected an embedding of {EMBEDDING_DIMENSION} values"
+            )));
+        }
+
+        Ok(Self {
+            model: Arc::new(model),
+            size,
+        })
+    }
+
+    fn load(size: u32, image: &[u8]) -> Result<image::DynamicImage, FaceRecognitionError> {
+        Ok(image::load_from_memory(image)
+            .map_err(|error| FaceRecognitionError::InvalidImage(error.to_string()))?
+            .resize_exact(size, size, FilterType::Triangle))
+    }
+
+    /// maps the variance of the laplacian of the grayscale image into `[0, 1)`.
+    fn sharpness(image: &image::DynamicImage) -> f64 {
+        let gray = image.to_luma8();
+        let (width, height) = gray.dimensions();
+        let pixel = |x: u32, y: u32| gray.get_pixel(x, y)[0] as f64;
+
+        let laplacians: Vec<f64> = (1..height.saturating_sub(1))
+            .flat_map(|y| (1..width.saturating_sub(1)).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                pixel(x - 1, y) + pixel(x + 1, y) + pixel(x, y - 1) + pixel(x, y + 1)
+                    - 4.0 * pixel(x, y)
+            })
+            .collect();
+
+        if laplacians.is_empty() {
+            return 0.0;
+        }
+
+        let count = laplacians.len() as f64;
+        let mean = laplacians.iter().sum::<f64>() / count;
+        let variance = laplacians
+            .iter()
+            .map(|value| (value - mean).powi(2))
+            .sum::<f64>()
+            / count;
+
+        variance / (variance + SHARPNESS_SCALE)
+    }
+
+    fn run(
+        model: &Model,
+        size: u32,
+        image: &image::DynamicImage,
+    ) -> Result<Vec<f64>, FaceRecognitionError> {
+        let image = image.to_rgb8();
+
+        let size = size as usize;
+        let input: Tensor =
+            tract_ndarray::Array4::from_shape_fn((1, 3, size, size), |(_, channel, y, x)| {
+                (image.get_pixel(x as u32, y as u32)[channel] as f32 - 127.5) / 128.0
+            })
+            .into();
+
+        let outputs = model
+            .run(tvec!(input.into()))
+            .map_err(|error| FaceRecognitionError::Model(error.to_string()))?;
+        let embedding = outputs[0]
+            .to_array_view::<f32>()
+            .map_err(|error| FaceRecognitionError::Model(error.to_string()))?;
+
+        // normalized so distances are comparable between images.
+        Ok(embedding
+            .iter()
+            .map(|x| *x as f64)
+            .collect::<Vec<f64>>()
+            .normalized())
+    }
+}
+
+#[async_trait]
+impl FaceRecognitionBackend for OnnxFaceRecognizer {
+    async fn embed(&self, image: &[u8]) -> Result<Vec<f64>, FaceRecognitionError> {
+        let (model, size, image) = (self.model.clone(), self.size, image.to_owned());
+
+        tokio::task::spawn_blocking(move || Self::run(&model, size, &Self::load(size, &image)?))
+            .await
+            .map_err(|error| FaceRecognitionError::Model(error.to_string()))?
+    }
+
+    async fn enroll(&self, image: &[u8]) -> Result<EmbeddedFace, FaceRecognitionError> {
+        let (model, size, image) = (self.model.clone(), self.size, image.to_owned());
+
+        tokio::task::spawn_blocking(move || {
+            let image = Self::load(size, &image)?;
+            Ok(EmbeddedFace {
+                embedding: Self::run(&model, size, &image)?,
+                quality: Self::sharpness(&image),
+            })
+        })
+        .await
+        .map_err(|error| FaceRecognitionError::Model(error.to_string()))?
+    }
+
+    fn detects_faces(&self) -> bool {
+        false
+    }
+
+    async fn detect(&self, _image: &[u8]) -> Result<Vec<DetectedFace>, FaceRecognitionError> {
+        Err(FaceRecognitionError::Unsupported("detecting faces"))
+    }
+
+    async fn classify(&self, _image: &[u8]) -> Result<Uuid, FaceRecognitionError> {
+        Err(FaceRecognitionError::Unsupported("classification"))
+    }
+
+    async fn upload_classifier(&self, _classifier: &[u8]) -> Result<String, FaceRecognitionError> {
+        Err(FaceRecognitionError::Unsupported("uploading a classifier"))
+    }
+}
@@ -1,8 +1,9 @@
 use std::sync::Arc;
 
+use chrono::Utc;
 use sea_orm::{
     prelude::{async_trait::async_trait, *},
-    Set,
+    QuerySelect, Set, TransactionTrait,
 };
 use uuid::Uuid;
 
@@ -10,10 +11,27 @@ use crate::entity::admins;
 
 use super::{models::*, AdminsRepoTrait};
 
-use crate::prelude::RepoError;
+use crate::{
+    audit_log::{self, AuditChange},
+    pagination::{ListQuery, Listable, Page},
+    password::{self, Verification},
+    prelude::RepoError,
+};
 
 pub struct AdminsRepo(pub Arc<DatabaseConnection>);
 
+impl Listable for admins::Entity {
+    const SORTS: &'static [(&'static str, admins::Column)] = &[
+        ("name", admins::Column::Name),
+        ("email", admins::Column::Email),
+        ("createdAt", admins::Column::CreateAt),
+    ];
+    const DEFAULT_SORT: &'static str = "name";
+    const ID: admins::Column = admins::Column::Id;
+    const NAME: Option<admins::Column> = Some(admins::Column::Name);
+    const CREATED_AT: Option<admins::Column> = Some(admins::Column::CreateAt);
+}
+
 impl AsRef<DatabaseConnection> for AdminsRepo {
     fn as_ref(&self) -> &DatabaseConnection {
         &self.0
@@ -23,15 +41,66 @@ impl AsRef<DatabaseConnection> for AdminsRepo {
 #[async_trait]
 impl AdminsRepoTrait for AdminsRepo {
     async fn create(&self, admin: CreateAdmin) -> Result<Admin, RepoError> {
-        Ok(admins::ActiveModel {
+        let password = password::hash(admin.password).await?;
+        let txn = self.as_ref().begin().await?;
+
+        let admin: Admin = admins::ActiveModel {
             name: Set(admin.name),
             email: Set(admin.email),
-            password: Set(admin.password),
+            password: Set(password),
             ..Default::default()
         }
-        .insert(self.as_ref())
+        .insert(&txn)
         .await?
-        .into())
+        .into();
+        audit_log::record(&txn, Some(admin.id), AuditChange::Created(&admin)).await?;
+        txn.commit().await?;
+
+        Ok(admin)
+    }
+    async fn update(
+        &self,
+        id: Uuid,
+        UpdateAdmin {
+            name,
+            email,
+            password,
+        }: UpdateAdmin,
+    ) -> Result<Admin, RepoError> {
+        let password = match password {
+            Some(password) => Some(password::hash(password).await?),
+            None => None,
+        };
+        let txn = self.as_ref().begin().await?;
+
+        let before = admins::Entity::find_by_id(id)
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or(RepoError::NotFound("admins".to_owned()))?;
+        let mut admin: admins::ActiveModel = before.clone().into();
+
+        if let Some(name) = name {
+            admin.name = Set(name);
+        }
+        if let Some(email) = email {
+            admin.email = Set(email);
+        }
+        if let Some(password) = password {
+            admin.password = Set(password);
+        }
+        admin.updated_at = Set(Utc::now().into());
+
+        let admin: Admin = admin.update(&txn).await?.into();
+        audit_log::record(
+            &txn,
+            Some(id),
+            AuditChange::Updated(&Admin::from(before), &admin),
+        )
+        .await?;
+        txn.commit().await?;
+
+        Ok(admin)
     }
     async fn get_by_id(&self, id: Uuid) -> Result<Admin, RepoError> {
         Ok(admins::Entity::find_by_id(id)
@@ -48,21 +117,76 @@ impl AdminsRepoTrait for AdminsRepo {
             .ok_or(RepoError::NotFound("admins".to_owned()))?
             .into())
     }
-    async fn get_all(&self) -> Result<Vec<Admin>, RepoError> {
-        let collection: Vec<Admin> = admins::Entity::find()
-            .all(self.as_ref())
+    async fn get_by_creds(&self, email: String, password: String) -> Result<Admin, RepoError> {
+        let Some(mut admin) = admins::Entity::find()
+            .filter(admins::Column::Email.eq(&email))
+            .one(self.as_ref())
             .await?
-            .into_iter()
-            .map(Admin::from)
-            .collect();
-        if collection.is_empty() {
-            Err(RepoError::NotFound("admins".to_owned()))
-        } else {
-            Ok(collection)
+        else {
+            password::verify_dummy(password).await;
+            return Err(RepoError::WrongCredentials);
+        };
+
+        match password::verify(password.clone(), admin.password.clone()).await {
+            Verification::Valid => {}
+            Verification::ValidLegacy => {
+                tracing::info!("re-hashing legacy password for admin `{}`", admin.id);
+                let mut active: admins::ActiveModel = admin.into();
+                active.password = Set(password::hash(password).await?);
+                admin = active.update(self.as_ref()).await?;
+            }
+            Verification::Invalid => return Err(RepoError::WrongCredentials),
         }
+
+        Ok(admin.into())
+    }
+    async fn get_all(&self, query: ListQuery) -> Result<Page<Admin>, RepoError> {
+        Ok(query
+            .page(self.as_ref(), admins::Entity::find())
+            .await?
+            .map(Admin::from))
+    }
+    async fn update_password(&self, id: Uuid, password: String) -> Result<Admin, RepoError> {
+        let password = password::hash(password).await?;
+        let txn = self.as_ref().begin().await?;
+
+        let before = admins::Entity::find_by_id(id)
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or(RepoError::NotFound("admins".to_owned()))?;
+        let mut admin: admins::ActiveModel = before.clone().into();
+
+        admin.password = Set(password);
+        admin.updated_at = Set(Utc::now().into());
+
+        let admin: Admin = admin.update(&txn).await?.into();
+        audit_log::record(
+            &txn,
+            Some(id),
+            AuditChange::Updated(&Admin::from(before), &admin),
+        )
+        .await?;
+        txn.commit().await?;
+
+        Ok(admin)
     }
     async fn delete_by_id(&self, id: Uuid) -> Result<(), RepoError> {
-        admins::Entity::delete_by_id(id).exec(self.as_ref()).await?;
+        let txn = self.as_ref().begin().await?;
+
+        // lock every admin so concurrent deletes can't remove the last two
+        let admins = admins::Entity::find().lock_exclusive().all(&txn).await?;
+        let Some(admin) = admins.iter().find(|admin| admin.id == id).cloned() else {
+            return Err(RepoError::NotFound("admins".to_owned()));
+        };
+        if admins.len() == 1 {
+            return Err(RepoError::LastAdmin);
+        }
+
+        admins::Entity::delete_by_id(id).exec(&txn).await?;
+        audit_log::record(&txn, Some(id), AuditChange::Deleted(&Admin::from(admin))).await?;
+        txn.commit().await?;
+
         Ok(())
     }
 }
@@ -4,7 +4,10 @@ mod models;
 use sea_orm::prelude::async_trait::async_trait;
 use uuid::Uuid;
 
-use crate::error::RepoError;
+use crate::{
+    error::RepoError,
+    pagination::{ListQuery, Page},
+};
 
 pub use impls::*;
 pub use models::*;
@@ -12,8 +15,13 @@ pub use models::*;
 #[async_trait]
 pub trait AdminsRepoTrait {
     async fn create(&self, admin: CreateAdmin) -> Result<Admin, RepoError>;
+    async fn update(&self, id: Uuid, update_admin: UpdateAdmin) -> Result<Admin, RepoError>;
     async fn get_by_id(&self, id: Uuid) -> Result<Admin, RepoError>;
     async fn get_by_email(&self, email: String) -> Result<Admin, RepoError>;
-    async fn get_all(&self) -> Result<Vec<Admin>, RepoError>;
+    async fn get_by_creds(&self, email: String, password: String) -> Result<Admin, RepoError>;
+    async fn get_all(&self, query: ListQuery) -> Result<Page<Admin>, RepoError>;
+    /// replaces the admin's password with the hash of `password`.
+    async fn update_password(&self, id: Uuid, password: String) -> Result<Admin, RepoError>;
+    /// deletes the admin unless it's the last one left.
     async fn delete_by_id(&self, id: Uuid) -> Result<(), RepoError>;
 }
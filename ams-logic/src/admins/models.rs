@@ -11,6 +11,7 @@ pub struct Admin {
     pub id: Uuid,
     pub name: String,
     pub email: String,
+    #[serde(skip)]
     pub password: String,
     pub create_at: DateTime<FixedOffset>,
     pub updated_at: DateTime<FixedOffset>,
@@ -38,10 +39,33 @@ impl From<admins::Model> for Admin {
     }
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateAdmin {
+    #[schema(example = "Mina Admin")]
     pub name: String,
+    #[schema(example = "MinaAdmin@outlook.com")]
     pub email: String,
+    #[schema(example = "12345678")]
     pub password: String,
 }
+
+#[derive(Deserialize, Debug, Default, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateAdmin {
+    #[schema(example = "Mina Admin")]
+    pub name: Option<String>,
+    #[schema(example = "MinaAdmin@outlook.com")]
+    pub email: Option<String>,
+    #[schema(example = "12345678")]
+    pub password: Option<String>,
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangePassword {
+    #[schema(example = "12345678")]
+    pub current_password: String,
+    #[schema(example = "87654321")]
+    pub new_password: String,
+}
@@ -1,45 +1,363 @@
 use std::sync::Arc;
 
-use chrono::{Datelike, Utc};
+use chrono::{Duration, Utc};
+use chrono_tz::Tz;
 use itertools::Itertools;
 use sea_orm::{
     prelude::{async_trait::async_trait, *},
-    QueryOrder, QueryTrait, Set, TransactionTrait,
+    sea_query::OnConflict,
+    DatabaseTransaction, IntoActiveModel, QueryOrder, QuerySelect, QueryTrait, Set,
+    TransactionTrait,
 };
 
+use tokio::sync::watch;
+
 use super::*;
 
-use crate::{entity::subject_dates, prelude::*};
+use crate::{
+    audit_log::{self, AuditChange},
+    class_sessions::generate_sessions,
+    entity::subject_dates,
+    pagination::Listable,
+    prelude::*,
+};
 
-use crate::entity::{attendances, attendees, instructors, subjects};
+use crate::entity::{
+    attendance_audit, attendances, attendees, attendees_subjects, class_sessions, instructors,
+    subjects,
+};
 
-pub struct AttendancesRepo(pub Arc<DatabaseConnection>);
+pub struct AttendancesRepo {
+    db: Arc<DatabaseConnection>,
+    timezone: Tz,
+    settings: watch::Receiver<Settings>,
+}
+
+impl Listable for attendances::Entity {
+    const SORTS: &'static [(&'static str, attendances::Column)] =
+        &[("createdAt", attendances::Column::CreateAt)];
+    const DEFAULT_SORT: &'static str = "createdAt";
+    const ID: attendances::Column = attendances::Column::Id;
+    const CREATED_AT: Option<attendances::Column> = Some(attendances::Column::CreateAt);
+}
+
+impl Listable for attendance_audit::Entity {
+    const SORTS: &'static [(&'static str, attendance_audit::Column)] =
+        &[("createdAt", attendance_audit::Column::CreateAt)];
+    const DEFAULT_SORT: &'static str = "-createdAt";
+    const ID: attendance_audit::Column = attendance_audit::Column::Id;
+    const CREATED_AT: Option<attendance_audit::Column> = Some(attendance_audit::Column::CreateAt);
+}
 
 impl AsRef<DatabaseConnection> for AttendancesRepo {
     fn as_ref(&self) -> &DatabaseConnection {
-        &self.0
+        &self.db
     }
 }
 
+impl AttendancesRepo {
+    /// the grace periods follow the latest `settings`.
+    pub fn new(
+        db: Arc<DatabaseConnection>,
+        timezone: Tz,
+        settings: watch::Receiver<Settings>,
+    ) -> Self {
+        Self {
+            db,
+            timezone,
+            settings,
+        }
+    }
+
+    fn window(&self) -> AttendanceWindow {
+        let settings = self.settings.borrow();
+
+        AttendanceWindow {
+            timezone: self.timezone,
+            early_grace: Duration::minutes(settings.attendance_early_grace),
+            late_grace: Duration::minutes(settings.attendance_late_grace),
+        }
+    }
+}
+
+impl AttendancesRepo {
+    /// the session of the subject that is currently open for attendance.
+    async fn open_session(&self, subject_id: Uuid) -> Result<class_sessions::Model, RepoError> {
+        let now = Utc::now();
+        let window = self.window();
+        let today = now.with_timezone(&window.timezone).date_naive();
+
+        // the sessions around now may not be generated yet, the grace periods
+        // can also push a window across midnight.
+        generate_sessions(
+            self.as_ref(),
+            window.timezone,
+            subject_id,
+            today - Duration::days(1),
+            today + Duration::days(7),
+        )
+        .await?;
+
+        let subject = subjects::Entity::find_by_id(subject_id)
+            .one(self.as_ref())
+            .await?
+            .ok_or(RepoError::NotFound("subjects".to_owned()))?;
+        let calendar = TermCalendar::of_subject(self.as_ref(), &subject).await?;
+
+        let sessions = subject
+            .find_related(class_sessions::Entity)
+            .filter(class_sessions::Column::Status.ne(ClassSessionStatus::Cancelled))
+            .filter(class_sessions::Column::EndsAt.gte(now - window.late_grace))
+            .order_by_asc(class_sessions::Column::StartsAt)
+            .all(self.as_ref())
+            .await?
+            .into_iter()
+            .filter(|session| TermCalendar::holds(calendar.as_ref(), session))
+            .collect_vec();
+
+        window
+            .current(&sessions, now)
+            .cloned()
+            .map_err(|next_session| RepoError::OutsideSessionWindow {
+                subject_id,
+                next_session,
+            })
+    }
+
+    /// inserts one attendance, duplicates are caught by the
+    /// `uk_attendance_per_occurrence` constraint.
+    async fn insert<C: ConnectionTrait>(
+        conn: &C,
+        CreateAttendance {
+            attendee_id,
+            subject_id,
+        }: CreateAttendance,
+        session: &class_sessions::Model,
+        status: AttendanceStatus,
+    ) -> Result<attendances::Model, RepoError> {
+        attendances::ActiveModel {
+            attendee_id: Set(attendee_id),
+            subject_id: Set(subject_id),
+            class_session_id: Set(session.id),
+            status: Set(status),
+            ..Default::default()
+        }
+        .insert(conn)
+        .await
+        .map_unique_violation(
+            "uk_attendance_per_occurrence",
+            RepoError::DuplicateAttendance {
+                attendee_id,
+                subject_id,
+            },
+        )
+    }
+
+    /// inserts one attendance unless the attendee already has one in the
+    /// session, `None` when it does.
+    async fn insert_missing<C: ConnectionTrait>(
+        conn: &C,
+        CreateAttendance {
+            attendee_id,
+            subject_id,
+        }: CreateAttendance,
+        session: &class_sessions::Model,
+    ) -> Result<Option<attendances::Model>, RepoError> {
+        let inserted = attendances::Entity::insert(attendances::ActiveModel {
+            attendee_id: Set(attendee_id),
+            subject_id: Set(subject_id),
+            class_session_id: Set(session.id),
+            status: Set(AttendanceStatus::Present),
+            ..Default::default()
+        })
+        .on_conflict(
+            OnConflict::columns([
+                attendances::Column::AttendeeId,
+                attendances::Column::ClassSessionId,
+            ])
+            .do_nothing()
+            .to_owned(),
+        )
+        .exec_without_returning(conn)
+        .await?;
+        if inserted == 0 {
+            return Ok(None);
+        }
+
+        Ok(attendances::Entity::find()
+            .filter(attendances::Column::AttendeeId.eq(attendee_id))
+            .filter(attendances::Column::ClassSessionId.eq(session.id))
+            .one(conn)
+            .await?)
+    }
+
+    /// records a change of `attendance` in its audit trail, within the
+    /// transaction of the change.
+    async fn audit<C: ConnectionTrait>(
+        conn: &C,
+        attendance: &attendances::Model,
+        old_status: Option<AttendanceStatus>,
+        new_status: Option<AttendanceStatus>,
+        by: Actor,
+        reason: Option<String>,
+    ) -> Result<(), RepoError> {
+        attendance_audit::ActiveModel {
+            attendance_id: Set(attendance.id),
+            subject_id: Set(attendance.subject_id),
+            attendee_id: Set(attendance.attendee_id),
+            class_session_id: Set(attendance.class_session_id),
+            changed_by_kind: Set(by.kind),
+            changed_by: Set(by.id),
+            old_status: Set(old_status),
+            new_status: Set(new_status),
+            reason: Set(reason),
+            ..Default::default()
+        }
+        .insert(conn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// changes the status of a locked attendance, an unchanged status isn't
+    /// audited.
+    async fn set_status<C: ConnectionTrait>(
+        conn: &C,
+        attendance: attendances::Model,
+        status: AttendanceStatus,
+        by: Actor,
+        reason: String,
+    ) -> Result<attendances::Model, RepoError> {
+        let old_status = attendance.status;
+        if old_status == status {
+            return Ok(attendance);
+        }
+
+        let before = Self::load(conn, attendance.clone()).await?;
+        let mut active = attendance.into_active_model();
+        active.status = Set(status);
+        let attendance = active.update(conn).await?;
+        audit_log::record(
+            conn,
+            Some(attendance.id),
+            AuditChange::Updated(&before, &Self::load(conn, attendance.clone()).await?),
+        )
+        .await?;
+
+        Self::audit(
+            conn,
+            &attendance,
+            Some(old_status),
+            Some(status),
+            by,
+            Some(reason),
+        )
+        .await?;
+
+        Ok(attendance)
+    }
+
+    /// the attendance along with its attendee, subject and session.
+    async fn load<C: ConnectionTrait>(
+        conn: &C,
+        attendance: attendances::Model,
+    ) -> Result<Attendance, RepoError> {
+        let attendee = attendance
+            .find_related(attendees::Entity)
+            .one(conn)
+            .await?
+            .ok_or(RepoError::NotFound("attendees".to_owned()))?
+            .into();
+
+        let subject = attendance
+            .find_related(subjects::Entity)
+            .one(conn)
+            .await?
+            .ok_or(RepoError::NotFound("subjects".to_owned()))?;
+
+        let session = attendance
+            .find_related(class_sessions::Entity)
+            .one(conn)
+            .await?
+            .ok_or(RepoError::NotFound("class_sessions".to_owned()))?
+            .into();
+
+        let instructor = subject
+            .find_related(instructors::Entity)
+            .one(conn)
+            .await?
+            .map(Instructor::from);
+
+        let dates = subject
+            .find_related(subject_dates::Entity)
+            .all(conn)
+            .await?
+            .into_iter()
+            .map_into()
+            .collect_vec();
+
+        Ok((
+            attendance,
+            attendee,
+            (subject, dates, instructor).into(),
+            session,
+        )
+            .into())
+    }
+
+    async fn lock(txn: &DatabaseTransaction, id: Uuid) -> Result<attendances::Model, RepoError> {
+        attendances::Entity::find_by_id(id)
+            .lock_exclusive()
+            .one(txn)
+            .await?
+            .ok_or(RepoError::AttendanceNotFound { id: id.to_string() })
+    }
+}
+
+/// a reason is required for manual changes, it has to fit the audit trail.
+fn reason(reason: String) -> Result<String, RepoError> {
+    let reason = reason.trim();
+    if reason.is_empty() {
+        return Err(RepoError::InvalidReason("can't be empty".to_owned()));
+    }
+    if reason.chars().count() > 256 {
+        return Err(RepoError::InvalidReason(
+            "can't be longer than 256 characters".to_owned(),
+        ));
+    }
+    Ok(reason.to_owned())
+}
+
 #[async_trait]
 impl AttendancesRepoTrait for AttendancesRepo {
     async fn create_many(
         &self,
         subject_id: Uuid,
         attendee_ids: Vec<Uuid>,
+        by: Actor,
     ) -> Result<Vec<Attendance>, RepoError> {
+        let session = self.open_session(subject_id).await?;
+
         let txn = self.as_ref().begin().await?;
 
         let mut created = Vec::with_capacity(attendee_ids.len());
 
         for attendee_id in attendee_ids {
-            let attendance = self
-                .create_one(CreateAttendance {
+            let attendance = Self::insert(
+                &txn,
+                CreateAttendance {
                     attendee_id,
                     subject_id,
-                })
-                .await?;
-            created.push(attendance)
+                },
+                &session,
+                AttendanceStatus::Present,
+            )
+            .await?;
+            Self::audit(&txn, &attendance, None, Some(attendance.status), by, None).await?;
+
+            let attendance = Self::load(&txn, attendance).await?;
+            audit_log::record(&txn, Some(attendance.id), AuditChange::Created(&attendance)).await?;
+            created.push(attendance);
         }
 
         txn.commit().await?;
@@ -47,57 +365,175 @@ impl AttendancesRepoTrait for AttendancesRepo {
         Ok(created)
     }
 
-    async fn create_one(&self, attendance: CreateAttendance) -> Result<Attendance, RepoError> {
-        if let Some(last_attendance) = attendances::Entity::find()
-            .order_by_desc(attendances::Column::CreateAt)
-            .one(self.as_ref())
+    async fn create_many_missing(
+        &self,
+        subject_id: Uuid,
+        attendee_ids: Vec<Uuid>,
+        by: Actor,
+    ) -> Result<TakenAttendances, RepoError> {
+        let session = self.open_session(subject_id).await?;
+
+        let txn = self.as_ref().begin().await?;
+
+        let mut created = Vec::with_capacity(attendee_ids.len());
+        let mut already_attended = Vec::new();
+
+        for attendee_id in attendee_ids {
+            let Some(attendance) = Self::insert_missing(
+                &txn,
+                CreateAttendance {
+                    attendee_id,
+                    subject_id,
+                },
+                &session,
+            )
             .await?
-        {
-            if last_attendance.attendee_id == attendance.attendee_id {
-                let now = Utc::now();
-                let last = last_attendance.create_at.with_timezone(&now.timezone());
-
-                if now.day() == last.day() {
-                    return Err(RepoError::DuplicateAttendance {
-                        attendee_id: attendance.attendee_id,
-                        subject_id: attendance.subject_id,
-                    });
-                }
-            }
-        };
+            else {
+                already_attended.push(attendee_id);
+                continue;
+            };
+            Self::audit(&txn, &attendance, None, Some(attendance.status), by, None).await?;
 
-        let attendance: attendances::Model = attendances::ActiveModel {
-            attendee_id: Set(attendance.attendee_id),
-            subject_id: Set(attendance.subject_id),
-            ..Default::default()
+            let attendance = Self::load(&txn, attendance).await?;
+            audit_log::record(&txn, Some(attendance.id), AuditChange::Created(&attendance)).await?;
+            created.push(attendance);
         }
-        .insert(self.as_ref())
-        .await
-        .map_duplicate(RepoError::DuplicateAttendance {
-            attendee_id: attendance.attendee_id,
-            subject_id: attendance.subject_id,
-        })?;
 
-        Ok(self.get_by_id(attendance.id).await?)
+        txn.commit().await?;
+
+        Ok(TakenAttendances {
+            created,
+            already_attended,
+        })
     }
-    async fn delete_by_id(&self, id: Uuid) -> Result<(), RepoError> {
-        attendances::Entity::delete_by_id(id)
-            .exec(self.as_ref())
-            .await?;
+
+    async fn create_one(
+        &self,
+        attendance: CreateAttendance,
+        by: Actor,
+    ) -> Result<Attendance, RepoError> {
+        let session = self.open_session(attendance.subject_id).await?;
+
+        let txn = self.as_ref().begin().await?;
+        let attendance =
+            Self::insert(&txn, attendance, &session, AttendanceStatus::Present).await?;
+        Self::audit(&txn, &attendance, None, Some(attendance.status), by, None).await?;
+
+        let attendance = Self::load(&txn, attendance).await?;
+        audit_log::record(&txn, Some(attendance.id), AuditChange::Created(&attendance)).await?;
+        txn.commit().await?;
+
+        Ok(attendance)
+    }
+    async fn delete_by_id(
+        &self,
+        id: Uuid,
+        by: Actor,
+        reason: Option<String>,
+    ) -> Result<(), RepoError> {
+        let reason = reason.map(self::reason).transpose()?;
+
+        let txn = self.as_ref().begin().await?;
+        let attendance = Self::lock(&txn, id).await?;
+        let before = Self::load(&txn, attendance.clone()).await?;
+        attendances::Entity::delete_by_id(id).exec(&txn).await?;
+        Self::audit(&txn, &attendance, Some(attendance.status), None, by, reason).await?;
+        audit_log::record(&txn, Some(id), AuditChange::Deleted(&before)).await?;
+        txn.commit().await?;
+
         Ok(())
     }
-    async fn get(&self, filter: AttendancesFilter) -> Result<Vec<Attendance>, RepoError> {
-        let attendances: Vec<attendances::Model> = attendances::Entity::find()
+    async fn correct(
+        &self,
+        id: Uuid,
+        CorrectAttendance { status, reason }: CorrectAttendance,
+        by: Actor,
+    ) -> Result<Attendance, RepoError> {
+        let reason = self::reason(reason)?;
+
+        let txn = self.as_ref().begin().await?;
+        let attendance = Self::lock(&txn, id).await?;
+        let attendance = Self::set_status(&txn, attendance, status, by, reason).await?;
+        txn.commit().await?;
+
+        Self::load(self.as_ref(), attendance).await
+    }
+    async fn mark(
+        &self,
+        class_session_id: Uuid,
+        attendee_id: Uuid,
+        CorrectAttendance { status, reason }: CorrectAttendance,
+        by: Actor,
+    ) -> Result<Attendance, RepoError> {
+        let reason = self::reason(reason)?;
+
+        let txn = self.as_ref().begin().await?;
+
+        let session = class_sessions::Entity::find_by_id(class_session_id)
+            .one(&txn)
+            .await?
+            .ok_or(RepoError::NotFound("class_sessions".to_owned()))?;
+
+        attendees_subjects::Entity::find_by_id((attendee_id, session.subject_id))
+            .one(&txn)
+            .await?
+            .ok_or(RepoError::AttendeeNotFound {
+                id: attendee_id.to_string(),
+            })?;
+
+        let existing = attendances::Entity::find()
+            .filter(attendances::Column::ClassSessionId.eq(class_session_id))
+            .filter(attendances::Column::AttendeeId.eq(attendee_id))
+            .lock_exclusive()
+            .one(&txn)
+            .await?;
+
+        let attendance = match existing {
+            Some(attendance) => Self::set_status(&txn, attendance, status, by, reason).await?,
+            None => {
+                let attendance = Self::insert(
+                    &txn,
+                    CreateAttendance {
+                        attendee_id,
+                        subject_id: session.subject_id,
+                    },
+                    &session,
+                    status,
+                )
+                .await?;
+
+                Self::audit(&txn, &attendance, None, Some(status), by, Some(reason)).await?;
+                audit_log::record(
+                    &txn,
+                    Some(attendance.id),
+                    AuditChange::Created(&Self::load(&txn, attendance.clone()).await?),
+                )
+                .await?;
+                attendance
+            }
+        };
+
+        txn.commit().await?;
+
+        Self::load(self.as_ref(), attendance).await
+    }
+    async fn get(
+        &self,
+        filter: AttendancesFilter,
+        query: ListQuery,
+    ) -> Result<Page<Attendance>, RepoError> {
+        let select = attendances::Entity::find()
             .apply_if(filter.subject_id, |query, subject| {
                 query.filter(attendances::Column::SubjectId.eq(subject))
             })
             .apply_if(filter.attendee_id, |query, attendee| {
-                query.filter(attendances::Column::SubjectId.eq(attendee))
+                query.filter(attendances::Column::AttendeeId.eq(attendee))
             })
-            .all(self.as_ref())
-            .await?
-            .into_iter()
-            .collect_vec();
+            .apply_if(filter.class_session_id, |query, class_session| {
+                query.filter(attendances::Column::ClassSessionId.eq(class_session))
+            });
+        let mut page = query.page(self.as_ref(), select).await?;
+        let attendances = std::mem::take(&mut page.items);
 
         let attendees = attendances
             .load_one(attendees::Entity, self.as_ref())
@@ -107,6 +543,14 @@ impl AttendancesRepoTrait for AttendancesRepo {
             .flatten()
             .map(Attendee::from);
 
+        let sessions = attendances
+            .load_one(class_sessions::Entity, self.as_ref())
+            .await?
+            .into_iter()
+            .flatten()
+            .map(ClassSession::from)
+            .collect_vec();
+
         let subjects: Vec<subjects::Model> = attendances
             .load_one(subjects::Entity, self.as_ref())
             .await
@@ -133,9 +577,11 @@ impl AttendancesRepoTrait for AttendancesRepo {
             .map_into()
             .collect_vec();
 
-        Ok(itertools::izip!(attendances, attendees, subjects)
+        let attendances = itertools::izip!(attendances, attendees, subjects, sessions)
             .map_into()
-            .collect_vec())
+            .collect_vec();
+
+        Ok(page.with_items(attendances))
     }
     async fn get_by_id(&self, id: Uuid) -> Result<Attendance, RepoError> {
         let attendance = attendances::Entity::find_by_id(id)
@@ -143,33 +589,30 @@ impl AttendancesRepoTrait for AttendancesRepo {
             .await?
             .ok_or(RepoError::NotFound("attendacnes".to_owned()))?;
 
-        let attendee = attendance
-            .find_related(attendees::Entity)
-            .one(self.as_ref())
-            .await?
-            .ok_or(RepoError::NotFound("attendees".to_owned()))?
-            .into();
-
-        let subject = attendance
-            .find_related(subjects::Entity)
-            .one(self.as_ref())
-            .await?
-            .ok_or(RepoError::NotFound("subjects".to_owned()))?;
-
-        let instructor = subject
-            .find_related(instructors::Entity)
-            .one(self.as_ref())
-            .await?
-            .map(Instructor::from);
+        Self::load(self.as_ref(), attendance).await
+    }
+    async fn get_audit(
+        &self,
+        filter: AttendanceAuditFilter,
+        query: ListQuery,
+    ) -> Result<Page<AttendanceAuditEntry>, RepoError> {
+        let select = attendance_audit::Entity::find()
+            .apply_if(filter.subject_id, |query, subject| {
+                query.filter(attendance_audit::Column::SubjectId.eq(subject))
+            })
+            .apply_if(filter.attendee_id, |query, attendee| {
+                query.filter(attendance_audit::Column::AttendeeId.eq(attendee))
+            })
+            .apply_if(filter.attendance_id, |query, attendance| {
+                query.filter(attendance_audit::Column::AttendanceId.eq(attendance))
+            });
 
-        let dates = subject
-            .find_related(subject_dates::Entity)
-            .all(self.as_ref())
+        Ok(query
+            .page(self.as_ref(), select)
             .await?
-            .into_iter()
-            .map_into()
-            .collect_vec();
-
-        Ok((attendance, attendee, (subject, dates, instructor).into()).into())
+            .map(AttendanceAuditEntry::from))
+    }
+    async fn current_session(&self, subject_id: Uuid) -> Result<ClassSession, RepoError> {
+        Ok(self.open_session(subject_id).await?.into())
     }
 }
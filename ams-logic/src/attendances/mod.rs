@@ -1,8 +1,10 @@
 mod impls;
 mod models;
+mod window;
 
 pub use impls::*;
 pub use models::*;
+pub use window::*;
 
 use sea_orm::prelude::{async_trait::async_trait, *};
 
@@ -10,14 +12,59 @@ use crate::prelude::*;
 
 #[async_trait]
 pub trait AttendancesRepoTrait {
-    async fn create_one(&self, attendance: CreateAttendance) -> Result<Attendance, RepoError>;
+    async fn create_one(
+        &self,
+        attendance: CreateAttendance,
+        by: Actor,
+    ) -> Result<Attendance, RepoError>;
     async fn create_many(
         &self,
         subject_id: Uuid,
         attendee_ids: Vec<Uuid>,
+        by: Actor,
     ) -> Result<Vec<Attendance>, RepoError>;
-    async fn delete_by_id(&self, id: Uuid) -> Result<(), RepoError>;
-    async fn get(&self, attendaces_filter: AttendancesFilter)
-        -> Result<Vec<Attendance>, RepoError>;
+    /// like `create_many`, but attendees that already have an attendance in the
+    /// open session are skipped instead of failing.
+    async fn create_many_missing(
+        &self,
+        subject_id: Uuid,
+        attendee_ids: Vec<Uuid>,
+        by: Actor,
+    ) -> Result<TakenAttendances, RepoError>;
+    async fn delete_by_id(
+        &self,
+        id: Uuid,
+        by: Actor,
+        reason: Option<String>,
+    ) -> Result<(), RepoError>;
+    /// changes the status of an attendance.
+    async fn correct(
+        &self,
+        id: Uuid,
+        correction: CorrectAttendance,
+        by: Actor,
+    ) -> Result<Attendance, RepoError>;
+    /// sets the status of an attendee in a class session, whether or not the
+    /// attendance was taken and regardless of the session window.
+    async fn mark(
+        &self,
+        class_session_id: Uuid,
+        attendee_id: Uuid,
+        correction: CorrectAttendance,
+        by: Actor,
+    ) -> Result<Attendance, RepoError>;
+    async fn get(
+        &self,
+        attendaces_filter: AttendancesFilter,
+        query: ListQuery,
+    ) -> Result<Page<Attendance>, RepoError>;
     async fn get_by_id(&self, id: Uuid) -> Result<Attendance, RepoError>;
+    /// the changes of attendances, newest first by default.
+    async fn get_audit(
+        &self,
+        filter: AttendanceAuditFilter,
+        query: ListQuery,
+    ) -> Result<Page<AttendanceAuditEntry>, RepoError>;
+    /// the session of the subject that is currently open for attendance.
+    async fn current_session(&self, subject_id: Uuid) -> Result<ClassSession, RepoError>;
 }
@@ -3,7 +3,9 @@ use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
-use crate::entity::attendances;
+pub use crate::entity::sea_orm_active_enums::AttendanceStatus;
+
+use crate::entity::{attendance_audit, attendances};
 use crate::prelude::*;
 
 #[derive(Deserialize, Serialize, Debug, ToSchema)]
@@ -12,21 +14,32 @@ pub struct Attendance {
     pub id: Uuid,
     pub attendee: Attendee,
     pub subject: Subject,
+    /// the class session this attendance was taken in.
+    pub class_session: ClassSession,
+    pub status: AttendanceStatus,
     pub create_at: DateTime<FixedOffset>,
 }
 
-impl From<(attendances::Model, Attendee, Subject)> for Attendance {
+impl From<(attendances::Model, Attendee, Subject, ClassSession)> for Attendance {
     fn from(
-        (attendances::Model { id, create_at, .. }, attendee, subject): (
-            attendances::Model,
-            Attendee,
-            Subject,
-        ),
+        (
+            attendances::Model {
+                id,
+                status,
+                create_at,
+                ..
+            },
+            attendee,
+            subject,
+            class_session,
+        ): (attendances::Model, Attendee, Subject, ClassSession),
     ) -> Self {
         Self {
             id,
             attendee,
             subject,
+            class_session,
+            status,
             create_at,
         }
     }
@@ -45,8 +58,85 @@ pub struct CreateAttendances {
     pub attendee_ids: Vec<Uuid>,
 }
 
+/// the attendances taken for a group of attendees, and the attendees that
+/// already had one.
+pub struct TakenAttendances {
+    pub created: Vec<Attendance>,
+    pub already_attended: Vec<Uuid>,
+}
+
 #[derive(Default)]
 pub struct AttendancesFilter {
     pub subject_id: Option<Uuid>,
     pub attendee_id: Option<Uuid>,
+    pub class_session_id: Option<Uuid>,
+}
+
+/// a manual change of an attendance, the reason ends up in the audit trail.
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CorrectAttendance {
+    pub status: AttendanceStatus,
+    /// at most 256 characters.
+    pub reason: String,
+}
+
+/// one change of an attendance.
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AttendanceAuditEntry {
+    pub id: Uuid,
+    /// the attendance may since have been deleted.
+    pub attendance_id: Uuid,
+    pub subject_id: Uuid,
+    pub attendee_id: Uuid,
+    pub class_session_id: Uuid,
+    pub changed_by: Actor,
+    /// none when the attendance was taken.
+    pub old_status: Option<AttendanceStatus>,
+    /// none when the attendance was deleted.
+    pub new_status: Option<AttendanceStatus>,
+    pub reason: Option<String>,
+    pub create_at: DateTime<FixedOffset>,
+}
+
+impl From<attendance_audit::Model> for AttendanceAuditEntry {
+    fn from(
+        attendance_audit::Model {
+            id,
+            attendance_id,
+            subject_id,
+            attendee_id,
+            class_session_id,
+            changed_by_kind,
+            changed_by,
+            old_status,
+            new_status,
+            reason,
+            create_at,
+        }: attendance_audit::Model,
+    ) -> Self {
+        Self {
+            id,
+            attendance_id,
+            subject_id,
+            attendee_id,
+            class_session_id,
+            changed_by: Actor {
+                kind: changed_by_kind,
+                id: changed_by,
+            },
+            old_status,
+            new_status,
+            reason,
+            create_at,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct AttendanceAuditFilter {
+    pub subject_id: Option<Uuid>,
+    pub attendee_id: Option<Uuid>,
+    pub attendance_id: Option<Uuid>,
 }
@@ -0,0 +1,51 @@
+use chrono::{DateTime, Duration, FixedOffset, Utc};
+use chrono_tz::Tz;
+
+use crate::entity::class_sessions;
+
+/// when attendance may be taken relative to the class sessions.
+#[derive(Debug, Clone)]
+pub struct AttendanceWindow {
+    /// the timezone subject dates are scheduled in.
+    pub timezone: Tz,
+    /// how early before a session starts attendance is accepted.
+    pub early_grace: Duration,
+    /// how late after a session ends attendance is accepted.
+    pub late_grace: Duration,
+}
+
+impl Default for AttendanceWindow {
+    fn default() -> Self {
+        Self {
+            timezone: chrono_tz::Africa::Cairo,
+            early_grace: Duration::zero(),
+            late_grace: Duration::zero(),
+        }
+    }
+}
+
+impl AttendanceWindow {
+    /// finds the session whose window, grace included, contains `now`.
+    /// cancelled sessions are expected to be filtered out already.
+    ///
+    /// # Errors
+    ///
+    /// returns the start of the next window among `sessions` if there is one.
+    pub fn current<'a>(
+        &self,
+        sessions: &'a [class_sessions::Model],
+        now: DateTime<Utc>,
+    ) -> Result<&'a class_sessions::Model, Option<DateTime<FixedOffset>>> {
+        if let Some(session) = sessions.iter().find(|session| {
+            session.starts_at - self.early_grace <= now && now <= session.ends_at + self.late_grace
+        }) {
+            return Ok(session);
+        }
+
+        Err(sessions
+            .iter()
+            .map(|session| session.starts_at - self.early_grace)
+            .filter(|opens_at| *opens_at > now)
+            .min())
+    }
+}
@@ -1,10 +1,23 @@
 use std::{path::PathBuf, sync::Arc};
 
-use sea_orm::{prelude::*, Set};
+use sea_orm::{
+    prelude::*,
+    sea_query::{Alias, Expr, Query, SimpleExpr},
+    FromQueryResult, JoinType, Order, QueryOrder, QueryResult, QuerySelect, QueryTrait, Set,
+    TransactionTrait,
+};
 use tokio::fs;
 
 use super::*;
 
+use crate::{
+    audit_log::{self, AuditChange},
+    entity::{attendees_subjects, face_samples},
+    match_settings::MatchMetric,
+    pagination::{ListQuery, Listable, Page},
+    password::{self, Verification},
+};
+
 pub struct AttendeesRepo {
     db: Arc<DatabaseConnection>,
     assets: PathBuf,
@@ -16,23 +29,34 @@ impl AsRef<DatabaseConnection> for AttendeesRepo {
     }
 }
 
+impl Listable for attendees::Entity {
+    const SORTS: &'static [(&'static str, attendees::Column)] = &[
+        ("name", attendees::Column::Name),
+        ("email", attendees::Column::Email),
+        ("number", attendees::Column::Number),
+        ("createdAt", attendees::Column::CreateAt),
+    ];
+    const DEFAULT_SORT: &'static str = "number";
+    const ID: attendees::Column = attendees::Column::Id;
+    const NAME: Option<attendees::Column> = Some(attendees::Column::Name);
+    const NUMBER: Option<attendees::Column> = Some(attendees::Column::Number);
+    const CREATED_AT: Option<attendees::Column> = Some(attendees::Column::CreateAt);
+}
+
 impl AttendeesRepo {
     pub fn new(db: Arc<DatabaseConnection>, assets: impl Into<PathBuf>) -> Self {
         let assets: PathBuf = assets.into();
-        if assets.exists() == false {
+        if !assets.exists() {
             std::fs::create_dir_all(&assets).unwrap();
         }
 
-        Self {
-            db,
-            assets: assets.into(),
-        }
+        Self { db, assets }
     }
 
     async fn save_image(&self, id: Uuid, image: &[u8], file_name: &str) -> PathBuf {
         let attendee_dir = self.assets.join(id.to_string());
 
-        if attendee_dir.exists() == false {
+        if !attendee_dir.exists() {
             fs::create_dir_all(&attendee_dir).await.unwrap();
         }
 
@@ -42,21 +66,55 @@ impl AttendeesRepo {
 
         image_path
     }
-}
 
-#[async_trait]
-impl AttendeesRepoTrait for AttendeesRepo {
-    async fn create(&self, attendee: CreateAttendee) -> Result<Attendee, RepoError> {
-        Ok(attendees::ActiveModel {
+    /// inserts one attendee, bulk imports insert within their own transaction.
+    pub(crate) async fn insert<C: ConnectionTrait>(
+        conn: &C,
+        attendee: CreateAttendee,
+    ) -> Result<Attendee, RepoError> {
+        let attendee: Attendee = attendees::ActiveModel {
             name: Set(attendee.name),
             email: Set(attendee.email),
-            password: Set(attendee.password),
+            password: Set(password::hash(attendee.password).await?),
             number: Set(attendee.number),
             ..Default::default()
         }
-        .insert(self.as_ref())
+        .insert(conn)
         .await?
-        .into())
+        .into();
+        audit_log::record(conn, Some(attendee.id), AuditChange::Created(&attendee)).await?;
+
+        Ok(attendee)
+    }
+}
+
+/// how many of the nearest face samples are searched for each attendee
+/// `find_nearest` returns with the best strategy when no roster narrows the
+/// search.
+const SAMPLES_PER_MATCH: u64 = 10;
+
+struct NearestRow {
+    attendee: attendees::Model,
+    distance: f64,
+}
+
+impl FromQueryResult for NearestRow {
+    fn from_query_result(res: &QueryResult, pre: &str) -> Result<Self, DbErr> {
+        Ok(Self {
+            attendee: attendees::Model::from_query_result(res, pre)?,
+            distance: res.try_get(pre, "distance")?,
+        })
+    }
+}
+
+#[async_trait]
+impl AttendeesRepoTrait for AttendeesRepo {
+    async fn create(&self, attendee: CreateAttendee) -> Result<Attendee, RepoError> {
+        let txn = self.as_ref().begin().await?;
+        let attendee = Self::insert(&txn, attendee).await?;
+        txn.commit().await?;
+
+        Ok(attendee)
     }
     async fn update(
         &self,
@@ -67,14 +125,20 @@ impl AttendeesRepoTrait for AttendeesRepo {
             email,
             password,
             number,
-            embedding,
         }: UpdateAttendee,
     ) -> Result<Attendee, RepoError> {
-        let mut attendee: attendees::ActiveModel = attendees::Entity::find_by_id(id)
-            .one(self.as_ref())
+        let password = match password {
+            Some(password) => Some(password::hash(password).await?),
+            None => None,
+        };
+        let txn = self.as_ref().begin().await?;
+
+        let before = attendees::Entity::find_by_id(id)
+            .lock_exclusive()
+            .one(&txn)
             .await?
-            .ok_or(RepoError::NotFound("attendees".to_owned()))?
-            .into();
+            .ok_or(RepoError::NotFound("attendees".to_owned()))?;
+        let mut attendee: attendees::ActiveModel = before.clone().into();
 
         if let Some(name) = name {
             attendee.name = Set(name);
@@ -88,9 +152,6 @@ impl AttendeesRepoTrait for AttendeesRepo {
         if let Some(number) = number {
             attendee.number = Set(number);
         }
-        if let Some(embedding) = embedding {
-            attendee.embedding = Set(embedding);
-        }
 
         if let Some((image, file_name)) = image {
             let path = self.save_image(id, &image, &file_name).await;
@@ -99,9 +160,16 @@ impl AttendeesRepoTrait for AttendeesRepo {
             }
         }
 
-        let attendee: attendees::Model = attendee.update(self.as_ref()).await?;
+        let attendee: Attendee = attendee.update(&txn).await?.into();
+        audit_log::record(
+            &txn,
+            Some(id),
+            AuditChange::Updated(&Attendee::from(before), &attendee),
+        )
+        .await?;
+        txn.commit().await?;
 
-        Ok(attendee.into())
+        Ok(attendee)
     }
     async fn get_by_id(&self, id: Uuid) -> Result<Attendee, RepoError> {
         Ok(attendees::Entity::find_by_id(id)
@@ -118,19 +186,163 @@ impl AttendeesRepoTrait for AttendeesRepo {
             .ok_or(RepoError::NotFound("attendees".to_owned()))?
             .into())
     }
-    async fn get_all(&self) -> Result<Vec<Attendee>, RepoError> {
-        let collections: Vec<Attendee> = attendees::Entity::find()
+    async fn get_by_creds(&self, email: String, password: String) -> Result<Attendee, RepoError> {
+        let Some(mut attendee) = attendees::Entity::find()
+            .filter(attendees::Column::Email.eq(&email))
+            .one(self.as_ref())
+            .await?
+        else {
+            password::verify_dummy(password).await;
+            return Err(RepoError::WrongCredentials);
+        };
+
+        match password::verify(password.clone(), attendee.password.clone()).await {
+            Verification::Valid => {}
+            Verification::ValidLegacy => {
+                tracing::info!("re-hashing legacy password for attendee `{}`", attendee.id);
+                let mut active: attendees::ActiveModel = attendee.into();
+                active.password = Set(password::hash(password).await?);
+                attendee = active.update(self.as_ref()).await?;
+            }
+            Verification::Invalid => return Err(RepoError::WrongCredentials),
+        }
+
+        Ok(attendee.into())
+    }
+    async fn get_all(&self, query: ListQuery) -> Result<Page<Attendee>, RepoError> {
+        Ok(query
+            .page(self.as_ref(), attendees::Entity::find())
+            .await?
+            .map(Attendee::from))
+    }
+    async fn find_nearest(
+        &self,
+        FindNearest {
+            embedding,
+            k,
+            metric,
+            threshold,
+            subject_id,
+            strategy,
+        }: FindNearest,
+    ) -> Result<Vec<NearestAttendee>, RepoError> {
+        let embedding: Vec<f32> = embedding.into_iter().map(|value| value as f32).collect();
+        let operator = match metric {
+            MatchMetric::L2 => "<->",
+            MatchMetric::Cosine => "<=>",
+            MatchMetric::Dot => "<#>",
+        };
+
+        let roster = |subject_id: Uuid| {
+            Query::select()
+                .column(attendees_subjects::Column::AttendeeId)
+                .from(attendees_subjects::Entity)
+                .and_where(attendees_subjects::Column::SubjectId.eq(subject_id))
+                .to_owned()
+        };
+
+        let (query, distance) = match strategy {
+            MatchStrategy::Centroid => {
+                let distance: SimpleExpr = Expr::cust_with_values(
+                    &format!(r#""attendees"."embedding" {operator} CAST($1 AS vector)"#),
+                    [embedding],
+                );
+                let query = attendees::Entity::find()
+                    .column_as(distance.clone(), "distance")
+                    .filter(attendees::Column::Embedding.is_not_null())
+                    .filter(Expr::expr(distance.clone()).lte(threshold))
+                    .apply_if(subject_id, |query, subject_id| {
+                        query.filter(attendees::Column::Id.in_subquery(roster(subject_id)))
+                    });
+                (query, distance)
+            }
+            MatchStrategy::Best => {
+                let sample_distance: SimpleExpr = Expr::cust_with_values(
+                    &format!(r#""embedding" {operator} CAST($1 AS vector)"#),
+                    [embedding],
+                );
+                let mut candidates = Query::select();
+                candidates
+                    .column(face_samples::Column::AttendeeId)
+                    .expr_as(sample_distance.clone(), Alias::new("distance"))
+                    .from(face_samples::Entity);
+                match subject_id {
+                    // an index scan only yields its nearest few samples before the
+                    // roster filter applies, which can leave enrolled attendees out.
+                    // a roster is small so all of its samples are compared instead.
+                    Some(subject_id) => {
+                        candidates.and_where(
+                            face_samples::Column::AttendeeId.in_subquery(roster(subject_id)),
+                        );
+                    }
+                    // the samples nearest to the embedding come from an index scan,
+                    // attendees have several samples so more than `k` are fetched
+                    // before keeping the nearest one of each attendee.
+                    None => {
+                        candidates
+                            .order_by_expr(sample_distance, Order::Asc)
+                            .limit(k.saturating_mul(SAMPLES_PER_MATCH));
+                    }
+                }
+                let nearest = Query::select()
+                    .distinct_on([face_samples::Column::AttendeeId])
+                    .column(face_samples::Column::AttendeeId)
+                    .column(Alias::new("distance"))
+                    .from_subquery(candidates, Alias::new("candidates"))
+                    .order_by(face_samples::Column::AttendeeId, Order::Asc)
+                    .order_by(Alias::new("distance"), Order::Asc)
+                    .to_owned();
+
+                let distance: SimpleExpr =
+                    Expr::col((Alias::new("nearest"), Alias::new("distance"))).into();
+                let mut query = attendees::Entity::find()
+                    .column_as(distance.clone(), "distance")
+                    .filter(Expr::expr(distance.clone()).lte(threshold));
+                QueryTrait::query(&mut query).join_subquery(
+                    JoinType::InnerJoin,
+                    nearest,
+                    Alias::new("nearest"),
+                    Expr::col((Alias::new("nearest"), face_samples::Column::AttendeeId))
+                        .equals((attendees::Entity, attendees::Column::Id)),
+                );
+                (query, distance)
+            }
+        };
+
+        let nearest = query
+            .order_by(distance, Order::Asc)
+            .limit(k)
+            .into_model::<NearestRow>()
             .all(self.as_ref())
             .await?
             .into_iter()
-            .map(Attendee::from)
+            .map(|NearestRow { attendee, distance }| NearestAttendee {
+                attendee: attendee.into(),
+                metric,
+                score: distance,
+            })
             .collect();
-        Ok(collections)
+
+        Ok(nearest)
     }
     async fn delete_by_id(&self, id: Uuid) -> Result<(), RepoError> {
-        attendees::Entity::delete_by_id(id)
-            .exec(self.as_ref())
+        let txn = self.as_ref().begin().await?;
+
+        let attendee = attendees::Entity::find_by_id(id)
+            .lock_exclusive()
+            .one(&txn)
             .await?;
+        if let Some(attendee) = attendee {
+            attendees::Entity::delete_by_id(id).exec(&txn).await?;
+            audit_log::record(
+                &txn,
+                Some(id),
+                AuditChange::Deleted(&Attendee::from(attendee)),
+            )
+            .await?;
+        }
+        txn.commit().await?;
+
         Ok(())
     }
 }
@@ -7,7 +7,10 @@ use uuid::Uuid;
 pub use impls::*;
 pub use models::*;
 
-use crate::error::RepoError;
+use crate::{
+    error::RepoError,
+    pagination::{ListQuery, Page},
+};
 
 use crate::entity::attendees;
 
@@ -21,6 +24,10 @@ pub trait AttendeesRepoTrait {
     ) -> Result<Attendee, RepoError>;
     async fn get_by_id(&self, id: Uuid) -> Result<Attendee, RepoError>;
     async fn get_by_email(&self, email: String) -> Result<Attendee, RepoError>;
-    async fn get_all(&self) -> Result<Vec<Attendee>, RepoError>;
+    async fn get_by_creds(&self, email: String, password: String) -> Result<Attendee, RepoError>;
+    async fn get_all(&self, query: ListQuery) -> Result<Page<Attendee>, RepoError>;
+    /// the attendees with the nearest embeddings within the threshold,
+    /// nearest first.
+    async fn find_nearest(&self, query: FindNearest) -> Result<Vec<NearestAttendee>, RepoError>;
     async fn delete_by_id(&self, id: Uuid) -> Result<(), RepoError>;
 }
@@ -1,11 +1,12 @@
 use std::borrow::Cow;
 
 use chrono::{DateTime, FixedOffset};
+use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
-use crate::entity::attendees;
+use crate::{entity::attendees, match_settings::MatchMetric};
 
 #[derive(Deserialize, Serialize, Debug, ToSchema, Default)]
 #[serde(rename_all = "camelCase")]
@@ -46,11 +47,46 @@ impl From<attendees::Model> for Attendee {
             password,
             create_at,
             updated_at,
-            embedding,
+            embedding: embedding.map(|embedding| embedding.into_iter().map_into().collect()),
         }
     }
 }
 
+/// how an attendee's face samples are compared to a searched embedding.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum MatchStrategy {
+    /// the distance to the attendee's nearest sample.
+    Best,
+    /// the distance to the centroid of the attendee's samples.
+    Centroid,
+}
+
+/// what `find_nearest` searches for.
+#[derive(Debug, Clone)]
+pub struct FindNearest {
+    pub embedding: Vec<f64>,
+    /// how many attendees to return at most.
+    pub k: u64,
+    pub metric: MatchMetric,
+    /// the greatest distance under `metric` that still counts as a match.
+    pub threshold: f64,
+    /// only attendees enrolled in this subject.
+    pub subject_id: Option<Uuid>,
+    pub strategy: MatchStrategy,
+}
+
+/// an attendee whose embedding is near the searched one.
+#[derive(Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NearestAttendee {
+    #[serde(flatten)]
+    pub attendee: Attendee,
+    pub metric: MatchMetric,
+    /// the distance under `metric` to the searched embedding, lower is nearer.
+    pub score: f64,
+}
+
 #[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateAttendee {
@@ -75,7 +111,5 @@ pub struct UpdateAttendee {
     #[schema(example = 3232323)]
     pub number: Option<i64>,
     #[serde(skip)]
-    pub embedding: Option<Option<Vec<f64>>>,
-    #[serde(skip)]
     pub image: Option<(Cow<'static, [u8]>, Cow<'static, str>)>,
 }
@@ -0,0 +1,162 @@
+use std::{
+    future::Future,
+    sync::{Arc, OnceLock},
+};
+
+use sea_orm::{
+    prelude::{async_trait::async_trait, *},
+    QueryTrait, Set,
+};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use super::*;
+
+use crate::{entity::audit_log, pagination::Listable};
+
+tokio::task_local! {
+    static CONTEXT: AuditContext;
+}
+
+impl AuditContext {
+    pub fn new(method: String, route: String, path: String) -> Self {
+        Self {
+            request: Some(AuditRequest {
+                method,
+                route,
+                path,
+            }),
+            actor: OnceLock::new(),
+        }
+    }
+
+    /// the context of a job the server runs on its own, its changes are
+    /// recorded without a user or request.
+    pub fn system() -> Self {
+        Self {
+            request: None,
+            actor: OnceLock::new(),
+        }
+    }
+
+    /// records the changes made while `future` runs as made by this request.
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CONTEXT.scope(self, future).await
+    }
+
+    /// sets the user behind the current request, if it's audited.
+    pub fn authenticated(actor: Actor) {
+        let _ = CONTEXT.try_with(|context| context.actor.set(actor));
+    }
+}
+
+/// appends `change` to the audit log when it's made by an authenticated api
+/// request or a server job, call it with the transaction that makes the change
+/// so both are kept or neither is.
+pub(crate) async fn record<C: ConnectionTrait, T: Serialize>(
+    conn: &C,
+    entity_id: Option<Uuid>,
+    change: AuditChange<T>,
+) -> Result<(), RepoError> {
+    let Ok(Some((actor, request))) = CONTEXT.try_with(|context| {
+        let actor = context.actor.get().copied();
+        if context.request.is_some() && actor.is_none() {
+            return None;
+        }
+        Some((actor, context.request.clone()))
+    }) else {
+        return Ok(());
+    };
+
+    let json = |value: T| serde_json::to_value(value).map_err(|_| RepoError::Unknown);
+    let (before, after) = match change {
+        AuditChange::Created(after) => (None, Some(json(after)?)),
+        AuditChange::Updated(before, after) => diff(json(before)?, json(after)?),
+        AuditChange::Deleted(before) => (Some(json(before)?), None),
+    };
+
+    audit_log::ActiveModel {
+        actor_kind: Set(actor.map(|actor| actor.kind)),
+        actor_id: Set(actor.map(|actor| actor.id)),
+        method: Set(request.as_ref().map(|request| request.method.clone())),
+        route: Set(request.as_ref().map(|request| request.route.clone())),
+        path: Set(request.map(|request| request.path)),
+        entity_id: Set(entity_id),
+        before: Set(before),
+        after: Set(after),
+        ..Default::default()
+    }
+    .insert(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// drops the fields both sides agree on, when both are objects.
+fn diff(before: Value, after: Value) -> (Option<Value>, Option<Value>) {
+    let (Value::Object(before), Value::Object(after)) = (&before, &after) else {
+        return (Some(before), Some(after));
+    };
+
+    let mut old = Map::new();
+    let mut new = Map::new();
+    let keys = before
+        .keys()
+        .chain(after.keys().filter(|key| !before.contains_key(*key)));
+    for key in keys {
+        let (from, to) = (before.get(key), after.get(key));
+        if from != to {
+            old.insert(key.clone(), from.cloned().unwrap_or(Value::Null));
+            new.insert(key.clone(), to.cloned().unwrap_or(Value::Null));
+        }
+    }
+
+    (Some(Value::Object(old)), Some(Value::Object(new)))
+}
+
+pub struct AuditLogRepo(pub Arc<DatabaseConnection>);
+
+impl Listable for audit_log::Entity {
+    const SORTS: &'static [(&'static str, audit_log::Column)] =
+        &[("createdAt", audit_log::Column::CreateAt)];
+    const DEFAULT_SORT: &'static str = "-createdAt";
+    const ID: audit_log::Column = audit_log::Column::Id;
+    const CREATED_AT: Option<audit_log::Column> = Some(audit_log::Column::CreateAt);
+}
+
+impl AsRef<DatabaseConnection> for AuditLogRepo {
+    fn as_ref(&self) -> &DatabaseConnection {
+        &self.0
+    }
+}
+
+#[async_trait]
+impl AuditLogRepoTrait for AuditLogRepo {
+    async fn get(
+        &self,
+        filter: AuditFilter,
+        query: ListQuery,
+    ) -> Result<Page<AuditEntry>, RepoError> {
+        let select = audit_log::Entity::find()
+            .apply_if(filter.actor_id, |query, actor| {
+                query.filter(audit_log::Column::ActorId.eq(actor))
+            })
+            .apply_if(filter.actor_kind, |query, kind| {
+                query.filter(audit_log::Column::ActorKind.eq(kind))
+            })
+            .apply_if(filter.method, |query, method| {
+                query.filter(audit_log::Column::Method.eq(method.to_uppercase()))
+            })
+            .apply_if(filter.route, |query, route| {
+                query.filter(audit_log::Column::Route.eq(route))
+            })
+            .apply_if(filter.entity_id, |query, entity| {
+                query.filter(audit_log::Column::EntityId.eq(entity))
+            });
+
+        Ok(query
+            .page(self.as_ref(), select)
+            .await?
+            .map(AuditEntry::from))
+    }
+}
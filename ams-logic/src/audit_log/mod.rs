@@ -0,0 +1,21 @@
+mod impls;
+mod models;
+
+pub use impls::*;
+pub use models::*;
+
+use sea_orm::prelude::async_trait::async_trait;
+
+use crate::prelude::*;
+
+/// the append-only log of the changes made through the api, the repos append
+/// to it with `audit_log::record` in the transaction that makes a change.
+#[async_trait]
+pub trait AuditLogRepoTrait {
+    /// the entries, newest first by default.
+    async fn get(
+        &self,
+        filter: AuditFilter,
+        query: ListQuery,
+    ) -> Result<Page<AuditEntry>, RepoError>;
+}
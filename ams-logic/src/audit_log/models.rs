@@ -0,0 +1,100 @@
+use std::sync::OnceLock;
+
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    entity::audit_log,
+    sessions::{Actor, UserKind},
+};
+
+/// one create, update or delete made through the api or by the server itself.
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub id: Uuid,
+    /// none for the changes the server makes on its own, like the class
+    /// sessions it generates on schedule, so are the request fields.
+    pub actor: Option<Actor>,
+    pub method: Option<String>,
+    /// the documented route, e.g. `/subjects/{subject_id}`.
+    pub route: Option<String>,
+    pub path: Option<String>,
+    /// the entity that was changed, none for settings and bulk changes.
+    pub entity_id: Option<Uuid>,
+    /// none when the entity was created.
+    #[schema(value_type = Option<Object>)]
+    pub before: Option<Value>,
+    /// none when the entity was deleted.
+    #[schema(value_type = Option<Object>)]
+    pub after: Option<Value>,
+    pub create_at: DateTime<FixedOffset>,
+}
+
+impl From<audit_log::Model> for AuditEntry {
+    fn from(
+        audit_log::Model {
+            id,
+            actor_kind,
+            actor_id,
+            method,
+            route,
+            path,
+            entity_id,
+            before,
+            after,
+            create_at,
+        }: audit_log::Model,
+    ) -> Self {
+        Self {
+            id,
+            actor: actor_kind
+                .zip(actor_id)
+                .map(|(kind, id)| Actor { kind, id }),
+            method,
+            route,
+            path,
+            entity_id,
+            before,
+            after,
+            create_at,
+        }
+    }
+}
+
+/// the api request or server job behind the changes recorded while it's
+/// handled, see [`AuditContext::scope`].
+#[derive(Debug)]
+pub struct AuditContext {
+    /// none for the server's own jobs.
+    pub(crate) request: Option<AuditRequest>,
+    /// set once the request is authenticated.
+    pub(crate) actor: OnceLock<Actor>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct AuditRequest {
+    pub(crate) method: String,
+    /// the documented route, e.g. `/subjects/{subject_id}`.
+    pub(crate) route: String,
+    pub(crate) path: String,
+}
+
+/// a change of an entity, as the api returns it.
+pub(crate) enum AuditChange<T> {
+    Created(T),
+    Updated(T, T),
+    Deleted(T),
+}
+
+#[derive(Default)]
+pub struct AuditFilter {
+    pub actor_id: Option<Uuid>,
+    pub actor_kind: Option<UserKind>,
+    pub method: Option<String>,
+    pub route: Option<String>,
+    pub entity_id: Option<Uuid>,
+}
@@ -0,0 +1,306 @@
+use std::sync::Arc;
+
+use chrono::{NaiveDate, Utc};
+use chrono_tz::Tz;
+use itertools::Itertools;
+use sea_orm::{
+    prelude::{async_trait::async_trait, *},
+    sea_query::OnConflict,
+    QueryOrder, QuerySelect, QueryTrait, Set, TransactionTrait,
+};
+
+use super::*;
+
+use crate::audit_log::{self, AuditChange};
+use crate::entity::{subject_dates, subjects};
+use crate::pagination::Listable;
+use crate::terms::TermCalendar;
+
+pub struct ClassSessionsRepo {
+    db: Arc<DatabaseConnection>,
+    timezone: Tz,
+}
+
+impl Listable for class_sessions::Entity {
+    const SORTS: &'static [(&'static str, class_sessions::Column)] = &[
+        ("startsAt", class_sessions::Column::StartsAt),
+        ("createdAt", class_sessions::Column::CreateAt),
+    ];
+    const DEFAULT_SORT: &'static str = "startsAt";
+    const ID: class_sessions::Column = class_sessions::Column::Id;
+    const CREATED_AT: Option<class_sessions::Column> = Some(class_sessions::Column::CreateAt);
+}
+
+impl AsRef<DatabaseConnection> for ClassSessionsRepo {
+    fn as_ref(&self) -> &DatabaseConnection {
+        &self.db
+    }
+}
+
+impl ClassSessionsRepo {
+    pub fn new(db: Arc<DatabaseConnection>, timezone: Tz) -> Self {
+        Self { db, timezone }
+    }
+
+    async fn find<C: ConnectionTrait>(
+        conn: &C,
+        id: Uuid,
+    ) -> Result<class_sessions::Model, RepoError> {
+        class_sessions::Entity::find_by_id(id)
+            .one(conn)
+            .await?
+            .ok_or(RepoError::NotFound("class_sessions".to_owned()))
+    }
+
+    /// every session of a subject between the given local dates.
+    async fn between<C: ConnectionTrait>(
+        conn: &C,
+        subject_id: Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<ClassSession>, RepoError> {
+        Ok(class_sessions::Entity::find()
+            .filter(class_sessions::Column::SubjectId.eq(subject_id))
+            .filter(class_sessions::Column::OccurrenceDate.between(from, to))
+            .order_by_asc(class_sessions::Column::StartsAt)
+            .all(conn)
+            .await?
+            .into_iter()
+            .map(ClassSession::from)
+            .collect())
+    }
+}
+
+/// the longest range sessions can be generated for at once, in days.
+pub const MAX_GENERATE_DAYS: i64 = 366;
+
+fn check_range(from: NaiveDate, to: NaiveDate) -> Result<(), RepoError> {
+    if from > to || (to - from).num_days() > MAX_GENERATE_DAYS {
+        return Err(RepoError::InvalidRange {
+            from,
+            to,
+            max_days: MAX_GENERATE_DAYS,
+        });
+    }
+    Ok(())
+}
+
+/// inserts the sessions of a subject between the given local dates that don't
+/// exist yet, already generated sessions are left as they are so cancelled and
+/// rescheduled ones survive regeneration. days outside the subject's term or on
+/// its holidays are skipped.
+///
+/// every inserted session is recorded in the audit log, returns how many were.
+pub(crate) async fn generate_sessions<C: ConnectionTrait>(
+    conn: &C,
+    timezone: Tz,
+    subject_id: Uuid,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<u64, RepoError> {
+    let subject = subjects::Entity::find_by_id(subject_id)
+        .one(conn)
+        .await?
+        .ok_or(RepoError::NotFound("subjects".to_owned()))?;
+
+    let dates = subject
+        .find_related(subject_dates::Entity)
+        .all(conn)
+        .await?;
+
+    let calendar = TermCalendar::of_subject(conn, &subject).await?;
+
+    let sessions = occurrences(timezone, &dates, from, to)
+        .into_iter()
+        .filter(|occurrence| {
+            calendar
+                .as_ref()
+                .is_none_or(|calendar| calendar.is_class_day(occurrence.date))
+        })
+        .map(|occurrence| class_sessions::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            subject_id: Set(subject_id),
+            subject_date_id: Set(Some(occurrence.subject_date_id)),
+            occurrence_date: Set(occurrence.date),
+            starts_at: Set(occurrence.starts_at),
+            ends_at: Set(occurrence.ends_at),
+            ..Default::default()
+        })
+        .collect_vec();
+
+    if sessions.is_empty() {
+        return Ok(0);
+    }
+
+    // the ids are picked here to tell the inserted sessions from the ones that
+    // already existed.
+    let ids = sessions
+        .iter()
+        .filter_map(|session| session.id.clone().take())
+        .collect_vec();
+    let inserted = class_sessions::Entity::insert_many(sessions)
+        .on_conflict(
+            OnConflict::columns([
+                class_sessions::Column::SubjectDateId,
+                class_sessions::Column::OccurrenceDate,
+            ])
+            .do_nothing()
+            .to_owned(),
+        )
+        .exec_without_returning(conn)
+        .await?;
+    if inserted == 0 {
+        return Ok(0);
+    }
+
+    let inserted = class_sessions::Entity::find()
+        .filter(class_sessions::Column::Id.is_in(ids))
+        .order_by_asc(class_sessions::Column::StartsAt)
+        .all(conn)
+        .await?;
+    for session in &inserted {
+        audit_log::record(
+            conn,
+            Some(session.id),
+            AuditChange::Created(&ClassSession::from(session.clone())),
+        )
+        .await?;
+    }
+
+    Ok(inserted.len() as u64)
+}
+
+#[async_trait]
+impl ClassSessionsRepoTrait for ClassSessionsRepo {
+    async fn generate(
+        &self,
+        subject_id: Uuid,
+        GenerateClassSessions { from, to }: GenerateClassSessions,
+    ) -> Result<Vec<ClassSession>, RepoError> {
+        check_range(from, to)?;
+        let txn = self.as_ref().begin().await?;
+
+        generate_sessions(&txn, self.timezone, subject_id, from, to).await?;
+        let sessions = Self::between(&txn, subject_id, from, to).await?;
+        txn.commit().await?;
+
+        Ok(sessions)
+    }
+
+    async fn generate_all(&self, from: NaiveDate, to: NaiveDate) -> Result<u64, RepoError> {
+        check_range(from, to)?;
+
+        let txn = self.as_ref().begin().await?;
+
+        let subject_ids: Vec<Uuid> = subjects::Entity::find()
+            .select_only()
+            .column(subjects::Column::Id)
+            .into_tuple()
+            .all(&txn)
+            .await?;
+
+        let mut generated = 0;
+        for subject_id in subject_ids {
+            generated += generate_sessions(&txn, self.timezone, subject_id, from, to).await?;
+        }
+        txn.commit().await?;
+
+        Ok(generated)
+    }
+
+    async fn get(
+        &self,
+        filter: ClassSessionsFilter,
+        query: ListQuery,
+    ) -> Result<Page<ClassSession>, RepoError> {
+        let select = class_sessions::Entity::find()
+            .apply_if(filter.subject_id, |query, subject| {
+                query.filter(class_sessions::Column::SubjectId.eq(subject))
+            })
+            .apply_if(filter.from, |query, from| {
+                query.filter(class_sessions::Column::OccurrenceDate.gte(from))
+            })
+            .apply_if(filter.to, |query, to| {
+                query.filter(class_sessions::Column::OccurrenceDate.lte(to))
+            });
+
+        Ok(query
+            .page(self.as_ref(), select)
+            .await?
+            .map(ClassSession::from))
+    }
+
+    async fn get_by_id(&self, id: Uuid) -> Result<ClassSession, RepoError> {
+        Ok(Self::find(self.as_ref(), id).await?.into())
+    }
+
+    async fn cancel(
+        &self,
+        id: Uuid,
+        CancelClassSession { note }: CancelClassSession,
+    ) -> Result<ClassSession, RepoError> {
+        let txn = self.as_ref().begin().await?;
+
+        let before = Self::find(&txn, id).await?;
+        let mut session: class_sessions::ActiveModel = before.clone().into();
+
+        session.status = Set(ClassSessionStatus::Cancelled);
+        session.cancel_reason = Set(Some(ClassSessionCancelReason::Instructor));
+        if let Some(note) = note {
+            session.note = Set(Some(note));
+        }
+        session.updated_at = Set(Utc::now().into());
+
+        let session: ClassSession = session.update(&txn).await?.into();
+        audit_log::record(
+            &txn,
+            Some(id),
+            AuditChange::Updated(&ClassSession::from(before), &session),
+        )
+        .await?;
+        txn.commit().await?;
+
+        Ok(session)
+    }
+
+    async fn reschedule(
+        &self,
+        id: Uuid,
+        RescheduleClassSession {
+            starts_at,
+            ends_at,
+            note,
+        }: RescheduleClassSession,
+    ) -> Result<ClassSession, RepoError> {
+        if starts_at >= ends_at {
+            return Err(RepoError::InvalidClassSessionTimes);
+        }
+
+        let txn = self.as_ref().begin().await?;
+
+        let before = Self::find(&txn, id).await?;
+        if before.status == ClassSessionStatus::Cancelled {
+            return Err(RepoError::ClassSessionCancelled { id });
+        }
+
+        let mut session: class_sessions::ActiveModel = before.clone().into();
+        session.status = Set(ClassSessionStatus::Rescheduled);
+        session.starts_at = Set(starts_at);
+        session.ends_at = Set(ends_at);
+        if let Some(note) = note {
+            session.note = Set(Some(note));
+        }
+        session.updated_at = Set(Utc::now().into());
+
+        let session: ClassSession = session.update(&txn).await?.into();
+        audit_log::record(
+            &txn,
+            Some(id),
+            AuditChange::Updated(&ClassSession::from(before), &session),
+        )
+        .await?;
+        txn.commit().await?;
+
+        Ok(session)
+    }
+}
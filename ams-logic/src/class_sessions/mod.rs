@@ -0,0 +1,47 @@
+mod impls;
+mod models;
+mod schedule;
+
+pub use impls::*;
+pub use models::*;
+pub use schedule::*;
+
+use chrono::NaiveDate;
+use sea_orm::prelude::async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::{
+    error::RepoError,
+    pagination::{ListQuery, Page},
+};
+
+use crate::entity::class_sessions;
+
+#[async_trait]
+pub trait ClassSessionsRepoTrait {
+    /// generates the missing sessions of a subject between the given local
+    /// dates and returns all of its sessions in that range. the range can span
+    /// at most a year.
+    async fn generate(
+        &self,
+        subject_id: Uuid,
+        range: GenerateClassSessions,
+    ) -> Result<Vec<ClassSession>, RepoError>;
+    /// generates the missing sessions of every subject between the given local
+    /// dates, spanning at most a year.
+    async fn generate_all(&self, from: NaiveDate, to: NaiveDate) -> Result<u64, RepoError>;
+    async fn get(
+        &self,
+        filter: ClassSessionsFilter,
+        query: ListQuery,
+    ) -> Result<Page<ClassSession>, RepoError>;
+    async fn get_by_id(&self, id: Uuid) -> Result<ClassSession, RepoError>;
+    async fn cancel(&self, id: Uuid, cancel: CancelClassSession)
+        -> Result<ClassSession, RepoError>;
+    /// moves a session that isn't cancelled.
+    async fn reschedule(
+        &self,
+        id: Uuid,
+        reschedule: RescheduleClassSession,
+    ) -> Result<ClassSession, RepoError>;
+}
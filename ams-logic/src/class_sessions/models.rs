@@ -0,0 +1,92 @@
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub use crate::entity::sea_orm_active_enums::{ClassSessionCancelReason, ClassSessionStatus};
+
+use super::class_sessions;
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassSession {
+    pub id: Uuid,
+    pub subject_id: Uuid,
+    /// the weekly subject date this session was generated from, if it still exists.
+    pub subject_date_id: Option<Uuid>,
+    /// the local date the session was originally scheduled on.
+    #[schema(value_type = String, format = Date)]
+    pub occurrence_date: NaiveDate,
+    pub starts_at: DateTime<FixedOffset>,
+    pub ends_at: DateTime<FixedOffset>,
+    pub status: ClassSessionStatus,
+    /// why the session was cancelled, set only while it is cancelled.
+    pub cancel_reason: Option<ClassSessionCancelReason>,
+    pub note: Option<String>,
+    pub create_at: DateTime<FixedOffset>,
+    pub updated_at: DateTime<FixedOffset>,
+}
+
+impl From<class_sessions::Model> for ClassSession {
+    fn from(
+        class_sessions::Model {
+            id,
+            subject_id,
+            subject_date_id,
+            occurrence_date,
+            starts_at,
+            ends_at,
+            status,
+            cancel_reason,
+            note,
+            create_at,
+            updated_at,
+        }: class_sessions::Model,
+    ) -> Self {
+        Self {
+            id,
+            subject_id,
+            subject_date_id,
+            occurrence_date,
+            starts_at,
+            ends_at,
+            status,
+            cancel_reason,
+            note,
+            create_at,
+            updated_at,
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateClassSessions {
+    #[schema(value_type = String, format = Date)]
+    pub from: NaiveDate,
+    #[schema(value_type = String, format = Date)]
+    pub to: NaiveDate,
+}
+
+#[derive(Deserialize, ToSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelClassSession {
+    pub note: Option<String>,
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RescheduleClassSession {
+    pub starts_at: DateTime<FixedOffset>,
+    pub ends_at: DateTime<FixedOffset>,
+    pub note: Option<String>,
+}
+
+#[derive(Default)]
+pub struct ClassSessionsFilter {
+    pub subject_id: Option<Uuid>,
+    /// first local occurrence date, inclusive.
+    pub from: Option<NaiveDate>,
+    /// last local occurrence date, inclusive.
+    pub to: Option<NaiveDate>,
+}
@@ -0,0 +1,60 @@
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, Offset, TimeZone};
+use chrono_tz::Tz;
+use uuid::Uuid;
+
+use crate::entity::subject_dates;
+
+/// a concrete occurrence of a weekly subject date.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Occurrence {
+    pub subject_date_id: Uuid,
+    /// the local date the occurrence takes place on.
+    pub date: NaiveDate,
+    pub starts_at: DateTime<FixedOffset>,
+    pub ends_at: DateTime<FixedOffset>,
+}
+
+fn occurrence_on(
+    timezone: Tz,
+    date: NaiveDate,
+    subject_date: &subject_dates::Model,
+) -> Option<Occurrence> {
+    if date.weekday().number_from_monday() as i32 != subject_date.day_of_week {
+        return None;
+    }
+
+    let starts_at = timezone
+        .from_local_datetime(&date.and_time(subject_date.start_time))
+        .earliest()?;
+    let ends_at = timezone
+        .from_local_datetime(&date.and_time(subject_date.end_time))
+        .latest()?;
+
+    Some(Occurrence {
+        subject_date_id: subject_date.id,
+        date,
+        starts_at: starts_at.with_timezone(&starts_at.offset().fix()),
+        ends_at: ends_at.with_timezone(&ends_at.offset().fix()),
+    })
+}
+
+/// the occurrences of the subject dates between the given local dates, ordered by start.
+pub fn occurrences(
+    timezone: Tz,
+    subject_dates: &[subject_dates::Model],
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Vec<Occurrence> {
+    let mut occurrences: Vec<Occurrence> = from
+        .iter_days()
+        .take_while(|date| *date <= to)
+        .flat_map(|date| {
+            subject_dates
+                .iter()
+                .filter_map(move |subject_date| occurrence_on(timezone, date, subject_date))
+        })
+        .collect();
+
+    occurrences.sort_by_key(|occurrence| occurrence.starts_at);
+    occurrences
+}
@@ -0,0 +1,167 @@
+use std::{path::PathBuf, sync::Arc};
+
+use chrono::Utc;
+use sea_orm::{
+    prelude::{async_trait::async_trait, *},
+    sea_query::Expr,
+    QueryOrder, Set, TransactionTrait,
+};
+use sha2::{Digest, Sha256};
+use tokio::fs;
+
+use super::*;
+
+use crate::{
+    audit_log::{self, AuditChange},
+    pagination::Listable,
+    sessions::hex,
+};
+
+pub struct ClassifiersRepo {
+    db: Arc<DatabaseConnection>,
+    dir: PathBuf,
+}
+
+impl Listable for classifiers::Entity {
+    const SORTS: &'static [(&'static str, classifiers::Column)] = &[
+        ("version", classifiers::Column::Version),
+        ("createdAt", classifiers::Column::CreateAt),
+    ];
+    const DEFAULT_SORT: &'static str = "-version";
+    const ID: classifiers::Column = classifiers::Column::Id;
+    const CREATED_AT: Option<classifiers::Column> = Some(classifiers::Column::CreateAt);
+}
+
+impl AsRef<DatabaseConnection> for ClassifiersRepo {
+    fn as_ref(&self) -> &DatabaseConnection {
+        &self.db
+    }
+}
+
+impl ClassifiersRepo {
+    pub fn new(db: Arc<DatabaseConnection>, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            db,
+            dir: dir.into(),
+        }
+    }
+
+    async fn find<C: ConnectionTrait>(conn: &C, id: Uuid) -> Result<classifiers::Model, RepoError> {
+        classifiers::Entity::find_by_id(id)
+            .one(conn)
+            .await?
+            .ok_or(RepoError::NotFound("classifiers".to_owned()))
+    }
+}
+
+#[async_trait]
+impl ClassifiersRepoTrait for ClassifiersRepo {
+    async fn create(
+        &self,
+        CreateClassifier {
+            data,
+            uploaded_by,
+            notes,
+        }: CreateClassifier,
+    ) -> Result<ClassifierModel, RepoError> {
+        let id = Uuid::new_v4();
+        fs::create_dir_all(&self.dir)
+            .await
+            .map_err(|_| RepoError::Unknown)?;
+        let path = self.dir.join(id.to_string());
+        fs::write(&path, &data)
+            .await
+            .map_err(|_| RepoError::Unknown)?;
+
+        let txn = self.as_ref().begin().await?;
+
+        let classifier: ClassifierModel = classifiers::ActiveModel {
+            id: Set(id),
+            checksum: Set(hex(&Sha256::digest(&data))),
+            size: Set(data.len() as i64),
+            path: Set(path.to_string_lossy().into()),
+            uploaded_by: Set(uploaded_by),
+            notes: Set(notes),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?
+        .into();
+        audit_log::record(&txn, Some(id), AuditChange::Created(&classifier)).await?;
+        txn.commit().await?;
+
+        Ok(classifier)
+    }
+    async fn get_all(&self, query: ListQuery) -> Result<Page<ClassifierModel>, RepoError> {
+        Ok(query
+            .page(self.as_ref(), classifiers::Entity::find())
+            .await?
+            .map(ClassifierModel::from))
+    }
+    async fn get_by_id(&self, id: Uuid) -> Result<ClassifierModel, RepoError> {
+        Ok(Self::find(self.as_ref(), id).await?.into())
+    }
+    async fn get_active(&self) -> Result<Option<ClassifierModel>, RepoError> {
+        Ok(classifiers::Entity::find()
+            .filter(classifiers::Column::Active.eq(true))
+            .one(self.as_ref())
+            .await?
+            .map(ClassifierModel::from))
+    }
+    async fn data(&self, id: Uuid) -> Result<Vec<u8>, RepoError> {
+        let classifier = Self::find(self.as_ref(), id).await?;
+
+        fs::read(&classifier.path)
+            .await
+            .map_err(|_| RepoError::Unknown)
+    }
+    async fn activate(&self, id: Uuid) -> Result<ClassifierModel, RepoError> {
+        let txn = self.as_ref().begin().await?;
+
+        let before = Self::find(&txn, id).await?;
+        let deactivated = classifiers::Entity::find()
+            .filter(classifiers::Column::Active.eq(true))
+            .filter(classifiers::Column::Id.ne(id))
+            .all(&txn)
+            .await?;
+
+        classifiers::Entity::update_many()
+            .col_expr(classifiers::Column::Active, Expr::value(false))
+            .filter(classifiers::Column::Active.eq(true))
+            .exec(&txn)
+            .await?;
+
+        let mut classifier: classifiers::ActiveModel = before.clone().into();
+        classifier.active = Set(true);
+        classifier.activated_at = Set(Some(Utc::now().into()));
+        let classifier: ClassifierModel = classifier.update(&txn).await?.into();
+
+        for previous in deactivated {
+            let before = ClassifierModel::from(previous.clone());
+            let after = ClassifierModel::from(classifiers::Model {
+                active: false,
+                ..previous
+            });
+            audit_log::record(&txn, Some(before.id), AuditChange::Updated(&before, &after)).await?;
+        }
+        audit_log::record(
+            &txn,
+            Some(id),
+            AuditChange::Updated(&ClassifierModel::from(before), &classifier),
+        )
+        .await?;
+        txn.commit().await?;
+
+        Ok(classifier)
+    }
+    async fn previous(&self) -> Result<ClassifierModel, RepoError> {
+        Ok(classifiers::Entity::find()
+            .filter(classifiers::Column::Active.eq(false))
+            .filter(classifiers::Column::ActivatedAt.is_not_null())
+            .order_by_desc(classifiers::Column::ActivatedAt)
+            .one(self.as_ref())
+            .await?
+            .ok_or(RepoError::NoClassifierToRollBack)?
+            .into())
+    }
+}
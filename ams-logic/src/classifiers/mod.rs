@@ -0,0 +1,31 @@
+mod impls;
+mod models;
+
+pub use impls::*;
+pub use models::*;
+
+use sea_orm::prelude::async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::{
+    error::RepoError,
+    pagination::{ListQuery, Page},
+};
+
+use crate::entity::classifiers;
+
+/// every classifier uploaded for the face recognition service, at most one of
+/// them is active.
+#[async_trait]
+pub trait ClassifiersRepoTrait {
+    async fn create(&self, classifier: CreateClassifier) -> Result<ClassifierModel, RepoError>;
+    /// newest version first by default.
+    async fn get_all(&self, query: ListQuery) -> Result<Page<ClassifierModel>, RepoError>;
+    async fn get_by_id(&self, id: Uuid) -> Result<ClassifierModel, RepoError>;
+    async fn get_active(&self) -> Result<Option<ClassifierModel>, RepoError>;
+    /// the uploaded classifier file.
+    async fn data(&self, id: Uuid) -> Result<Vec<u8>, RepoError>;
+    async fn activate(&self, id: Uuid) -> Result<ClassifierModel, RepoError>;
+    /// the classifier that was active before the current one.
+    async fn previous(&self) -> Result<ClassifierModel, RepoError>;
+}
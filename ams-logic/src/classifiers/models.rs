@@ -0,0 +1,60 @@
+use std::borrow::Cow;
+
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::entity::classifiers;
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassifierModel {
+    pub id: Uuid,
+    pub version: i32,
+    /// hex encoded sha256 of the classifier file.
+    pub checksum: String,
+    /// in bytes.
+    pub size: i64,
+    pub uploaded_by: Option<Uuid>,
+    pub notes: Option<String>,
+    pub active: bool,
+    pub activated_at: Option<DateTime<FixedOffset>>,
+    pub create_at: DateTime<FixedOffset>,
+}
+
+impl From<classifiers::Model> for ClassifierModel {
+    fn from(
+        classifiers::Model {
+            id,
+            version,
+            checksum,
+            size,
+            uploaded_by,
+            notes,
+            active,
+            activated_at,
+            create_at,
+            ..
+        }: classifiers::Model,
+    ) -> Self {
+        Self {
+            id,
+            version,
+            checksum,
+            size,
+            uploaded_by,
+            notes,
+            active,
+            activated_at,
+            create_at,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CreateClassifier {
+    pub data: Cow<'static, [u8]>,
+    pub uploaded_by: Option<Uuid>,
+    pub notes: Option<String>,
+}
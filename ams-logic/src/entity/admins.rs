@@ -16,6 +16,15 @@ pub struct Model {
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-pub enum Relation {}
+pub enum Relation {
+    #[sea_orm(has_many = "super::classifiers::Entity")]
+    Classifiers,
+}
+
+impl Related<super::classifiers::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Classifiers.def()
+    }
+}
 
 impl ActiveModelBehavior for ActiveModel {}
@@ -1,5 +1,6 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.11.2
 
+use super::sea_orm_active_enums::AttendanceStatus;
 use sea_orm::entity::prelude::*;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
@@ -10,6 +11,8 @@ pub struct Model {
     pub subject_id: Uuid,
     pub attendee_id: Uuid,
     pub create_at: DateTimeWithTimeZone,
+    pub class_session_id: Uuid,
+    pub status: AttendanceStatus,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -22,6 +25,14 @@ pub enum Relation {
         on_delete = "NoAction"
     )]
     Attendees,
+    #[sea_orm(
+        belongs_to = "super::class_sessions::Entity",
+        from = "Column::ClassSessionId",
+        to = "super::class_sessions::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    ClassSessions,
     #[sea_orm(
         belongs_to = "super::subjects::Entity",
         from = "Column::SubjectId",
@@ -38,6 +49,12 @@ impl Related<super::attendees::Entity> for Entity {
     }
 }
 
+impl Related<super::class_sessions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ClassSessions.def()
+    }
+}
+
 impl Related<super::subjects::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Subjects.def()
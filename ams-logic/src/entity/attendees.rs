@@ -13,7 +13,8 @@ pub struct Model {
     #[sea_orm(unique)]
     pub email: String,
     pub password: String,
-    pub embedding: Option<Vec<f64>>,
+    #[sea_orm(select_as = "real[]", save_as = "vector")]
+    pub embedding: Option<Vec<f32>>,
     pub image: Option<String>,
     pub create_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
@@ -23,6 +24,8 @@ pub struct Model {
 pub enum Relation {
     #[sea_orm(has_many = "super::attendances::Entity")]
     Attendances,
+    #[sea_orm(has_many = "super::face_samples::Entity")]
+    FaceSamples,
 }
 
 impl Related<super::attendances::Entity> for Entity {
@@ -31,6 +34,12 @@ impl Related<super::attendances::Entity> for Entity {
     }
 }
 
+impl Related<super::face_samples::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::FaceSamples.def()
+    }
+}
+
 impl Related<super::subjects::Entity> for Entity {
     fn to() -> RelationDef {
         super::attendees_subjects::Relation::Subjects.def()
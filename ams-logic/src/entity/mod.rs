@@ -3,9 +3,20 @@
 pub mod prelude;
 
 pub mod admins;
+pub mod attendance_audit;
 pub mod attendances;
 pub mod attendees;
 pub mod attendees_subjects;
+pub mod audit_log;
+pub mod class_sessions;
+pub mod classifiers;
+pub mod face_samples;
 pub mod instructors;
+pub mod match_settings;
+pub mod sea_orm_active_enums;
+pub mod sessions;
+pub mod settings;
 pub mod subject_dates;
 pub mod subjects;
+pub mod term_holidays;
+pub mod terms;
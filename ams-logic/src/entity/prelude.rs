@@ -1,9 +1,19 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.11.2
 
 pub use super::admins::Entity as Admins;
+pub use super::attendance_audit::Entity as AttendanceAudit;
 pub use super::attendances::Entity as Attendances;
 pub use super::attendees::Entity as Attendees;
 pub use super::attendees_subjects::Entity as AttendeesSubjects;
+pub use super::audit_log::Entity as AuditLog;
+pub use super::class_sessions::Entity as ClassSessions;
+pub use super::classifiers::Entity as Classifiers;
+pub use super::face_samples::Entity as FaceSamples;
 pub use super::instructors::Entity as Instructors;
+pub use super::match_settings::Entity as MatchSettings;
+pub use super::sessions::Entity as Sessions;
+pub use super::settings::Entity as Settings;
 pub use super::subject_dates::Entity as SubjectDates;
 pub use super::subjects::Entity as Subjects;
+pub use super::term_holidays::Entity as TermHolidays;
+pub use super::terms::Entity as Terms;
@@ -17,6 +17,8 @@ pub struct Model {
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::class_sessions::Entity")]
+    ClassSessions,
     #[sea_orm(
         belongs_to = "super::subjects::Entity",
         from = "Column::SubjectId",
@@ -27,6 +29,12 @@ pub enum Relation {
     Subjects,
 }
 
+impl Related<super::class_sessions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ClassSessions.def()
+    }
+}
+
 impl Related<super::subjects::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Subjects.def()
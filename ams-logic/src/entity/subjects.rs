@@ -10,6 +10,8 @@ pub struct Model {
     #[sea_orm(unique)]
     pub name: String,
     pub instructor_id: Option<Uuid>,
+    pub term_id: Option<Uuid>,
+    pub capacity: Option<i32>,
     pub create_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
 }
@@ -18,6 +20,8 @@ pub struct Model {
 pub enum Relation {
     #[sea_orm(has_many = "super::attendances::Entity")]
     Attendances,
+    #[sea_orm(has_many = "super::class_sessions::Entity")]
+    ClassSessions,
     #[sea_orm(
         belongs_to = "super::instructors::Entity",
         from = "Column::InstructorId",
@@ -26,8 +30,18 @@ pub enum Relation {
         on_delete = "NoAction"
     )]
     Instructors,
+    #[sea_orm(has_one = "super::match_settings::Entity")]
+    MatchSettings,
     #[sea_orm(has_many = "super::subject_dates::Entity")]
     SubjectDates,
+    #[sea_orm(
+        belongs_to = "super::terms::Entity",
+        from = "Column::TermId",
+        to = "super::terms::Column::Id",
+        on_update = "NoAction",
+        on_delete = "SetNull"
+    )]
+    Terms,
 }
 
 impl Related<super::attendances::Entity> for Entity {
@@ -36,18 +50,36 @@ impl Related<super::attendances::Entity> for Entity {
     }
 }
 
+impl Related<super::class_sessions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ClassSessions.def()
+    }
+}
+
 impl Related<super::instructors::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Instructors.def()
     }
 }
 
+impl Related<super::match_settings::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::MatchSettings.def()
+    }
+}
+
 impl Related<super::subject_dates::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::SubjectDates.def()
     }
 }
 
+impl Related<super::terms::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Terms.def()
+    }
+}
+
 impl Related<super::attendees::Entity> for Entity {
     fn to() -> RelationDef {
         super::attendees_subjects::Relation::Attendees.def()
@@ -1,9 +1,12 @@
+use chrono::{DateTime, FixedOffset, NaiveDate};
 use sea_orm::{DbErr, RuntimeErr};
 use serde::Serialize;
-use sqlx::{postgres::PgDatabaseError, Error};
+use sqlx::postgres::PgDatabaseError;
 use thiserror::Error;
 use uuid::Uuid;
 
+use crate::entity::sea_orm_active_enums::MatchMetric;
+
 #[derive(Error, Debug, Serialize)]
 pub enum RepoError {
     #[error("subject `{id}` not found")]
@@ -27,33 +30,100 @@ pub enum RepoError {
     DuplicateAttendance { attendee_id: Uuid, subject_id: Uuid },
     #[error("admin already exists")]
     DuplicateAdmin,
+    #[error("term already exists")]
+    DuplicateTerm,
+
+    #[error("attendance for subject `{subject_id}` is outside its session window")]
+    OutsideSessionWindow {
+        subject_id: Uuid,
+        next_session: Option<DateTime<FixedOffset>>,
+    },
+
+    #[error("class session must start before it ends")]
+    InvalidClassSessionTimes,
+    #[error("date range from `{starts_on}` to `{ends_on}` is invalid")]
+    InvalidDateRange {
+        starts_on: NaiveDate,
+        ends_on: NaiveDate,
+    },
+    #[error("range from `{from}` to `{to}` is invalid, it can span at most {max_days} days")]
+    InvalidRange {
+        from: NaiveDate,
+        to: NaiveDate,
+        max_days: i64,
+    },
+    #[error("class session `{id}` is cancelled")]
+    ClassSessionCancelled { id: Uuid },
+
+    #[error("threshold `{threshold}` is out of range for the {metric:?} metric")]
+    InvalidMatchThreshold { metric: MatchMetric, threshold: f64 },
+
+    #[error("setting `{key}` {reason}")]
+    InvalidSetting { key: String, reason: String },
+
+    #[error("invalid list query: {0}")]
+    InvalidListQuery(String),
+
+    #[error("reason {0}")]
+    InvalidReason(String),
+
+    #[error("invalid csv file: {0}")]
+    InvalidCsv(String),
+
+    #[error("the last admin can't be deleted")]
+    LastAdmin,
+
+    #[error("capacity `{0}` must be positive")]
+    InvalidCapacity(i32),
+    #[error("subject `{subject_id}` can enroll at most {capacity} attendees")]
+    OverCapacity { subject_id: Uuid, capacity: i32 },
+
+    #[error("no earlier classifier to roll back to")]
+    NoClassifierToRollBack,
+
+    #[error("failed to export: {0}")]
+    Export(String),
+
+    #[error("wrong credentials")]
+    WrongCredentials,
+    #[error("session is invalid, expired or revoked")]
+    InvalidSession,
 
     #[error("duplicate in {0} `{1}`")]
     Duplicate(String, String),
+    /// a foreign key, check or not null constraint the change violates.
+    #[error("{0}")]
+    ConstraintViolation(String),
     #[error("no record found in {0}")]
     NotFound(String),
     #[error("unknown repository error")]
     Unknown,
 }
 
+fn pg_error(value: &DbErr) -> Option<&PgDatabaseError> {
+    match value {
+        DbErr::Query(RuntimeErr::SqlxError(rt_err))
+        | DbErr::Exec(RuntimeErr::SqlxError(rt_err)) => rt_err
+            .as_database_error()
+            .and_then(|db_err| db_err.try_downcast_ref::<PgDatabaseError>()),
+        _ => None,
+    }
+}
+
 impl From<DbErr> for RepoError {
     fn from(value: DbErr) -> Self {
         tracing::debug!("{value:#?}");
         match value {
-            DbErr::Query(RuntimeErr::SqlxError(rt_err))
-            | DbErr::Exec(RuntimeErr::SqlxError(rt_err)) => {
-                if let Some(pg_err) = rt_err
-                    .as_database_error()
-                    .and_then(|db_err| db_err.try_downcast_ref::<PgDatabaseError>())
-                {
-                    if let ("23505", Some(table), Some(detail)) =
-                        (pg_err.code(), pg_err.table(), pg_err.detail())
-                    {
-                        return Self::Duplicate(table.to_owned(), detail.to_owned());
-                    } else if matches!(Error::RowNotFound, _rt_err) {
-                        if let Some(table) = pg_err.table() {
-                            return Self::NotFound(table.to_owned());
+            DbErr::Query(RuntimeErr::SqlxError(_)) | DbErr::Exec(RuntimeErr::SqlxError(_)) => {
+                if let Some(pg_err) = pg_error(&value) {
+                    match (pg_err.code(), pg_err.table(), pg_err.detail()) {
+                        ("23505", Some(table), Some(detail)) => {
+                            return Self::Duplicate(table.to_owned(), detail.to_owned());
+                        }
+                        ("23502" | "23503" | "23514", ..) => {
+                            return Self::ConstraintViolation(pg_err.message().to_owned());
                         }
+                        _ => {}
                     }
                 }
             }
@@ -65,23 +135,35 @@ impl From<DbErr> for RepoError {
 
 pub(crate) trait MapDuplicateExt {
     type Output;
-    fn map_duplicate(self, duplicate_error: RepoError) -> Result<Self::Output, RepoError>
+    /// maps violations of the given unique constraint to `duplicate_error`.
+    fn map_unique_violation(
+        self,
+        constraint: &str,
+        duplicate_error: RepoError,
+    ) -> Result<Self::Output, RepoError>
     where
         Self: Sized;
 }
 
 impl<S> MapDuplicateExt for Result<S, DbErr> {
     type Output = S;
-    fn map_duplicate(self, duplicate_error: RepoError) -> Result<Self::Output, RepoError>
+    fn map_unique_violation(
+        self,
+        constraint: &str,
+        duplicate_error: RepoError,
+    ) -> Result<Self::Output, RepoError>
     where
         Self: Sized,
     {
         self.map_err(|error| {
-            let error = error.into();
-            if let RepoError::Duplicate(_, _) = error {
+            let violated = pg_error(&error)
+                .filter(|pg_err| pg_err.code() == "23505")
+                .and_then(|pg_err| pg_err.constraint())
+                == Some(constraint);
+            if violated {
                 duplicate_error
             } else {
-                error
+                error.into()
             }
         })
     }
@@ -0,0 +1,204 @@
+use std::{path::PathBuf, sync::Arc};
+
+use chrono::Utc;
+use sea_orm::{
+    prelude::{async_trait::async_trait, *},
+    sea_query::Expr,
+    Set, TransactionTrait,
+};
+use tokio::fs;
+
+use super::*;
+
+use crate::{
+    audit_log::{self, AuditChange},
+    entity::attendees,
+    pagination::Listable,
+};
+
+pub struct FaceSamplesRepo {
+    db: Arc<DatabaseConnection>,
+    assets: PathBuf,
+}
+
+impl Listable for face_samples::Entity {
+    const SORTS: &'static [(&'static str, face_samples::Column)] = &[
+        ("capturedAt", face_samples::Column::CapturedAt),
+        ("quality", face_samples::Column::Quality),
+    ];
+    const DEFAULT_SORT: &'static str = "capturedAt";
+    const ID: face_samples::Column = face_samples::Column::Id;
+    const CREATED_AT: Option<face_samples::Column> = Some(face_samples::Column::CapturedAt);
+}
+
+impl AsRef<DatabaseConnection> for FaceSamplesRepo {
+    fn as_ref(&self) -> &DatabaseConnection {
+        &self.db
+    }
+}
+
+fn to_vector(embedding: Vec<f64>) -> Vec<f32> {
+    embedding.into_iter().map(|value| value as f32).collect()
+}
+
+/// sets the attendee's embedding to the centroid of its samples, or clears it
+/// when none are left.
+async fn update_centroid<C: ConnectionTrait>(conn: &C, attendee_id: Uuid) -> Result<(), RepoError> {
+    attendees::Entity::update_many()
+        .col_expr(
+            attendees::Column::Embedding,
+            Expr::cust_with_values(
+                r#"(SELECT AVG("embedding") FROM "face_samples" WHERE "attendee_id" = $1)"#,
+                [attendee_id],
+            ),
+        )
+        .col_expr(attendees::Column::UpdatedAt, Expr::value(Utc::now()))
+        .filter(attendees::Column::Id.eq(attendee_id))
+        .exec(conn)
+        .await?;
+
+    Ok(())
+}
+
+impl FaceSamplesRepo {
+    pub fn new(db: Arc<DatabaseConnection>, assets: impl Into<PathBuf>) -> Self {
+        Self {
+            db,
+            assets: assets.into(),
+        }
+    }
+
+    async fn find<C: ConnectionTrait>(
+        conn: &C,
+        attendee_id: Uuid,
+        id: Uuid,
+    ) -> Result<face_samples::Model, RepoError> {
+        face_samples::Entity::find_by_id(id)
+            .filter(face_samples::Column::AttendeeId.eq(attendee_id))
+            .one(conn)
+            .await?
+            .ok_or(RepoError::NotFound("face_samples".to_owned()))
+    }
+}
+
+#[async_trait]
+impl FaceSamplesRepoTrait for FaceSamplesRepo {
+    async fn create(
+        &self,
+        attendee_id: Uuid,
+        CreateFaceSample {
+            embedding,
+            quality,
+            image,
+            extension,
+        }: CreateFaceSample,
+    ) -> Result<FaceSample, RepoError> {
+        attendees::Entity::find_by_id(attendee_id)
+            .one(self.as_ref())
+            .await?
+            .ok_or(RepoError::AttendeeNotFound {
+                id: attendee_id.to_string(),
+            })?;
+
+        let id = Uuid::new_v4();
+        let samples_dir = self.assets.join(attendee_id.to_string()).join("samples");
+        fs::create_dir_all(&samples_dir)
+            .await
+            .map_err(|_| RepoError::Unknown)?;
+        let path = samples_dir.join(format!("{id}.{extension}"));
+        fs::write(&path, image)
+            .await
+            .map_err(|_| RepoError::Unknown)?;
+
+        let txn = self.as_ref().begin().await?;
+
+        let sample: FaceSample = face_samples::ActiveModel {
+            id: Set(id),
+            attendee_id: Set(attendee_id),
+            embedding: Set(to_vector(embedding)),
+            image: Set(path.to_string_lossy().into()),
+            quality: Set(quality),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?
+        .into();
+        update_centroid(&txn, attendee_id).await?;
+        audit_log::record(&txn, Some(id), AuditChange::Created(&sample)).await?;
+
+        txn.commit().await?;
+
+        Ok(sample)
+    }
+    async fn get_all(
+        &self,
+        attendee_id: Uuid,
+        query: ListQuery,
+    ) -> Result<Page<FaceSample>, RepoError> {
+        let select =
+            face_samples::Entity::find().filter(face_samples::Column::AttendeeId.eq(attendee_id));
+
+        Ok(query
+            .page(self.as_ref(), select)
+            .await?
+            .map(FaceSample::from))
+    }
+    async fn get_by_id(&self, attendee_id: Uuid, id: Uuid) -> Result<FaceSample, RepoError> {
+        Ok(Self::find(self.as_ref(), attendee_id, id).await?.into())
+    }
+    async fn image(&self, attendee_id: Uuid, id: Uuid) -> Result<Vec<u8>, RepoError> {
+        let sample = Self::find(self.as_ref(), attendee_id, id).await?;
+        fs::read(&sample.image)
+            .await
+            .map_err(|_| RepoError::NotFound("face_samples image".to_owned()))
+    }
+    async fn update_embedding(
+        &self,
+        attendee_id: Uuid,
+        id: Uuid,
+        UpdateFaceSampleEmbedding { embedding, quality }: UpdateFaceSampleEmbedding,
+    ) -> Result<FaceSample, RepoError> {
+        let txn = self.as_ref().begin().await?;
+
+        let before = Self::find(&txn, attendee_id, id).await?;
+        let mut sample: face_samples::ActiveModel = before.clone().into();
+        sample.embedding = Set(to_vector(embedding));
+        sample.quality = Set(quality);
+        sample.updated_at = Set(Utc::now().into());
+
+        let sample: FaceSample = sample.update(&txn).await?.into();
+        update_centroid(&txn, attendee_id).await?;
+        audit_log::record(
+            &txn,
+            Some(id),
+            AuditChange::Updated(&FaceSample::from(before), &sample),
+        )
+        .await?;
+        txn.commit().await?;
+
+        Ok(sample)
+    }
+    async fn delete_by_id(&self, attendee_id: Uuid, id: Uuid) -> Result<(), RepoError> {
+        let txn = self.as_ref().begin().await?;
+
+        let sample = Self::find(&txn, attendee_id, id).await?;
+        face_samples::Entity::delete_by_id(id).exec(&txn).await?;
+        update_centroid(&txn, attendee_id).await?;
+        audit_log::record(
+            &txn,
+            Some(id),
+            AuditChange::Deleted(&FaceSample::from(sample.clone())),
+        )
+        .await?;
+        txn.commit().await?;
+
+        if let Err(error) = fs::remove_file(&sample.image).await {
+            tracing::warn!(
+                "could not remove face sample image `{}`: {error}",
+                sample.image
+            );
+        }
+
+        Ok(())
+    }
+}
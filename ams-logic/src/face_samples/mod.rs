@@ -0,0 +1,41 @@
+mod impls;
+mod models;
+
+pub use impls::*;
+pub use models::*;
+
+use sea_orm::prelude::async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::{
+    error::RepoError,
+    pagination::{ListQuery, Page},
+};
+
+use crate::entity::face_samples;
+
+/// the face images an attendee is recognized by, the attendee's embedding is
+/// kept as the centroid of them.
+#[async_trait]
+pub trait FaceSamplesRepoTrait {
+    async fn create(
+        &self,
+        attendee_id: Uuid,
+        sample: CreateFaceSample,
+    ) -> Result<FaceSample, RepoError>;
+    async fn get_all(
+        &self,
+        attendee_id: Uuid,
+        query: ListQuery,
+    ) -> Result<Page<FaceSample>, RepoError>;
+    async fn get_by_id(&self, attendee_id: Uuid, id: Uuid) -> Result<FaceSample, RepoError>;
+    /// the source image of the sample, to embed it again.
+    async fn image(&self, attendee_id: Uuid, id: Uuid) -> Result<Vec<u8>, RepoError>;
+    async fn update_embedding(
+        &self,
+        attendee_id: Uuid,
+        id: Uuid,
+        embedding: UpdateFaceSampleEmbedding,
+    ) -> Result<FaceSample, RepoError>;
+    async fn delete_by_id(&self, attendee_id: Uuid, id: Uuid) -> Result<(), RepoError>;
+}
@@ -0,0 +1,58 @@
+use std::borrow::Cow;
+
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::entity::face_samples;
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FaceSample {
+    pub id: Uuid,
+    pub attendee_id: Uuid,
+    pub image: String,
+    /// from `0` for unusable to `1` for ideal.
+    pub quality: f64,
+    pub captured_at: DateTime<FixedOffset>,
+    pub updated_at: DateTime<FixedOffset>,
+}
+
+impl From<face_samples::Model> for FaceSample {
+    fn from(
+        face_samples::Model {
+            id,
+            attendee_id,
+            image,
+            quality,
+            captured_at,
+            updated_at,
+            ..
+        }: face_samples::Model,
+    ) -> Self {
+        Self {
+            id,
+            attendee_id,
+            image,
+            quality,
+            captured_at,
+            updated_at,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CreateFaceSample {
+    pub embedding: Vec<f64>,
+    pub quality: f64,
+    pub image: Cow<'static, [u8]>,
+    /// the extension the image is stored with.
+    pub extension: Cow<'static, str>,
+}
+
+#[derive(Debug)]
+pub struct UpdateFaceSampleEmbedding {
+    pub embedding: Vec<f64>,
+    pub quality: f64,
+}
@@ -0,0 +1,161 @@
+use std::sync::Arc;
+
+use csv::{ReaderBuilder, StringRecord};
+use sea_orm::{
+    prelude::{async_trait::async_trait, *},
+    DatabaseTransaction, TransactionTrait,
+};
+use serde::de::DeserializeOwned;
+
+use super::*;
+
+use crate::{
+    attendees::{AttendeesRepo, CreateAttendee},
+    entity::{attendees, subjects},
+    instructors::{CreateInstructor, InstructorsRepo},
+    subjects::SubjectsRepository,
+};
+
+pub struct ImportsService(pub Arc<DatabaseConnection>);
+
+impl AsRef<DatabaseConnection> for ImportsService {
+    fn as_ref(&self) -> &DatabaseConnection {
+        &self.0
+    }
+}
+
+fn parse<T: DeserializeOwned>(record: &StringRecord, headers: &StringRecord) -> Result<T, String> {
+    record
+        .deserialize(Some(headers))
+        .map_err(|error| match error.kind() {
+            csv::ErrorKind::Deserialize { err, .. } => {
+                match err.field().and_then(|field| headers.get(field as usize)) {
+                    Some(column) => format!("`{column}`: {}", err.kind()),
+                    None => err.kind().to_string(),
+                }
+            }
+            _ => error.to_string(),
+        })
+}
+
+/// imports one row, a failing row leaves nothing behind.
+async fn import_row(
+    txn: &DatabaseTransaction,
+    kind: ImportKind,
+    record: &StringRecord,
+    headers: &StringRecord,
+) -> Result<(), (ImportErrorKind, String)> {
+    let invalid = |message| (ImportErrorKind::Invalid, message);
+    let failed = |error: RepoError| ((&error).into(), error.to_string());
+
+    // a savepoint, postgres aborts the whole transaction on a failed statement
+    let row = txn.begin().await.map_err(|error| failed(error.into()))?;
+    let result = match kind {
+        ImportKind::Attendees => {
+            let attendee = parse::<CreateAttendee>(record, headers).map_err(invalid)?;
+            AttendeesRepo::insert(&row, attendee).await.map(|_| ())
+        }
+        ImportKind::Instructors => {
+            let instructor = parse::<CreateInstructor>(record, headers).map_err(invalid)?;
+            InstructorsRepo::insert(&row, instructor).await.map(|_| ())
+        }
+        ImportKind::Enrollments => {
+            let enrollment = parse::<CreateEnrollment>(record, headers).map_err(invalid)?;
+            enroll(&row, enrollment).await
+        }
+    };
+
+    match result {
+        Ok(()) => row.commit().await.map_err(|error| failed(error.into())),
+        Err(error) => {
+            row.rollback().await.map_err(|error| failed(error.into()))?;
+            Err(failed(error))
+        }
+    }
+}
+
+async fn enroll<C: ConnectionTrait>(
+    conn: &C,
+    CreateEnrollment {
+        attendee_number,
+        subject,
+    }: CreateEnrollment,
+) -> Result<(), RepoError> {
+    let attendee = attendees::Entity::find()
+        .filter(attendees::Column::Number.eq(attendee_number))
+        .one(conn)
+        .await?
+        .ok_or(RepoError::AttendeeNotFound {
+            id: attendee_number.to_string(),
+        })?;
+    let subject = subjects::Entity::find()
+        .filter(subjects::Column::Name.eq(subject.as_str()))
+        .one(conn)
+        .await?
+        .ok_or(RepoError::SubjectNotFound { id: subject })?;
+
+    SubjectsRepository::enroll(conn, subject.id, attendee.id).await
+}
+
+#[async_trait]
+impl ImportsServiceTrait for ImportsService {
+    async fn import(
+        &self,
+        kind: ImportKind,
+        csv: Vec<u8>,
+        dry_run: bool,
+    ) -> Result<ImportReport, RepoError> {
+        // rows with missing columns are reported like any other invalid row
+        let mut reader = ReaderBuilder::new()
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(&csv[..]);
+        let headers = reader
+            .headers()
+            .map_err(|error| RepoError::InvalidCsv(error.to_string()))?
+            .clone();
+
+        let txn = self.as_ref().begin().await?;
+
+        let (mut rows, mut imported, mut errors) = (0, 0, Vec::new());
+        let mut record = StringRecord::new();
+        loop {
+            let line = reader.position().line();
+            match reader.read_record(&mut record) {
+                Ok(false) => break,
+                Ok(true) => {}
+                // the reader can't go on past a malformed row
+                Err(error) => {
+                    txn.rollback().await?;
+                    return Err(RepoError::InvalidCsv(format!("line {line}: {error}")));
+                }
+            }
+            rows += 1;
+
+            match import_row(&txn, kind, &record, &headers).await {
+                Ok(()) => imported += 1,
+                Err((kind, message)) => errors.push(ImportRowError {
+                    line,
+                    kind,
+                    message,
+                }),
+            }
+        }
+
+        let committed = !dry_run && errors.is_empty();
+        if committed {
+            txn.commit().await?;
+        } else {
+            txn.rollback().await?;
+        }
+
+        Ok(ImportReport {
+            kind,
+            dry_run,
+            committed,
+            rows,
+            imported,
+            errors,
+        })
+    }
+}
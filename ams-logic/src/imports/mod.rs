@@ -0,0 +1,22 @@
+mod impls;
+mod models;
+
+pub use impls::*;
+pub use models::*;
+
+use sea_orm::prelude::async_trait::async_trait;
+
+use crate::error::RepoError;
+
+/// bulk imports of csv files.
+#[async_trait]
+pub trait ImportsServiceTrait {
+    /// imports every row of a csv file within one transaction, which is only
+    /// committed when it isn't a dry run and no row failed.
+    async fn import(
+        &self,
+        kind: ImportKind,
+        csv: Vec<u8>,
+        dry_run: bool,
+    ) -> Result<ImportReport, RepoError>;
+}
@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::error::RepoError;
+
+/// what the rows of an imported csv file are.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportKind {
+    /// `name`, `email`, `password` and `number` columns.
+    Attendees,
+    /// `name`, `email`, `password` and `number` columns.
+    Instructors,
+    /// `attendeeNumber` and `subject` columns, the subject by its name.
+    Enrollments,
+}
+
+/// a row of an enrollments csv file.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateEnrollment {
+    pub attendee_number: i64,
+    pub subject: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportErrorKind {
+    /// the row doesn't match the columns of the import.
+    Invalid,
+    /// the row is already in the database or earlier in the file.
+    Duplicate,
+    /// the attendee or subject the row refers to doesn't exist.
+    NotFound,
+    Failed,
+}
+
+impl From<&RepoError> for ImportErrorKind {
+    fn from(error: &RepoError) -> Self {
+        match error {
+            RepoError::Duplicate(..)
+            | RepoError::DuplicateAttendee
+            | RepoError::DuplicateInstructor => Self::Duplicate,
+            RepoError::NotFound(_)
+            | RepoError::AttendeeNotFound { .. }
+            | RepoError::SubjectNotFound { .. } => Self::NotFound,
+            _ => Self::Failed,
+        }
+    }
+}
+
+/// why a row can't be imported.
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportRowError {
+    /// the line of the row in the file, the header is line 1.
+    pub line: u64,
+    pub kind: ImportErrorKind,
+    pub message: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    pub kind: ImportKind,
+    pub dry_run: bool,
+    /// whether the rows were written, never on a dry run or when a row failed.
+    pub committed: bool,
+    pub rows: usize,
+    /// the rows that were, or on a dry run would be, imported.
+    pub imported: usize,
+    pub errors: Vec<ImportRowError>,
+}
@@ -1,32 +1,49 @@
 use std::{path::PathBuf, sync::Arc};
 
-use sea_orm::Set;
+use sea_orm::{QuerySelect, Set, TransactionTrait};
 use tokio::fs;
 
 use super::*;
 
+use crate::{
+    audit_log::{self, AuditChange},
+    pagination::{ListQuery, Listable, Page},
+    password::{self, Verification},
+};
+
 pub struct InstructorsRepo {
     db: Arc<DatabaseConnection>,
     assets: PathBuf,
 }
 
+impl Listable for instructors::Entity {
+    const SORTS: &'static [(&'static str, instructors::Column)] = &[
+        ("name", instructors::Column::Name),
+        ("email", instructors::Column::Email),
+        ("number", instructors::Column::Number),
+        ("createdAt", instructors::Column::CreateAt),
+    ];
+    const DEFAULT_SORT: &'static str = "number";
+    const ID: instructors::Column = instructors::Column::Id;
+    const NAME: Option<instructors::Column> = Some(instructors::Column::Name);
+    const NUMBER: Option<instructors::Column> = Some(instructors::Column::Number);
+    const CREATED_AT: Option<instructors::Column> = Some(instructors::Column::CreateAt);
+}
+
 impl InstructorsRepo {
     pub fn new(db: Arc<DatabaseConnection>, assets: impl Into<PathBuf>) -> Self {
         let assets: PathBuf = assets.into();
-        if assets.exists() == false {
+        if !assets.exists() {
             std::fs::create_dir_all(&assets).unwrap();
         }
 
-        Self {
-            db,
-            assets: assets.into(),
-        }
+        Self { db, assets }
     }
 
     async fn save_image(&self, id: Uuid, image: Vec<u8>) -> PathBuf {
         let instructor_dir = self.assets.join(id.to_string());
 
-        if instructor_dir.exists() == false {
+        if !instructor_dir.exists() {
             fs::create_dir_all(&instructor_dir).await.unwrap();
         }
 
@@ -36,6 +53,26 @@ impl InstructorsRepo {
 
         image_path
     }
+
+    /// inserts one instructor, bulk imports insert within their own transaction.
+    pub(crate) async fn insert<C: ConnectionTrait>(
+        conn: &C,
+        instructor: CreateInstructor,
+    ) -> Result<Instructor, RepoError> {
+        let instructor: Instructor = instructors::ActiveModel {
+            name: Set(instructor.name),
+            email: Set(instructor.email),
+            password: Set(password::hash(instructor.password).await?),
+            number: Set(instructor.number),
+            ..Default::default()
+        }
+        .insert(conn)
+        .await?
+        .into();
+        audit_log::record(conn, Some(instructor.id), AuditChange::Created(&instructor)).await?;
+
+        Ok(instructor)
+    }
 }
 
 impl AsRef<DatabaseConnection> for InstructorsRepo {
@@ -47,16 +84,11 @@ impl AsRef<DatabaseConnection> for InstructorsRepo {
 #[async_trait]
 impl InstructorsRepoTrait for InstructorsRepo {
     async fn create(&self, instructor: CreateInstructor) -> Result<Instructor, RepoError> {
-        Ok(instructors::ActiveModel {
-            name: Set(instructor.name),
-            email: Set(instructor.email),
-            password: Set(instructor.password),
-            number: Set(instructor.number),
-            ..Default::default()
-        }
-        .insert(self.as_ref())
-        .await?
-        .into())
+        let txn = self.as_ref().begin().await?;
+        let instructor = Self::insert(&txn, instructor).await?;
+        txn.commit().await?;
+
+        Ok(instructor)
     }
     async fn update(
         &self,
@@ -69,11 +101,18 @@ impl InstructorsRepoTrait for InstructorsRepo {
             number,
         }: UpdateInstructor,
     ) -> Result<Instructor, RepoError> {
-        let mut instructor: instructors::ActiveModel = instructors::Entity::find_by_id(id)
-            .one(self.as_ref())
+        let password = match password {
+            Some(password) => Some(password::hash(password).await?),
+            None => None,
+        };
+        let txn = self.as_ref().begin().await?;
+
+        let before = instructors::Entity::find_by_id(id)
+            .lock_exclusive()
+            .one(&txn)
             .await?
-            .ok_or(RepoError::NotFound("instructors".to_owned()))?
-            .into();
+            .ok_or(RepoError::NotFound("instructors".to_owned()))?;
+        let mut instructor: instructors::ActiveModel = before.clone().into();
 
         if let Some(name) = name {
             instructor.name = Set(name);
@@ -93,9 +132,16 @@ impl InstructorsRepoTrait for InstructorsRepo {
             instructor.image = Set(Some(path.to_string_lossy().into()))
         }
 
-        let instructor: instructors::Model = instructor.update(self.as_ref()).await?;
+        let instructor: Instructor = instructor.update(&txn).await?.into();
+        audit_log::record(
+            &txn,
+            Some(id),
+            AuditChange::Updated(&Instructor::from(before), &instructor),
+        )
+        .await?;
+        txn.commit().await?;
 
-        Ok(instructor.into())
+        Ok(instructor)
     }
     async fn get_by_id(&self, id: Uuid) -> Result<Instructor, RepoError> {
         Ok(instructors::Entity::find_by_id(id)
@@ -112,19 +158,56 @@ impl InstructorsRepoTrait for InstructorsRepo {
             .ok_or(RepoError::NotFound("instructors".to_owned()))?
             .into())
     }
-    async fn get_all(&self) -> Result<Vec<Instructor>, RepoError> {
-        let collection: Vec<Instructor> = instructors::Entity::find()
-            .all(self.as_ref())
+    async fn get_by_creds(&self, email: String, password: String) -> Result<Instructor, RepoError> {
+        let Some(mut instructor) = instructors::Entity::find()
+            .filter(instructors::Column::Email.eq(&email))
+            .one(self.as_ref())
             .await?
-            .into_iter()
-            .map(Instructor::from)
-            .collect();
-        Ok(collection)
+        else {
+            password::verify_dummy(password).await;
+            return Err(RepoError::WrongCredentials);
+        };
+
+        match password::verify(password.clone(), instructor.password.clone()).await {
+            Verification::Valid => {}
+            Verification::ValidLegacy => {
+                tracing::info!(
+                    "re-hashing legacy password for instructor `{}`",
+                    instructor.id
+                );
+                let mut active: instructors::ActiveModel = instructor.into();
+                active.password = Set(password::hash(password).await?);
+                instructor = active.update(self.as_ref()).await?;
+            }
+            Verification::Invalid => return Err(RepoError::WrongCredentials),
+        }
+
+        Ok(instructor.into())
+    }
+    async fn get_all(&self, query: ListQuery) -> Result<Page<Instructor>, RepoError> {
+        Ok(query
+            .page(self.as_ref(), instructors::Entity::find())
+            .await?
+            .map(Instructor::from))
     }
     async fn delete_by_id(&self, id: Uuid) -> Result<(), RepoError> {
-        instructors::Entity::delete_by_id(id)
-            .exec(self.as_ref())
+        let txn = self.as_ref().begin().await?;
+
+        let instructor = instructors::Entity::find_by_id(id)
+            .lock_exclusive()
+            .one(&txn)
+            .await?;
+        if let Some(instructor) = instructor {
+            instructors::Entity::delete_by_id(id).exec(&txn).await?;
+            audit_log::record(
+                &txn,
+                Some(id),
+                AuditChange::Deleted(&Instructor::from(instructor)),
+            )
             .await?;
+        }
+        txn.commit().await?;
+
         Ok(())
     }
 }
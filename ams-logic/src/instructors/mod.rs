@@ -6,7 +6,10 @@ use sea_orm::prelude::{async_trait::async_trait, *};
 pub use impls::*;
 pub use models::*;
 
-use crate::error::RepoError;
+use crate::{
+    error::RepoError,
+    pagination::{ListQuery, Page},
+};
 
 use crate::entity::instructors;
 
@@ -20,6 +23,7 @@ pub trait InstructorsRepoTrait {
     ) -> Result<Instructor, RepoError>;
     async fn get_by_id(&self, id: Uuid) -> Result<Instructor, RepoError>;
     async fn get_by_email(&self, email: String) -> Result<Instructor, RepoError>;
-    async fn get_all(&self) -> Result<Vec<Instructor>, RepoError>;
+    async fn get_by_creds(&self, email: String, password: String) -> Result<Instructor, RepoError>;
+    async fn get_all(&self, query: ListQuery) -> Result<Page<Instructor>, RepoError>;
     async fn delete_by_id(&self, id: Uuid) -> Result<(), RepoError>;
 }
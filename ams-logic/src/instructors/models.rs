@@ -61,7 +61,7 @@ pub struct CreateInstructor {
 
 #[derive(Deserialize, Serialize, Debug, ToSchema, Default)]
 #[serde(rename_all = "camelCase")]
-pub struct UpdateInstructor{
+pub struct UpdateInstructor {
     #[schema(example = "Emil Instructor")]
     pub name: Option<String>,
     #[schema(example = "EmilInstructor@outlook.com")]
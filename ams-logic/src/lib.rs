@@ -1,8 +1,20 @@
 pub mod admins;
 pub mod attendances;
 pub mod attendees;
+pub mod audit_log;
+pub mod class_sessions;
+pub mod classifiers;
 pub mod entity;
 pub mod error;
+pub mod face_samples;
+pub mod imports;
 pub mod instructors;
+pub mod match_settings;
+pub mod pagination;
+pub mod password;
 pub mod prelude;
+pub mod reports;
+pub mod sessions;
+pub mod settings;
 pub mod subjects;
+pub mod terms;
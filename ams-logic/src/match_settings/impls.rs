@@ -0,0 +1,165 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use sea_orm::{
+    prelude::{async_trait::async_trait, *},
+    sea_query::OnConflict,
+    QuerySelect, Set, TransactionTrait,
+};
+
+use super::*;
+
+use crate::{
+    audit_log::{self, AuditChange},
+    entity::subjects,
+};
+
+pub struct MatchSettingsRepo(pub Arc<DatabaseConnection>);
+
+impl AsRef<DatabaseConnection> for MatchSettingsRepo {
+    fn as_ref(&self) -> &DatabaseConnection {
+        &self.0
+    }
+}
+
+fn check_threshold(settings: &UpdateMatchSettings) -> Result<(), RepoError> {
+    if !settings.metric.accepts(settings.threshold) {
+        return Err(RepoError::InvalidMatchThreshold {
+            metric: settings.metric,
+            threshold: settings.threshold,
+        });
+    }
+    Ok(())
+}
+
+impl MatchSettingsRepo {
+    async fn find_default<C: ConnectionTrait>(
+        conn: &C,
+    ) -> Result<match_settings::Model, RepoError> {
+        match_settings::Entity::find()
+            .filter(match_settings::Column::SubjectId.is_null())
+            .one(conn)
+            .await?
+            .ok_or(RepoError::NotFound("match_settings".to_owned()))
+    }
+
+    /// the override of a subject, if it has one.
+    async fn find_override<C: ConnectionTrait>(
+        conn: &C,
+        subject_id: Uuid,
+    ) -> Result<Option<match_settings::Model>, RepoError> {
+        Ok(match_settings::Entity::find()
+            .filter(match_settings::Column::SubjectId.eq(subject_id))
+            .lock_exclusive()
+            .one(conn)
+            .await?)
+    }
+}
+
+#[async_trait]
+impl MatchSettingsRepoTrait for MatchSettingsRepo {
+    async fn get_default(&self) -> Result<MatchSettings, RepoError> {
+        Ok(Self::find_default(self.as_ref()).await?.into())
+    }
+    async fn update_default(
+        &self,
+        settings: UpdateMatchSettings,
+    ) -> Result<MatchSettings, RepoError> {
+        check_threshold(&settings)?;
+
+        let txn = self.as_ref().begin().await?;
+
+        let before = Self::find_default(&txn).await?;
+        let mut default: match_settings::ActiveModel = before.clone().into();
+        default.metric = Set(settings.metric);
+        default.threshold = Set(settings.threshold);
+        default.updated_at = Set(Utc::now().into());
+
+        let default: MatchSettings = default.update(&txn).await?.into();
+        audit_log::record(
+            &txn,
+            None,
+            AuditChange::Updated(&MatchSettings::from(before), &default),
+        )
+        .await?;
+        txn.commit().await?;
+
+        Ok(default)
+    }
+    async fn get_for_subject(&self, subject_id: Uuid) -> Result<MatchSettings, RepoError> {
+        let settings = match_settings::Entity::find()
+            .filter(match_settings::Column::SubjectId.eq(subject_id))
+            .one(self.as_ref())
+            .await?;
+
+        match settings {
+            Some(settings) => Ok(settings.into()),
+            None => self.get_default().await,
+        }
+    }
+    async fn set_for_subject(
+        &self,
+        subject_id: Uuid,
+        settings: UpdateMatchSettings,
+    ) -> Result<MatchSettings, RepoError> {
+        check_threshold(&settings)?;
+        let txn = self.as_ref().begin().await?;
+
+        subjects::Entity::find_by_id(subject_id)
+            .one(&txn)
+            .await?
+            .ok_or(RepoError::SubjectNotFound {
+                id: subject_id.to_string(),
+            })?;
+        let before = Self::find_override(&txn, subject_id).await?;
+
+        match_settings::Entity::insert(match_settings::ActiveModel {
+            subject_id: Set(Some(subject_id)),
+            metric: Set(settings.metric),
+            threshold: Set(settings.threshold),
+            ..Default::default()
+        })
+        .on_conflict(
+            OnConflict::column(match_settings::Column::SubjectId)
+                .update_columns([
+                    match_settings::Column::Metric,
+                    match_settings::Column::Threshold,
+                ])
+                .value(match_settings::Column::UpdatedAt, Utc::now())
+                .to_owned(),
+        )
+        .exec_without_returning(&txn)
+        .await?;
+
+        let after: MatchSettings = Self::find_override(&txn, subject_id)
+            .await?
+            .ok_or(RepoError::NotFound("match_settings".to_owned()))?
+            .into();
+        let change = match before {
+            Some(before) => AuditChange::Updated(MatchSettings::from(before), after.clone()),
+            None => AuditChange::Created(after.clone()),
+        };
+        audit_log::record(&txn, Some(subject_id), change).await?;
+        txn.commit().await?;
+
+        Ok(after)
+    }
+    async fn clear_for_subject(&self, subject_id: Uuid) -> Result<(), RepoError> {
+        let txn = self.as_ref().begin().await?;
+
+        if let Some(before) = Self::find_override(&txn, subject_id).await? {
+            match_settings::Entity::delete_by_id(before.id)
+                .exec(&txn)
+                .await?;
+            audit_log::record(
+                &txn,
+                Some(subject_id),
+                AuditChange::Deleted(&MatchSettings::from(before)),
+            )
+            .await?;
+        }
+        txn.commit().await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,30 @@
+mod impls;
+mod models;
+
+pub use impls::*;
+pub use models::*;
+
+use sea_orm::prelude::async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::error::RepoError;
+
+use crate::entity::match_settings;
+
+/// how faces are matched, for the whole deployment and per subject.
+#[async_trait]
+pub trait MatchSettingsRepoTrait {
+    async fn get_default(&self) -> Result<MatchSettings, RepoError>;
+    async fn update_default(
+        &self,
+        settings: UpdateMatchSettings,
+    ) -> Result<MatchSettings, RepoError>;
+    /// the subject's override, or the default when it has none.
+    async fn get_for_subject(&self, subject_id: Uuid) -> Result<MatchSettings, RepoError>;
+    async fn set_for_subject(
+        &self,
+        subject_id: Uuid,
+        settings: UpdateMatchSettings,
+    ) -> Result<MatchSettings, RepoError>;
+    async fn clear_for_subject(&self, subject_id: Uuid) -> Result<(), RepoError>;
+}
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::entity::match_settings;
+
+pub use crate::entity::sea_orm_active_enums::MatchMetric;
+
+impl MatchMetric {
+    /// whether `threshold` is a reachable distance between unit embeddings.
+    pub fn accepts(&self, threshold: f64) -> bool {
+        match self {
+            MatchMetric::L2 | MatchMetric::Cosine => (0.0..=2.0).contains(&threshold),
+            MatchMetric::Dot => (-1.0..=1.0).contains(&threshold),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchSettings {
+    pub metric: MatchMetric,
+    /// the greatest distance under `metric` that still counts as a match.
+    #[schema(example = 0.6)]
+    pub threshold: f64,
+    /// the subject the settings are overridden for, none for the default.
+    pub subject_id: Option<Uuid>,
+}
+
+impl From<match_settings::Model> for MatchSettings {
+    fn from(
+        match_settings::Model {
+            subject_id,
+            metric,
+            threshold,
+            ..
+        }: match_settings::Model,
+    ) -> Self {
+        Self {
+            metric,
+            threshold,
+            subject_id,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateMatchSettings {
+    pub metric: MatchMetric,
+    #[schema(example = 0.6)]
+    pub threshold: f64,
+}
@@ -0,0 +1,298 @@
+//! paging, sorting and filtering shared by every list.
+//!
+//! lists are paged by cursor: the `nextCursor` of a page holds the sort value
+//! and id of its last item, and the next page starts right after them, so
+//! pages don't shift when items are added or removed in between. `offset`
+//! pages are supported too for jumping to an arbitrary page.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use sea_orm::{
+    sea_query::{extension::postgres::PgExpr, Expr, LikeExpr},
+    ColumnTrait, Condition, ConnectionTrait, EntityTrait, ModelTrait, Order, PaginatorTrait,
+    QueryFilter, QueryOrder, QuerySelect, Select, Value,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use crate::error::RepoError;
+
+/// one page of a list.
+#[derive(Debug)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// how many items match the filters across all pages.
+    pub total: u64,
+    /// the `cursor` of the next page, `None` on the last one.
+    pub next_cursor: Option<String>,
+}
+
+impl<T> Page<T> {
+    /// the same page holding `items`, converted from the original ones.
+    pub fn with_items<U>(self, items: Vec<U>) -> Page<U> {
+        Page {
+            items,
+            total: self.total,
+            next_cursor: self.next_cursor,
+        }
+    }
+
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            total: self.total,
+            next_cursor: self.next_cursor,
+        }
+    }
+}
+
+/// how to page, sort and filter a list, the default lists every item.
+#[derive(Deserialize, IntoParams, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct ListQuery {
+    /// how many items per page.
+    pub limit: Option<u64>,
+    /// how many items to skip, can't be combined with `cursor`.
+    pub offset: Option<u64>,
+    /// the `nextCursor` of the previous page.
+    pub cursor: Option<String>,
+    /// the field to sort by, prefixed with `-` for descending order.
+    #[param(example = "-createdAt")]
+    pub sort: Option<String>,
+    /// case insensitive search in the name.
+    pub name: Option<String>,
+    /// the smallest number, inclusive.
+    pub number_from: Option<i64>,
+    /// the largest number, inclusive.
+    pub number_to: Option<i64>,
+    /// created at or after.
+    #[param(value_type = Option<String>, format = DateTime)]
+    pub created_from: Option<DateTime<FixedOffset>>,
+    /// created at or before.
+    #[param(value_type = Option<String>, format = DateTime)]
+    pub created_to: Option<DateTime<FixedOffset>>,
+}
+
+/// an entity that can be listed with a [`ListQuery`].
+pub(crate) trait Listable: EntityTrait {
+    /// the fields a list can be sorted by and their columns, none of them is
+    /// nullable.
+    const SORTS: &'static [(&'static str, Self::Column)];
+    /// the sort used when `sort` is omitted.
+    const DEFAULT_SORT: &'static str;
+    /// breaks ties between equal sort values.
+    const ID: Self::Column;
+    const NAME: Option<Self::Column> = None;
+    const NUMBER: Option<Self::Column> = None;
+    const CREATED_AT: Option<Self::Column> = None;
+}
+
+/// a sort value that survives the round trip through a cursor.
+#[derive(Serialize, Deserialize)]
+enum CursorValue {
+    Int(i64),
+    Float(f64),
+    Text(String),
+    Date(NaiveDate),
+    DateTime(DateTime<FixedOffset>),
+}
+
+impl CursorValue {
+    fn from_value(value: Value) -> Option<Self> {
+        Some(match value {
+            Value::SmallInt(Some(value)) => Self::Int(value.into()),
+            Value::Int(Some(value)) => Self::Int(value.into()),
+            Value::BigInt(Some(value)) => Self::Int(value),
+            Value::Double(Some(value)) => Self::Float(value),
+            Value::String(Some(value)) => Self::Text(*value),
+            Value::ChronoDate(Some(value)) => Self::Date(*value),
+            Value::ChronoDateTimeWithTimeZone(Some(value)) => Self::DateTime(*value),
+            _ => return None,
+        })
+    }
+}
+
+impl From<CursorValue> for Value {
+    fn from(value: CursorValue) -> Self {
+        match value {
+            CursorValue::Int(value) => value.into(),
+            CursorValue::Float(value) => value.into(),
+            CursorValue::Text(value) => value.into(),
+            CursorValue::Date(value) => value.into(),
+            CursorValue::DateTime(value) => value.into(),
+        }
+    }
+}
+
+/// where the next page starts, along with the sort it was made for.
+#[derive(Serialize, Deserialize)]
+struct Cursor {
+    sort: String,
+    value: CursorValue,
+    id: Uuid,
+}
+
+impl Cursor {
+    fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).unwrap_or_default())
+    }
+
+    fn decode(cursor: &str) -> Result<Self, RepoError> {
+        URL_SAFE_NO_PAD
+            .decode(cursor)
+            .ok()
+            .and_then(|json| serde_json::from_slice(&json).ok())
+            .ok_or_else(|| invalid("the cursor is invalid"))
+    }
+}
+
+fn invalid(reason: impl Into<String>) -> RepoError {
+    RepoError::InvalidListQuery(reason.into())
+}
+
+fn unsupported(param: &str) -> RepoError {
+    invalid(format!("`{param}` isn't supported by this list"))
+}
+
+impl ListQuery {
+    /// the sort field, its column and direction.
+    fn sort<E: Listable>(&self) -> Result<(&str, E::Column, Order), RepoError> {
+        let sort = self.sort.as_deref().unwrap_or(E::DEFAULT_SORT);
+        let (field, order) = match sort.strip_prefix('-') {
+            Some(field) => (field, Order::Desc),
+            None => (sort, Order::Asc),
+        };
+
+        E::SORTS
+            .iter()
+            .find(|(name, _)| *name == field)
+            .map(|(_, column)| (sort, *column, order))
+            .ok_or_else(|| {
+                let fields = E::SORTS.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+                invalid(format!(
+                    "can't sort by `{field}`, only by {}",
+                    fields.join(", ")
+                ))
+            })
+    }
+
+    fn filter<E: Listable>(&self, mut select: Select<E>) -> Result<Select<E>, RepoError> {
+        if let Some(name) = self.name.as_deref().filter(|name| !name.is_empty()) {
+            let column = E::NAME.ok_or_else(|| unsupported("name"))?;
+            // backslash is the default escape character of postgres
+            let escaped = name
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_");
+            select = select.filter(
+                Expr::col(column.as_column_ref()).ilike(LikeExpr::new(format!("%{escaped}%"))),
+            );
+        }
+
+        let number = |param| E::NUMBER.ok_or_else(|| unsupported(param));
+        if let Some(from) = self.number_from {
+            select = select.filter(number("numberFrom")?.gte(from));
+        }
+        if let Some(to) = self.number_to {
+            select = select.filter(number("numberTo")?.lte(to));
+        }
+
+        let created_at = |param| E::CREATED_AT.ok_or_else(|| unsupported(param));
+        if let Some(from) = self.created_from {
+            select = select.filter(created_at("createdFrom")?.gte(from));
+        }
+        if let Some(to) = self.created_to {
+            select = select.filter(created_at("createdTo")?.lte(to));
+        }
+
+        Ok(select)
+    }
+
+    /// fetches the page of `select` this query refers to.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the sort, a filter or the cursor
+    /// doesn't apply to the list.
+    pub(crate) async fn page<E, C>(
+        &self,
+        conn: &C,
+        select: Select<E>,
+    ) -> Result<Page<E::Model>, RepoError>
+    where
+        E: Listable,
+        E::Model: Sync,
+        C: ConnectionTrait,
+    {
+        let (sort, column, order) = self.sort::<E>()?;
+        let select = self.filter(select)?;
+        let total = select.clone().count(conn).await?;
+
+        let mut select = select
+            .order_by(column, order.clone())
+            .order_by(E::ID, order.clone());
+
+        if let Some(cursor) = &self.cursor {
+            if self.offset.is_some() {
+                return Err(invalid("`offset` can't be combined with `cursor`"));
+            }
+            let Cursor {
+                sort: for_sort,
+                value,
+                id,
+            } = Cursor::decode(cursor)?;
+            if for_sort != sort {
+                return Err(invalid(format!(
+                    "the cursor was made for sorting by `{for_sort}`"
+                )));
+            }
+            let value = Value::from(value);
+            select = select.filter(match order {
+                Order::Asc => Condition::any()
+                    .add(column.gt(value.clone()))
+                    .add(Condition::all().add(column.eq(value)).add(E::ID.gt(id))),
+                _ => Condition::any()
+                    .add(column.lt(value.clone()))
+                    .add(Condition::all().add(column.eq(value)).add(E::ID.lt(id))),
+            });
+        }
+        if let Some(offset) = self.offset {
+            select = select.offset(offset);
+        }
+        // one more item tells whether there is a next page
+        if let Some(limit) = self.limit {
+            select = select.limit(limit + 1);
+        }
+
+        let mut items = select.all(conn).await?;
+
+        let mut next_cursor = None;
+        if let Some(limit) = self.limit {
+            if items.len() as u64 > limit {
+                items.truncate(limit as usize);
+                next_cursor = items.last().and_then(|last| {
+                    let value = CursorValue::from_value(last.get(column))?;
+                    let Value::Uuid(Some(id)) = last.get(E::ID) else {
+                        return None;
+                    };
+                    Some(
+                        Cursor {
+                            sort: sort.to_owned(),
+                            value,
+                            id: *id,
+                        }
+                        .encode(),
+                    )
+                });
+            }
+        }
+
+        Ok(Page {
+            items,
+            total,
+            next_cursor,
+        })
+    }
+}
@@ -0,0 +1,75 @@
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use once_cell::sync::Lazy;
+use subtle::ConstantTimeEq;
+
+use crate::error::RepoError;
+
+/// a valid hash used to burn the same amount of time when the account does not exist.
+static DUMMY_HASH: Lazy<String> = Lazy::new(|| hash_blocking("dummy password").unwrap());
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verification {
+    /// the password matches an argon2 hash.
+    Valid,
+    /// the password matches a legacy plaintext value and should be re-hashed.
+    ValidLegacy,
+    Invalid,
+}
+
+fn hash_blocking(password: &str) -> Result<String, RepoError> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|error| {
+            tracing::error!("failed to hash a password: {error}");
+            RepoError::Unknown
+        })
+}
+
+fn verify_blocking(password: &str, stored: &str) -> Verification {
+    match PasswordHash::new(stored) {
+        Ok(hash) => match Argon2::default().verify_password(password.as_bytes(), &hash) {
+            Ok(()) => Verification::Valid,
+            Err(_) => Verification::Invalid,
+        },
+        Err(_) => {
+            if bool::from(password.as_bytes().ct_eq(stored.as_bytes())) {
+                Verification::ValidLegacy
+            } else {
+                Verification::Invalid
+            }
+        }
+    }
+}
+
+/// hashes a password with argon2 and a random salt.
+///
+/// # Errors
+///
+/// This function will return an error if the hasher fails.
+pub async fn hash(password: String) -> Result<String, RepoError> {
+    tokio::task::spawn_blocking(move || hash_blocking(&password))
+        .await
+        .map_err(|_| RepoError::Unknown)?
+}
+
+/// verifies a password against a stored value.
+///
+/// stored values that are not argon2 hashes are treated as legacy plaintext
+/// passwords and compared in constant time.
+pub async fn verify(password: String, stored: String) -> Verification {
+    tokio::task::spawn_blocking(move || verify_blocking(&password, &stored))
+        .await
+        .unwrap_or(Verification::Invalid)
+}
+
+/// burns the time of a real verification so unknown accounts can't be told apart.
+pub async fn verify_dummy(password: String) {
+    tokio::task::spawn_blocking(move || verify_blocking(&password, &DUMMY_HASH))
+        .await
+        .ok();
+}
@@ -1,2 +1,7 @@
 pub use crate::subjects::*;
-pub use crate::{admins::*, attendances::*, attendees::*, error::*, instructors::*};
+pub use crate::terms::*;
+pub use crate::{
+    admins::*, attendances::*, attendees::*, audit_log::*, class_sessions::*, classifiers::*,
+    error::*, face_samples::*, imports::*, instructors::*, match_settings::*, pagination::*,
+    reports::*, sessions::*, settings::*,
+};
@@ -0,0 +1,105 @@
+use itertools::Itertools;
+use rust_xlsxwriter::{Format, Workbook, XlsxError};
+
+use sea_orm::ActiveEnum;
+
+use super::SubjectReport;
+use crate::error::RepoError;
+
+impl From<csv::Error> for RepoError {
+    fn from(error: csv::Error) -> Self {
+        Self::Export(error.to_string())
+    }
+}
+
+impl From<XlsxError> for RepoError {
+    fn from(error: XlsxError) -> Self {
+        Self::Export(error.to_string())
+    }
+}
+
+impl SubjectReport {
+    fn header(&self) -> Vec<String> {
+        ["number", "name"]
+            .into_iter()
+            .map(str::to_owned)
+            .chain(
+                self.sessions
+                    .iter()
+                    .map(|session| session.starts_at.format("%Y-%m-%d %H:%M").to_string()),
+            )
+            .chain(
+                ["attended", "excused", "absences", "absence rate", "warning"]
+                    .into_iter()
+                    .map(str::to_owned),
+            )
+            .collect_vec()
+    }
+
+    /// the report as csv, one row per attendee and one column per session.
+    pub fn to_csv(&self) -> Result<Vec<u8>, RepoError> {
+        let mut writer = csv::Writer::from_writer(vec![]);
+
+        writer.write_record(self.header())?;
+
+        for row in &self.rows {
+            writer.write_record(
+                [row.number.to_string(), row.name.clone()]
+                    .into_iter()
+                    .chain(row.statuses.iter().map(|status| status.to_value()))
+                    .chain([
+                        row.attended.to_string(),
+                        row.excused.to_string(),
+                        row.absences.to_string(),
+                        format!("{:.1}", row.absence_rate),
+                        row.warning.to_string(),
+                    ]),
+            )?;
+        }
+
+        writer
+            .into_inner()
+            .map_err(|error| RepoError::Export(error.to_string()))
+    }
+
+    /// the report as an xlsx workbook with the same layout as [`Self::to_csv`],
+    /// flagged attendees are highlighted.
+    pub fn to_xlsx(&self) -> Result<Vec<u8>, RepoError> {
+        let bold = Format::new().set_bold();
+        let flagged = Format::new().set_background_color("#FFC7CE");
+
+        let mut workbook = Workbook::new();
+        let sheet = workbook.add_worksheet();
+
+        for (col, title) in self.header().iter().enumerate() {
+            sheet.write_string_with_format(0, col as u16, title, &bold)?;
+        }
+        sheet.set_freeze_panes(1, 2)?;
+
+        for (index, row) in self.rows.iter().enumerate() {
+            let line = index as u32 + 1;
+            let format = if row.warning {
+                &flagged
+            } else {
+                &Format::default()
+            };
+
+            sheet.write_number_with_format(line, 0, row.number as f64, format)?;
+            sheet.write_string_with_format(line, 1, &row.name, format)?;
+
+            let mut col = 2;
+            for status in &row.statuses {
+                sheet.write_string_with_format(line, col, status.to_value(), format)?;
+                col += 1;
+            }
+
+            sheet.write_number_with_format(line, col, row.attended as f64, format)?;
+            sheet.write_number_with_format(line, col + 1, row.excused as f64, format)?;
+            sheet.write_number_with_format(line, col + 2, row.absences as f64, format)?;
+            sheet.write_number_with_format(line, col + 3, row.absence_rate, format)?;
+            sheet.write_boolean_with_format(line, col + 4, row.warning, format)?;
+        }
+
+        Ok(workbook.save_to_buffer()?)
+    }
+}
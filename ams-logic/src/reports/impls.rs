@@ -0,0 +1,192 @@
+use std::{collections::HashMap, sync::Arc};
+
+use chrono::Utc;
+use chrono_tz::Tz;
+use itertools::Itertools;
+use sea_orm::{
+    prelude::{async_trait::async_trait, *},
+    sea_query::Query,
+    QueryOrder, QuerySelect, QueryTrait, TransactionTrait,
+};
+
+use super::*;
+
+use crate::entity::{attendances, attendees, attendees_subjects, class_sessions, subjects};
+use crate::{
+    attendances::AttendanceStatus,
+    class_sessions::{generate_sessions, ClassSessionStatus},
+    terms::TermCalendar,
+};
+
+pub struct ReportsRepo {
+    db: Arc<DatabaseConnection>,
+    timezone: Tz,
+}
+
+impl ReportsRepo {
+    pub fn new(db: Arc<DatabaseConnection>, timezone: Tz) -> Self {
+        Self { db, timezone }
+    }
+}
+
+impl AsRef<DatabaseConnection> for ReportsRepo {
+    fn as_ref(&self) -> &DatabaseConnection {
+        &self.db
+    }
+}
+
+#[async_trait]
+impl ReportsRepoTrait for ReportsRepo {
+    async fn subject_report(
+        &self,
+        subject_id: Uuid,
+        ReportOptions {
+            from,
+            to,
+            absence_threshold,
+        }: ReportOptions,
+    ) -> Result<SubjectReport, RepoError> {
+        let txn = self.as_ref().begin().await?;
+
+        let subject = subjects::Entity::find_by_id(subject_id)
+            .one(&txn)
+            .await?
+            .ok_or(RepoError::NotFound("subjects".to_owned()))?;
+
+        let now = Utc::now();
+        let calendar = TermCalendar::of_subject(&txn, &subject).await?;
+
+        // sessions nobody generated yet still took place, the range is clamped
+        // to the term and to today since later sessions don't count anyway.
+        let today = now.with_timezone(&self.timezone).date_naive();
+        let mut first = from.unwrap_or_else(|| match &calendar {
+            Some(calendar) => calendar.starts_on,
+            None => subject.create_at.with_timezone(&self.timezone).date_naive(),
+        });
+        let mut last = to.map_or(today, |to| to.min(today));
+        if let Some(calendar) = &calendar {
+            first = first.max(calendar.starts_on);
+            last = last.min(calendar.ends_on);
+        }
+        if first <= last {
+            generate_sessions(&txn, self.timezone, subject_id, first, last).await?;
+        }
+
+        // only sessions that already started count, cancelled ones and the ones
+        // the calendar rules out never took place.
+        let sessions = subject
+            .find_related(class_sessions::Entity)
+            .filter(class_sessions::Column::Status.ne(ClassSessionStatus::Cancelled))
+            .filter(class_sessions::Column::StartsAt.lte(now))
+            .apply_if(from, |query, from| {
+                query.filter(class_sessions::Column::OccurrenceDate.gte(from))
+            })
+            .apply_if(to, |query, to| {
+                query.filter(class_sessions::Column::OccurrenceDate.lte(to))
+            })
+            .order_by_asc(class_sessions::Column::StartsAt)
+            .all(&txn)
+            .await?
+            .into_iter()
+            .filter(|session| TermCalendar::holds(calendar.as_ref(), session))
+            .collect_vec();
+
+        let roster = attendees::Entity::find()
+            .filter(
+                attendees::Column::Id.in_subquery(
+                    Query::select()
+                        .column(attendees_subjects::Column::AttendeeId)
+                        .from(attendees_subjects::Entity)
+                        .and_where(attendees_subjects::Column::SubjectId.eq(subject_id))
+                        .to_owned(),
+                ),
+            )
+            .order_by_asc(attendees::Column::Number)
+            .all(&txn)
+            .await?;
+
+        let taken: HashMap<(Uuid, Uuid), AttendanceStatus> = attendances::Entity::find()
+            .select_only()
+            .column(attendances::Column::AttendeeId)
+            .column(attendances::Column::ClassSessionId)
+            .column(attendances::Column::Status)
+            .filter(
+                attendances::Column::ClassSessionId
+                    .is_in(sessions.iter().map(|session| session.id).collect_vec()),
+            )
+            .into_tuple::<(Uuid, Uuid, AttendanceStatus)>()
+            .all(&txn)
+            .await?
+            .into_iter()
+            .map(|(attendee_id, session_id, status)| ((attendee_id, session_id), status))
+            .collect();
+
+        let rows = roster
+            .into_iter()
+            .map(|attendee| {
+                let statuses = sessions
+                    .iter()
+                    .map(|session| {
+                        taken
+                            .get(&(attendee.id, session.id))
+                            .copied()
+                            .unwrap_or(AttendanceStatus::Absent)
+                    })
+                    .collect_vec();
+                let presence = statuses
+                    .iter()
+                    .map(|status| {
+                        matches!(status, AttendanceStatus::Present | AttendanceStatus::Late)
+                    })
+                    .collect_vec();
+                let attended = presence.iter().filter(|present| **present).count();
+                let excused = statuses
+                    .iter()
+                    .filter(|status| **status == AttendanceStatus::Excused)
+                    .count();
+                let absences = statuses.len() - attended - excused;
+                let counted = statuses.len() - excused;
+                let absence_rate = if counted == 0 {
+                    0.0
+                } else {
+                    absences as f64 * 100.0 / counted as f64
+                };
+
+                ReportRow {
+                    attendee_id: attendee.id,
+                    number: attendee.number,
+                    name: attendee.name,
+                    presence,
+                    statuses,
+                    attended,
+                    excused,
+                    absences,
+                    absence_rate,
+                    warning: absence_rate > absence_threshold,
+                }
+            })
+            .collect_vec();
+
+        let sessions = sessions
+            .into_iter()
+            .map(|session| ReportSession {
+                id: session.id,
+                occurrence_date: session.occurrence_date,
+                starts_at: session.starts_at,
+                ends_at: session.ends_at,
+                status: session.status,
+            })
+            .collect_vec();
+
+        txn.commit().await?;
+
+        Ok(SubjectReport {
+            subject_id,
+            subject_name: subject.name,
+            absence_threshold,
+            sessions,
+            rows,
+            generated_at: now.into(),
+        })
+    }
+}
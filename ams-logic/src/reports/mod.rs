@@ -0,0 +1,21 @@
+mod export;
+mod impls;
+mod models;
+
+pub use impls::*;
+pub use models::*;
+
+use sea_orm::prelude::async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::error::RepoError;
+
+#[async_trait]
+pub trait ReportsRepoTrait {
+    /// builds the roster by session attendance matrix of a subject.
+    async fn subject_report(
+        &self,
+        subject_id: Uuid,
+        options: ReportOptions,
+    ) -> Result<SubjectReport, RepoError>;
+}
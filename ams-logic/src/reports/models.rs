@@ -0,0 +1,63 @@
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{attendances::AttendanceStatus, class_sessions::ClassSessionStatus};
+
+/// a column of the report.
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportSession {
+    pub id: Uuid,
+    #[schema(value_type = String, format = Date)]
+    pub occurrence_date: NaiveDate,
+    pub starts_at: DateTime<FixedOffset>,
+    pub ends_at: DateTime<FixedOffset>,
+    pub status: ClassSessionStatus,
+}
+
+/// a row of the report, one per enrolled attendee.
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportRow {
+    pub attendee_id: Uuid,
+    pub number: i64,
+    pub name: String,
+    /// whether the attendee was present or late, in the order of the report
+    /// sessions.
+    pub presence: Vec<bool>,
+    /// the status in each of the report sessions, absent when no attendance
+    /// was taken.
+    pub statuses: Vec<AttendanceStatus>,
+    pub attended: usize,
+    /// excused sessions are neither attended nor absences.
+    pub excused: usize,
+    pub absences: usize,
+    /// percentage of the sessions the attendee was absent from, excused
+    /// sessions aside.
+    pub absence_rate: f64,
+    /// set when `absence_rate` is above the report threshold.
+    pub warning: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SubjectReport {
+    pub subject_id: Uuid,
+    pub subject_name: String,
+    /// the absence percentage above which attendees are flagged.
+    pub absence_threshold: f64,
+    pub sessions: Vec<ReportSession>,
+    pub rows: Vec<ReportRow>,
+    pub generated_at: DateTime<FixedOffset>,
+}
+
+pub struct ReportOptions {
+    /// first local occurrence date, inclusive.
+    pub from: Option<NaiveDate>,
+    /// last local occurrence date, inclusive.
+    pub to: Option<NaiveDate>,
+    /// the absence percentage above which attendees are flagged.
+    pub absence_threshold: f64,
+}
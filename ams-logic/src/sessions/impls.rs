@@ -0,0 +1,181 @@
+use std::sync::Arc;
+
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use chrono::{DateTime, FixedOffset, Utc};
+use sea_orm::{
+    prelude::{async_trait::async_trait, *},
+    sea_query::Expr,
+    Set,
+};
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+
+use super::*;
+
+pub struct SessionsRepo(pub Arc<DatabaseConnection>);
+
+impl AsRef<DatabaseConnection> for SessionsRepo {
+    fn as_ref(&self) -> &DatabaseConnection {
+        &self.0
+    }
+}
+
+/// generates a random secret and returns it along with its hash.
+fn generate_secret() -> (String, String) {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    let secret = hex(&bytes);
+    let hash = hash_secret(&secret);
+    (secret, hash)
+}
+
+fn hash_secret(secret: &str) -> String {
+    hex(&Sha256::digest(secret.as_bytes()))
+}
+
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// refresh tokens are formatted as `<session id>.<secret>`.
+fn parse_refresh_token(refresh_token: &str) -> Option<(Uuid, &str)> {
+    let (id, secret) = refresh_token.split_once('.')?;
+    Some((Uuid::parse_str(id).ok()?, secret))
+}
+
+#[async_trait]
+impl SessionsRepoTrait for SessionsRepo {
+    async fn create(
+        &self,
+        CreateSession {
+            user_kind,
+            user_id,
+            expires_at,
+        }: CreateSession,
+    ) -> Result<IssuedSession, RepoError> {
+        let (secret, refresh_token_hash) = generate_secret();
+
+        let session = sessions::ActiveModel {
+            user_kind: Set(user_kind),
+            user_id: Set(user_id),
+            refresh_token_hash: Set(refresh_token_hash),
+            expires_at: Set(expires_at),
+            ..Default::default()
+        }
+        .insert(self.as_ref())
+        .await?;
+
+        Ok(IssuedSession {
+            refresh_token: format!("{}.{secret}", session.id),
+            session: session.into(),
+        })
+    }
+
+    async fn rotate(
+        &self,
+        refresh_token: String,
+        expires_at: DateTime<FixedOffset>,
+    ) -> Result<IssuedSession, RepoError> {
+        let (id, secret) = parse_refresh_token(&refresh_token).ok_or(RepoError::InvalidSession)?;
+
+        let session = sessions::Entity::find_by_id(id)
+            .one(self.as_ref())
+            .await?
+            .ok_or(RepoError::InvalidSession)?;
+
+        if session.revoked_at.is_some() || session.expires_at < Utc::now() {
+            return Err(RepoError::InvalidSession);
+        }
+
+        let presented = hash_secret(secret);
+        if !bool::from(
+            presented
+                .as_bytes()
+                .ct_eq(session.refresh_token_hash.as_bytes()),
+        ) {
+            // an already rotated token was replayed, the session is likely stolen.
+            tracing::warn!("refresh token reuse detected for session `{id}`");
+            self.revoke(id).await?;
+            return Err(RepoError::InvalidSession);
+        }
+
+        let (secret, refresh_token_hash) = generate_secret();
+        let updated_at: DateTime<FixedOffset> = Utc::now().into();
+
+        // only the first of concurrent refreshes with the same token swaps the
+        // hash, the others are treated as reuse.
+        let rotated = sessions::Entity::update_many()
+            .col_expr(
+                sessions::Column::RefreshTokenHash,
+                Expr::value(refresh_token_hash.clone()),
+            )
+            .col_expr(sessions::Column::ExpiresAt, Expr::value(expires_at))
+            .col_expr(sessions::Column::UpdatedAt, Expr::value(updated_at))
+            .filter(sessions::Column::Id.eq(id))
+            .filter(sessions::Column::RefreshTokenHash.eq(presented))
+            .filter(sessions::Column::RevokedAt.is_null())
+            .exec(self.as_ref())
+            .await?;
+        if rotated.rows_affected == 0 {
+            tracing::warn!("refresh token reuse detected for session `{id}`");
+            self.revoke(id).await?;
+            return Err(RepoError::InvalidSession);
+        }
+
+        let session = sessions::Model {
+            refresh_token_hash,
+            expires_at,
+            updated_at,
+            ..session
+        };
+
+        Ok(IssuedSession {
+            refresh_token: format!("{}.{secret}", session.id),
+            session: session.into(),
+        })
+    }
+
+    async fn get_by_id(&self, id: Uuid) -> Result<Session, RepoError> {
+        Ok(sessions::Entity::find_by_id(id)
+            .one(self.as_ref())
+            .await?
+            .ok_or(RepoError::NotFound("sessions".to_owned()))?
+            .into())
+    }
+
+    async fn is_active(&self, id: Uuid) -> Result<bool, RepoError> {
+        let Some(session) = sessions::Entity::find_by_id(id).one(self.as_ref()).await? else {
+            return Ok(false);
+        };
+
+        Ok(session.revoked_at.is_none() && session.expires_at > Utc::now())
+    }
+
+    async fn revoke(&self, id: Uuid) -> Result<(), RepoError> {
+        let mut session: sessions::ActiveModel = sessions::Entity::find_by_id(id)
+            .one(self.as_ref())
+            .await?
+            .ok_or(RepoError::NotFound("sessions".to_owned()))?
+            .into();
+
+        let now: DateTime<FixedOffset> = Utc::now().into();
+        session.revoked_at = Set(Some(now));
+        session.updated_at = Set(now);
+        session.update(self.as_ref()).await?;
+
+        Ok(())
+    }
+    async fn revoke_all(&self, user_kind: UserKind, user_id: Uuid) -> Result<u64, RepoError> {
+        let now: DateTime<FixedOffset> = Utc::now().into();
+        let result = sessions::Entity::update_many()
+            .col_expr(sessions::Column::RevokedAt, Expr::value(now))
+            .col_expr(sessions::Column::UpdatedAt, Expr::value(now))
+            .filter(sessions::Column::UserKind.eq(user_kind))
+            .filter(sessions::Column::UserId.eq(user_id))
+            .filter(sessions::Column::RevokedAt.is_null())
+            .exec(self.as_ref())
+            .await?;
+
+        Ok(result.rows_affected)
+    }
+}
@@ -0,0 +1,28 @@
+mod impls;
+mod models;
+
+use chrono::{DateTime, FixedOffset};
+use sea_orm::prelude::async_trait::async_trait;
+use uuid::Uuid;
+
+pub use impls::*;
+pub use models::*;
+
+use crate::error::RepoError;
+
+use crate::entity::sessions;
+
+#[async_trait]
+pub trait SessionsRepoTrait {
+    async fn create(&self, session: CreateSession) -> Result<IssuedSession, RepoError>;
+    async fn rotate(
+        &self,
+        refresh_token: String,
+        expires_at: DateTime<FixedOffset>,
+    ) -> Result<IssuedSession, RepoError>;
+    async fn get_by_id(&self, id: Uuid) -> Result<Session, RepoError>;
+    async fn is_active(&self, id: Uuid) -> Result<bool, RepoError>;
+    async fn revoke(&self, id: Uuid) -> Result<(), RepoError>;
+    /// revokes every active session of the user, returning how many were.
+    async fn revoke_all(&self, user_kind: UserKind, user_id: Uuid) -> Result<u64, RepoError>;
+}
@@ -0,0 +1,70 @@
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub use crate::entity::sea_orm_active_enums::UserKind;
+
+use super::sessions;
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Session {
+    pub id: Uuid,
+    #[serde(skip)]
+    pub user_kind: UserKind,
+    pub user_id: Uuid,
+    pub expires_at: DateTime<FixedOffset>,
+    pub revoked_at: Option<DateTime<FixedOffset>>,
+    pub create_at: DateTime<FixedOffset>,
+    pub updated_at: DateTime<FixedOffset>,
+}
+
+impl From<sessions::Model> for Session {
+    fn from(
+        sessions::Model {
+            id,
+            user_kind,
+            user_id,
+            expires_at,
+            revoked_at,
+            create_at,
+            updated_at,
+            ..
+        }: sessions::Model,
+    ) -> Self {
+        Self {
+            id,
+            user_kind,
+            user_id,
+            expires_at,
+            revoked_at,
+            create_at,
+            updated_at,
+        }
+    }
+}
+
+/// a session along with the plain refresh token handed to the client.
+///
+/// only a hash of the refresh token is stored, so this is the only chance to read it.
+#[derive(Debug)]
+pub struct IssuedSession {
+    pub session: Session,
+    pub refresh_token: String,
+}
+
+#[derive(Debug)]
+pub struct CreateSession {
+    pub user_kind: UserKind,
+    pub user_id: Uuid,
+    pub expires_at: DateTime<FixedOffset>,
+}
+
+/// the user behind a change.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Actor {
+    pub kind: UserKind,
+    pub id: Uuid,
+}
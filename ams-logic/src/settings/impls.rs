@@ -0,0 +1,140 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::Utc;
+use sea_orm::{
+    prelude::{async_trait::async_trait, *},
+    sea_query::OnConflict,
+    DatabaseBackend, Set, Statement, TransactionTrait,
+};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use sqlx::postgres::PgListener;
+use tokio::sync::watch;
+
+use super::*;
+
+use crate::audit_log::{self, AuditChange};
+
+/// notified on every change, so every instance reloads its settings.
+const SETTINGS_CHANNEL: &str = "settings_changed";
+
+pub struct SettingsService {
+    db: Arc<DatabaseConnection>,
+    defaults: Settings,
+    settings: watch::Sender<Settings>,
+}
+
+impl AsRef<DatabaseConnection> for SettingsService {
+    fn as_ref(&self) -> &DatabaseConnection {
+        &self.db
+    }
+}
+
+fn to_map<T: Serialize>(value: &T) -> Result<Map<String, Value>, RepoError> {
+    match serde_json::to_value(value) {
+        Ok(Value::Object(map)) => Ok(map),
+        _ => Err(RepoError::Unknown),
+    }
+}
+
+/// the stored settings over `defaults`.
+async fn read<C: ConnectionTrait>(conn: &C, defaults: &Settings) -> Result<Settings, RepoError> {
+    let mut values = to_map(defaults)?;
+    for setting in settings::Entity::find().all(conn).await? {
+        values.insert(setting.key, setting.value);
+    }
+
+    serde_json::from_value(Value::Object(values)).map_err(|error| {
+        tracing::error!("stored settings are invalid: {error}");
+        RepoError::Unknown
+    })
+}
+
+impl SettingsService {
+    /// loads the stored settings, the ones never changed keep their `defaults`.
+    pub async fn load(db: Arc<DatabaseConnection>, defaults: Settings) -> Result<Self, RepoError> {
+        let settings = read(db.as_ref(), &defaults).await?;
+        let (settings, _) = watch::channel(settings);
+
+        Ok(Self {
+            db,
+            defaults,
+            settings,
+        })
+    }
+}
+
+#[async_trait]
+impl SettingsServiceTrait for SettingsService {
+    fn current(&self) -> Settings {
+        self.settings.borrow().clone()
+    }
+    fn subscribe(&self) -> watch::Receiver<Settings> {
+        self.settings.subscribe()
+    }
+    async fn update(&self, settings: UpdateSettings) -> Result<Settings, RepoError> {
+        settings.validate()?;
+
+        let txn = self.as_ref().begin().await?;
+        let before = read(&txn, &self.defaults).await?;
+
+        for (key, value) in to_map(&settings)? {
+            settings::Entity::insert(settings::ActiveModel {
+                key: Set(key),
+                value: Set(value),
+                updated_at: Set(Utc::now().into()),
+            })
+            .on_conflict(
+                OnConflict::column(settings::Column::Key)
+                    .update_columns([settings::Column::Value, settings::Column::UpdatedAt])
+                    .to_owned(),
+            )
+            .exec(&txn)
+            .await?;
+        }
+        let after = read(&txn, &self.defaults).await?;
+        audit_log::record(&txn, None, AuditChange::Updated(&before, &after)).await?;
+
+        // delivered once the transaction commits
+        txn.execute(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            "SELECT pg_notify($1, '')",
+            [SETTINGS_CHANNEL.into()],
+        ))
+        .await?;
+
+        txn.commit().await?;
+
+        self.reload().await
+    }
+    async fn reload(&self) -> Result<Settings, RepoError> {
+        let settings = read(self.as_ref(), &self.defaults).await?;
+        self.settings.send_replace(settings.clone());
+
+        Ok(settings)
+    }
+    async fn listen(&self) -> Result<(), RepoError> {
+        let mut listener = PgListener::connect_with(self.db.get_postgres_connection_pool())
+            .await
+            .map_err(|_| RepoError::Unknown)?;
+        listener
+            .listen(SETTINGS_CHANNEL)
+            .await
+            .map_err(|_| RepoError::Unknown)?;
+
+        loop {
+            match listener.recv().await {
+                Ok(_) => {
+                    if let Err(error) = self.reload().await {
+                        tracing::error!("failed to reload the settings: {error}");
+                    }
+                }
+                // the listener reconnects on the next receive
+                Err(error) => {
+                    tracing::error!("lost the settings notifications: {error}");
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,28 @@
+mod impls;
+mod models;
+
+pub use impls::*;
+pub use models::*;
+
+use sea_orm::prelude::async_trait::async_trait;
+use tokio::sync::watch;
+
+use crate::error::RepoError;
+
+use crate::entity::settings;
+
+/// the runtime settings, cached and kept in sync with the `settings` table.
+#[async_trait]
+pub trait SettingsServiceTrait {
+    /// the cached settings.
+    fn current(&self) -> Settings;
+    /// yields the settings every time they change.
+    fn subscribe(&self) -> watch::Receiver<Settings>;
+    /// writes the given settings through to the database.
+    async fn update(&self, settings: UpdateSettings) -> Result<Settings, RepoError>;
+    /// reads the settings from the database again.
+    async fn reload(&self) -> Result<Settings, RepoError>;
+    /// reloads the settings whenever any instance changes them, until the
+    /// database can no longer be listened to.
+    async fn listen(&self) -> Result<(), RepoError>;
+}
@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{attendees::MatchStrategy, error::RepoError};
+
+/// how attendees are recognized from an image.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum FaceRecMode {
+    /// by the classifier of the face recognition service.
+    Classify,
+    /// by searching the nearest attendee embeddings.
+    Embed,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Settings {
+    pub face_rec_mode: FaceRecMode,
+    pub match_strategy: MatchStrategy,
+    /// how many candidates an embedding search returns.
+    #[schema(example = 5)]
+    pub max_matches: u64,
+    /// from `0` to `1`, face samples of lower quality are rejected.
+    #[schema(example = 0.5)]
+    pub face_sample_min_quality: f64,
+    /// absence percentage above which reports flag attendees.
+    #[schema(example = 25.0)]
+    pub absence_warning_threshold: f64,
+    /// minutes before a session starts attendance is accepted.
+    pub attendance_early_grace: i64,
+    /// minutes after a session ends attendance is accepted.
+    pub attendance_late_grace: i64,
+}
+
+/// the settings to change, the rest are kept.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub face_rec_mode: Option<FaceRecMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub match_strategy: Option<MatchStrategy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_matches: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub face_sample_min_quality: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub absence_warning_threshold: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attendance_early_grace: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attendance_late_grace: Option<i64>,
+}
+
+impl UpdateSettings {
+    /// every changed setting within its range.
+    pub(crate) fn validate(&self) -> Result<(), RepoError> {
+        let invalid = |key: &str, reason: &str| {
+            Err(RepoError::InvalidSetting {
+                key: key.to_owned(),
+                reason: reason.to_owned(),
+            })
+        };
+
+        if self.max_matches == Some(0) {
+            return invalid("maxMatches", "must be at least 1");
+        }
+        if let Some(quality) = self.face_sample_min_quality {
+            if !(0.0..=1.0).contains(&quality) {
+                return invalid("faceSampleMinQuality", "must be between 0 and 1");
+            }
+        }
+        if let Some(threshold) = self.absence_warning_threshold {
+            if !(0.0..=100.0).contains(&threshold) {
+                return invalid("absenceWarningThreshold", "must be between 0 and 100");
+            }
+        }
+        if self.attendance_early_grace.is_some_and(|grace| grace < 0) {
+            return invalid("attendanceEarlyGrace", "must not be negative");
+        }
+        if self.attendance_late_grace.is_some_and(|grace| grace < 0) {
+            return invalid("attendanceLateGrace", "must not be negative");
+        }
+
+        Ok(())
+    }
+}
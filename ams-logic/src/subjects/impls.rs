@@ -1,16 +1,22 @@
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 
 use itertools::Itertools;
 use sea_orm::{
     prelude::{async_trait::async_trait, *},
     sea_query::Query,
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryTrait, Set,
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DatabaseTransaction, EntityTrait,
+    QueryFilter, QueryOrder, QuerySelect, QueryTrait, Set, TransactionTrait,
 };
 use uuid::Uuid;
 
 pub use crate::prelude::*;
 
-use crate::entity::{attendees, attendees_subjects, instructors, subject_dates, subjects};
+use crate::{
+    audit_log::{self, AuditChange},
+    entity::{attendees, attendees_subjects, class_sessions, instructors, subject_dates, subjects},
+    pagination::Listable,
+    terms::sync_sessions,
+};
 
 pub struct SubjectsRepository(pub Arc<DatabaseConnection>);
 
@@ -20,17 +26,212 @@ impl AsRef<DatabaseConnection> for SubjectsRepository {
     }
 }
 
+impl Listable for subjects::Entity {
+    const SORTS: &'static [(&'static str, subjects::Column)] = &[
+        ("name", subjects::Column::Name),
+        ("createdAt", subjects::Column::CreateAt),
+    ];
+    const DEFAULT_SORT: &'static str = "name";
+    const ID: subjects::Column = subjects::Column::Id;
+    const NAME: Option<subjects::Column> = Some(subjects::Column::Name);
+    const CREATED_AT: Option<subjects::Column> = Some(subjects::Column::CreateAt);
+}
+
+fn check_capacity(capacity: Option<i32>) -> Result<(), RepoError> {
+    match capacity {
+        Some(capacity) if capacity <= 0 => Err(RepoError::InvalidCapacity(capacity)),
+        _ => Ok(()),
+    }
+}
+
+impl SubjectsRepository {
+    /// locks the subject until the transaction ends, so concurrent enrollments
+    /// can't exceed its capacity together.
+    async fn lock<C: ConnectionTrait>(conn: &C, id: Uuid) -> Result<subjects::Model, RepoError> {
+        subjects::Entity::find_by_id(id)
+            .lock_exclusive()
+            .one(conn)
+            .await?
+            .ok_or(RepoError::NotFound("subjects".to_owned()))
+    }
+
+    /// the subject along with its dates and instructor.
+    async fn load<C: ConnectionTrait>(
+        conn: &C,
+        subject: subjects::Model,
+    ) -> Result<Subject, RepoError> {
+        let instructor = subject
+            .find_related(instructors::Entity)
+            .one(conn)
+            .await?
+            .map(Instructor::from);
+
+        let dates = subject
+            .find_related(subject_dates::Entity)
+            .all(conn)
+            .await?
+            .into_iter()
+            .map_into()
+            .collect_vec();
+
+        Ok((subject, dates, instructor).into())
+    }
+
+    /// the ids of the attendees enrolled in a subject, sorted.
+    async fn roster<C: ConnectionTrait>(conn: &C, id: Uuid) -> Result<Vec<Uuid>, RepoError> {
+        Ok(attendees_subjects::Entity::find()
+            .select_only()
+            .column(attendees_subjects::Column::AttendeeId)
+            .filter(attendees_subjects::Column::SubjectId.eq(id))
+            .order_by_asc(attendees_subjects::Column::AttendeeId)
+            .into_tuple()
+            .all(conn)
+            .await?)
+    }
+
+    /// enrolls an attendee in a subject, bulk imports enroll within their own
+    /// transaction.
+    pub(crate) async fn enroll<C: ConnectionTrait>(
+        conn: &C,
+        id: Uuid,
+        attendee_id: Uuid,
+    ) -> Result<(), RepoError> {
+        let subject = Self::lock(conn, id).await?;
+        let before = Self::roster(conn, id).await?;
+        if let Some(capacity) = subject.capacity {
+            if before.len() >= capacity as usize {
+                return Err(RepoError::OverCapacity {
+                    subject_id: id,
+                    capacity,
+                });
+            }
+        }
+
+        attendees_subjects::ActiveModel {
+            subject_id: Set(id),
+            attendee_id: Set(attendee_id),
+        }
+        .insert(conn)
+        .await?;
+
+        let after = before
+            .iter()
+            .copied()
+            .chain([attendee_id])
+            .sorted()
+            .collect_vec();
+        audit_log::record(conn, Some(id), AuditChange::Updated(&before, &after)).await?;
+
+        Ok(())
+    }
+
+    /// enrolls the attendees as `mode` says within the transaction.
+    async fn apply_roster(
+        txn: &DatabaseTransaction,
+        id: Uuid,
+        attendee_ids: Vec<Uuid>,
+        mode: RosterMode,
+    ) -> Result<RosterDiff, RepoError> {
+        let subject = Self::lock(txn, id).await?;
+        let wanted = attendee_ids.into_iter().unique().collect_vec();
+
+        if !wanted.is_empty() {
+            let found: HashSet<Uuid> = attendees::Entity::find()
+                .select_only()
+                .column(attendees::Column::Id)
+                .filter(attendees::Column::Id.is_in(wanted.clone()))
+                .into_tuple()
+                .all(txn)
+                .await?
+                .into_iter()
+                .collect();
+            if let Some(missing) = wanted.iter().find(|id| !found.contains(id)) {
+                return Err(RepoError::AttendeeNotFound {
+                    id: missing.to_string(),
+                });
+            }
+        }
+
+        let before = Self::roster(txn, id).await?;
+        let current: HashSet<Uuid> = before.iter().copied().collect();
+        let added = wanted
+            .iter()
+            .filter(|attendee_id| !current.contains(attendee_id))
+            .copied()
+            .collect_vec();
+        let removed = match mode {
+            RosterMode::Replace => current
+                .difference(&wanted.iter().copied().collect())
+                .copied()
+                .sorted()
+                .collect_vec(),
+            RosterMode::Merge => Vec::new(),
+        };
+
+        let enrolled = current.len() + added.len() - removed.len();
+        if let Some(capacity) = subject.capacity {
+            if enrolled > capacity as usize {
+                return Err(RepoError::OverCapacity {
+                    subject_id: id,
+                    capacity,
+                });
+            }
+        }
+
+        if !removed.is_empty() {
+            attendees_subjects::Entity::delete_many()
+                .filter(attendees_subjects::Column::SubjectId.eq(id))
+                .filter(attendees_subjects::Column::AttendeeId.is_in(removed.clone()))
+                .exec(txn)
+                .await?;
+        }
+        if !added.is_empty() {
+            attendees_subjects::Entity::insert_many(added.iter().map(|&attendee_id| {
+                attendees_subjects::ActiveModel {
+                    subject_id: Set(id),
+                    attendee_id: Set(attendee_id),
+                }
+            }))
+            .exec(txn)
+            .await?;
+        }
+
+        let after = before
+            .iter()
+            .filter(|attendee_id| !removed.contains(attendee_id))
+            .chain(&added)
+            .copied()
+            .sorted()
+            .collect_vec();
+        audit_log::record(txn, Some(id), AuditChange::Updated(&before, &after)).await?;
+
+        Ok(RosterDiff {
+            added,
+            removed,
+            enrolled,
+        })
+    }
+}
+
 #[async_trait]
 impl SubjectsRepoTrait for SubjectsRepository {
     async fn create(&self, subject: CreateSubject) -> Result<Subject, RepoError> {
+        check_capacity(subject.capacity)?;
+        let txn = self.as_ref().begin().await?;
+
         let created_subject = subjects::ActiveModel {
             name: Set(subject.name),
+            term_id: Set(subject.term_id),
+            capacity: Set(subject.capacity),
             ..Default::default()
         }
-        .insert(self.as_ref())
+        .insert(&txn)
         .await?;
+        let subject: Subject = (created_subject, vec![], None).into();
+        audit_log::record(&txn, Some(subject.id), AuditChange::Created(&subject)).await?;
+        txn.commit().await?;
 
-        Ok((created_subject, vec![], None).into())
+        Ok(subject)
     }
 
     async fn remove_subject_date(
@@ -38,18 +239,33 @@ impl SubjectsRepoTrait for SubjectsRepository {
         subject_id: Uuid,
         subject_date_id: Uuid,
     ) -> Result<(), RepoError> {
-        let subject_date: subject_dates::ActiveModel = subject_dates::Entity::find()
+        let txn = self.as_ref().begin().await?;
+
+        let subject_date = subject_dates::Entity::find()
             .filter(
                 subject_dates::Column::Id
                     .eq(subject_date_id)
                     .and(subject_dates::Column::SubjectId.eq(subject_id)),
             )
-            .one(self.as_ref())
+            .one(&txn)
             .await?
-            .ok_or(RepoError::NotFound("subject_dates".to_owned()))?
-            .into();
+            .ok_or(RepoError::NotFound("subject_dates".to_owned()))?;
+
+        // sessions that already took place keep their attendances.
+        class_sessions::Entity::delete_many()
+            .filter(class_sessions::Column::SubjectDateId.eq(subject_date_id))
+            .filter(class_sessions::Column::StartsAt.gt(chrono::Utc::now()))
+            .exec(&txn)
+            .await?;
 
-        subject_date.delete(self.as_ref()).await?;
+        subject_date.clone().delete(&txn).await?;
+        audit_log::record(
+            &txn,
+            Some(subject_date_id),
+            AuditChange::Deleted(&SubjectDate::from(subject_date)),
+        )
+        .await?;
+        txn.commit().await?;
 
         Ok(())
     }
@@ -63,17 +279,27 @@ impl SubjectsRepoTrait for SubjectsRepository {
             end_time,
         }: CreateSubjectDate,
     ) -> Result<SubjectDate, RepoError> {
-        let subject_date = subject_dates::ActiveModel {
+        let txn = self.as_ref().begin().await?;
+
+        let subject_date: SubjectDate = subject_dates::ActiveModel {
             subject_id: Set(subject_id),
             day_of_week: Set(day_of_week),
             start_time: Set(start_time),
             end_time: Set(end_time),
             ..Default::default()
         }
-        .insert(self.as_ref())
+        .insert(&txn)
+        .await?
+        .into();
+        audit_log::record(
+            &txn,
+            Some(subject_date.id),
+            AuditChange::Created(&subject_date),
+        )
         .await?;
+        txn.commit().await?;
 
-        Ok(subject_date.into())
+        Ok(subject_date)
     }
 
     async fn get_by_id(&self, id: Uuid) -> Result<Subject, RepoError> {
@@ -82,30 +308,20 @@ impl SubjectsRepoTrait for SubjectsRepository {
             .await?
             .ok_or(RepoError::NotFound("subjects".to_owned()))?;
 
-        let instructor = subject
-            .find_related(instructors::Entity)
-            .one(self.as_ref())
-            .await?
-            .map(Instructor::from);
-
-        let dates = subject
-            .find_related(subject_dates::Entity)
-            .all(self.as_ref())
-            .await?
-            .into_iter()
-            .map_into()
-            .collect_vec();
-
-        Ok((subject, dates, instructor).into())
+        Self::load(self.as_ref(), subject).await
     }
 
-    async fn get(&self, filter: SubjectsFilter) -> Result<Vec<Subject>, RepoError> {
-        let subjects: Vec<subjects::Model> = subjects::Entity::find()
+    async fn get(
+        &self,
+        filter: SubjectsFilter,
+        query: ListQuery,
+    ) -> Result<Page<Subject>, RepoError> {
+        let select = subjects::Entity::find()
             .apply_if(filter.id, |query, id| {
                 query.filter(subjects::Column::Id.eq(id))
             })
-            .apply_if(filter.name, |query, name| {
-                query.filter(subjects::Column::Name.eq(format!("%{name}%")))
+            .apply_if(filter.term_id, |query, term| {
+                query.filter(subjects::Column::TermId.eq(term))
             })
             .apply_if(filter.instructor_id, |query, instructor| {
                 query.filter(subjects::Column::InstructorId.eq(instructor))
@@ -120,11 +336,9 @@ impl SubjectsRepoTrait for SubjectsRepository {
                             .to_owned(),
                     ),
                 )
-            })
-            .all(self.as_ref())
-            .await?
-            .into_iter()
-            .collect();
+            });
+        let mut page = query.page(self.as_ref(), select).await?;
+        let subjects = std::mem::take(&mut page.items);
 
         let instructors: Vec<Option<Instructor>> = subjects
             .load_one(instructors::Entity, self.as_ref())
@@ -140,81 +354,169 @@ impl SubjectsRepoTrait for SubjectsRepository {
             .map(|dates| dates.into_iter().map_into().collect_vec())
             .collect_vec();
 
-        Ok(itertools::izip!(subjects, dates, instructors)
+        let subjects = itertools::izip!(subjects, dates, instructors)
             .map_into()
-            .collect_vec())
+            .collect_vec();
+
+        Ok(page.with_items(subjects))
     }
     async fn update(
         &self,
         id: Uuid,
         UpdateSubject {
             name,
+            term_id,
+            capacity,
             instructor_id,
         }: UpdateSubject,
     ) -> Result<Subject, RepoError> {
-        let mut subject: subjects::ActiveModel = subjects::Entity::find_by_id(id)
-            .one(self.as_ref())
-            .await?
-            .ok_or(RepoError::NotFound("subjects".to_owned()))?
-            .into();
+        let txn = self.as_ref().begin().await?;
+
+        let before = Self::lock(&txn, id).await?;
+        let mut subject: subjects::ActiveModel = before.clone().into();
 
         if let Some(name) = name {
             subject.name = Set(name);
         }
+        if let Some(term_id) = term_id {
+            subject.term_id = Set(term_id);
+        }
         if let Some(instructor_id) = instructor_id {
             subject.instructor_id = Set(instructor_id);
         }
+        if let Some(capacity) = capacity {
+            check_capacity(capacity)?;
+            if let Some(capacity) = capacity {
+                let enrolled = Self::roster(&txn, id).await?.len();
+                if enrolled > capacity as usize {
+                    return Err(RepoError::OverCapacity {
+                        subject_id: id,
+                        capacity,
+                    });
+                }
+            }
+            subject.capacity = Set(capacity);
+        }
 
-        let subject: subjects::Model = subject.update(self.as_ref()).await?;
-
-        let instructor = subject
-            .find_related(instructors::Entity)
-            .one(self.as_ref())
-            .await?
-            .map(Instructor::from);
+        let subject = subject.update(&txn).await?;
+        if term_id.is_some() {
+            let calendar = TermCalendar::of_subject(&txn, &subject).await?;
+            sync_sessions(&txn, subjects::Column::Id.eq(id), calendar.as_ref()).await?;
+        }
 
-        let dates = subject
-            .find_related(subject_dates::Entity)
-            .all(self.as_ref())
-            .await?
-            .into_iter()
-            .map_into()
-            .collect_vec();
+        let before = Self::load(&txn, before).await?;
+        let subject = Self::load(&txn, subject).await?;
+        audit_log::record(&txn, Some(id), AuditChange::Updated(&before, &subject)).await?;
+        txn.commit().await?;
 
-        Ok((subject, dates, instructor).into())
+        Ok(subject)
     }
     async fn add_attendee(&self, id: Uuid, attendee_id: Uuid) -> Result<(), RepoError> {
-        attendees_subjects::ActiveModel {
-            subject_id: Set(id),
-            attendee_id: Set(attendee_id),
-        }
-        .insert(self.as_ref())
-        .await?;
+        let txn = self.as_ref().begin().await?;
+        Self::enroll(&txn, id, attendee_id).await?;
+        txn.commit().await?;
+
         Ok(())
     }
+    async fn update_roster(
+        &self,
+        id: Uuid,
+        UpdateRoster { attendee_ids, mode }: UpdateRoster,
+    ) -> Result<RosterDiff, RepoError> {
+        let txn = self.as_ref().begin().await?;
+        let diff = Self::apply_roster(&txn, id, attendee_ids, mode).await?;
+        txn.commit().await?;
+
+        Ok(diff)
+    }
+    async fn copy_roster(
+        &self,
+        id: Uuid,
+        CopyRoster {
+            from_subject_id,
+            mode,
+        }: CopyRoster,
+    ) -> Result<RosterDiff, RepoError> {
+        let txn = self.as_ref().begin().await?;
+        subjects::Entity::find_by_id(from_subject_id)
+            .one(&txn)
+            .await?
+            .ok_or(RepoError::SubjectNotFound {
+                id: from_subject_id.to_string(),
+            })?;
+        let attendee_ids = Self::roster(&txn, from_subject_id).await?;
+        let diff = Self::apply_roster(&txn, id, attendee_ids, mode).await?;
+        txn.commit().await?;
+
+        Ok(diff)
+    }
     async fn remove_attendee(&self, id: Uuid, attendee_id: Uuid) -> Result<(), RepoError> {
+        let txn = self.as_ref().begin().await?;
+
+        let before = Self::roster(&txn, id).await?;
         let attedee_subject: attendees_subjects::ActiveModel = attendees_subjects::Entity::find()
             .filter(
                 attendees_subjects::Column::AttendeeId
                     .eq(attendee_id)
                     .and(attendees_subjects::Column::SubjectId.eq(id)),
             )
-            .one(self.as_ref())
+            .one(&txn)
             .await?
             .ok_or(RepoError::NotFound("attendees_subjects".to_owned()))?
             .into();
-        attedee_subject.delete(self.as_ref()).await?;
+        attedee_subject.delete(&txn).await?;
+
+        let after = before
+            .iter()
+            .copied()
+            .filter(|enrolled| *enrolled != attendee_id)
+            .collect_vec();
+        audit_log::record(&txn, Some(id), AuditChange::Updated(&before, &after)).await?;
+        txn.commit().await?;
+
         Ok(())
     }
     async fn delete_by_id(&self, id: Uuid) -> Result<(), RepoError> {
-        subjects::Entity::delete_by_id(id)
-            .exec(self.as_ref())
+        let txn = self.as_ref().begin().await?;
+
+        let subject = subjects::Entity::find_by_id(id)
+            .lock_exclusive()
+            .one(&txn)
             .await?;
+        if let Some(subject) = subject {
+            let subject = Self::load(&txn, subject).await?;
+            subjects::Entity::delete_by_id(id).exec(&txn).await?;
+            audit_log::record(&txn, Some(id), AuditChange::Deleted(&subject)).await?;
+        }
+        txn.commit().await?;
+
         Ok(())
     }
 
-    async fn get_all_attendees(&self, id: Uuid) -> Result<Vec<Attendee>, RepoError> {
-        let attendees: Vec<attendees::Model> = attendees::Entity::find()
+    async fn get_all_attendees(
+        &self,
+        id: Uuid,
+        query: ListQuery,
+    ) -> Result<Page<Attendee>, RepoError> {
+        let select = attendees::Entity::find().filter(
+            attendees::Column::Id.in_subquery(
+                Query::select()
+                    .column(attendees_subjects::Column::AttendeeId)
+                    .from(attendees_subjects::Entity)
+                    .and_where(attendees_subjects::Column::SubjectId.eq(id))
+                    .to_owned(),
+            ),
+        );
+
+        Ok(query.page(self.as_ref(), select).await?.map(Attendee::from))
+    }
+
+    async fn get_missing_attendees(
+        &self,
+        id: Uuid,
+        found: Vec<Uuid>,
+    ) -> Result<Vec<Attendee>, RepoError> {
+        Ok(attendees::Entity::find()
             .filter(
                 attendees::Column::Id.in_subquery(
                     Query::select()
@@ -224,11 +526,12 @@ impl SubjectsRepoTrait for SubjectsRepository {
                         .to_owned(),
                 ),
             )
+            .filter(attendees::Column::Id.is_not_in(found))
+            .order_by_asc(attendees::Column::Number)
             .all(self.as_ref())
-            .await?;
-
-        let attendees = attendees.into_iter().map_into().collect_vec();
-
-        Ok(attendees)
+            .await?
+            .into_iter()
+            .map(Attendee::from)
+            .collect())
     }
 }
@@ -7,8 +7,6 @@ use uuid::Uuid;
 pub use impls::*;
 pub use models::*;
 
-use crate::error::RepoError;
-
 #[async_trait]
 pub trait SubjectsRepoTrait {
     async fn create(&self, subject: CreateSubject) -> Result<Subject, RepoError>;
@@ -23,10 +21,30 @@ pub trait SubjectsRepoTrait {
         subject_date: CreateSubjectDate,
     ) -> Result<SubjectDate, RepoError>;
     async fn get_by_id(&self, id: Uuid) -> Result<Subject, RepoError>;
-    async fn get(&self, filter: SubjectsFilter) -> Result<Vec<Subject>, RepoError>;
-    async fn get_all_attendees(&self, id: Uuid) -> Result<Vec<Attendee>, RepoError>;
+    async fn get(
+        &self,
+        filter: SubjectsFilter,
+        query: ListQuery,
+    ) -> Result<Page<Subject>, RepoError>;
+    async fn get_all_attendees(
+        &self,
+        id: Uuid,
+        query: ListQuery,
+    ) -> Result<Page<Attendee>, RepoError>;
+    /// the attendees of a subject other than `found`, by number.
+    async fn get_missing_attendees(
+        &self,
+        id: Uuid,
+        found: Vec<Uuid>,
+    ) -> Result<Vec<Attendee>, RepoError>;
     async fn update(&self, id: Uuid, update_subject: UpdateSubject) -> Result<Subject, RepoError>;
+    /// fails when the subject is at its capacity.
     async fn add_attendee(&self, id: Uuid, attendee_id: Uuid) -> Result<(), RepoError>;
+    /// replaces or merges into the roster of a subject all at once, failing
+    /// when it would exceed the capacity.
+    async fn update_roster(&self, id: Uuid, roster: UpdateRoster) -> Result<RosterDiff, RepoError>;
+    /// applies the roster of another subject, like [`Self::update_roster`].
+    async fn copy_roster(&self, id: Uuid, copy: CopyRoster) -> Result<RosterDiff, RepoError>;
     async fn remove_attendee(&self, id: Uuid, attendee_id: Uuid) -> Result<(), RepoError>;
     async fn delete_by_id(&self, id: Uuid) -> Result<(), RepoError>;
 }
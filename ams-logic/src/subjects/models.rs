@@ -1,5 +1,5 @@
 use chrono::{DateTime, FixedOffset};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
@@ -49,6 +49,10 @@ impl From<subject_dates::Model> for SubjectDate {
 pub struct Subject {
     pub id: Uuid,
     pub name: String,
+    /// the term the subject is taught in, subjects without one repeat every week.
+    pub term_id: Option<Uuid>,
+    /// the most attendees the subject can enroll, unlimited when omitted.
+    pub capacity: Option<i32>,
     pub instructor: Option<Instructor>,
     pub dates: Vec<SubjectDate>,
     pub create_at: DateTime<FixedOffset>,
@@ -61,6 +65,8 @@ impl From<(subjects::Model, Vec<SubjectDate>, Option<Instructor>)> for Subject {
             subjects::Model {
                 id,
                 name,
+                term_id,
+                capacity,
                 create_at,
                 updated_at,
                 ..
@@ -72,6 +78,8 @@ impl From<(subjects::Model, Vec<SubjectDate>, Option<Instructor>)> for Subject {
         Self {
             id,
             name,
+            term_id,
+            capacity,
             instructor,
             dates,
             create_at,
@@ -95,6 +103,18 @@ pub struct CreateSubjectDate {
 pub struct CreateSubject {
     #[schema(example = "intro to computer science")]
     pub name: String,
+    pub term_id: Option<Uuid>,
+    #[schema(example = 40)]
+    pub capacity: Option<i32>,
+}
+
+/// tells an omitted field, `None`, apart from a `null` one, `Some(None)`.
+fn present<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
 }
 
 #[derive(Deserialize, Serialize, Default, ToSchema)]
@@ -102,14 +122,60 @@ pub struct CreateSubject {
 pub struct UpdateSubject {
     #[schema(example = "updated intro to computer science")]
     pub name: Option<String>,
+    /// `null` takes the subject out of its term.
+    #[serde(default, deserialize_with = "present")]
+    #[schema(value_type = Option<Uuid>)]
+    pub term_id: Option<Option<Uuid>>,
+    /// `null` lifts the limit.
+    #[serde(default, deserialize_with = "present")]
+    #[schema(value_type = Option<i32>, example = 40)]
+    pub capacity: Option<Option<i32>>,
     #[serde(skip)]
     pub instructor_id: Option<Option<Uuid>>,
 }
 
+/// how a new roster is applied to the enrolled attendees.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum RosterMode {
+    /// the roster becomes exactly the given attendees.
+    #[default]
+    Replace,
+    /// the given attendees are enrolled along with the current ones.
+    Merge,
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateRoster {
+    pub attendee_ids: Vec<Uuid>,
+    #[serde(default)]
+    pub mode: RosterMode,
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CopyRoster {
+    /// the subject whose attendees are copied.
+    pub from_subject_id: Uuid,
+    #[serde(default)]
+    pub mode: RosterMode,
+}
+
+/// who a roster change enrolled and unenrolled.
+#[derive(Deserialize, Serialize, Debug, Default, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RosterDiff {
+    pub added: Vec<Uuid>,
+    pub removed: Vec<Uuid>,
+    /// how many attendees are enrolled afterwards.
+    pub enrolled: usize,
+}
+
 #[derive(Default)]
 pub struct SubjectsFilter {
     pub id: Option<Uuid>,
-    pub name: Option<String>,
     pub instructor_id: Option<Uuid>,
     pub attendee_id: Option<Uuid>,
+    pub term_id: Option<Uuid>,
 }
@@ -0,0 +1,67 @@
+use chrono::NaiveDate;
+use sea_orm::prelude::*;
+
+use crate::entity::{
+    class_sessions, sea_orm_active_enums::ClassSessionStatus, subjects, term_holidays, terms,
+};
+use crate::error::RepoError;
+
+/// the days of a term classes take place on.
+#[derive(Debug, Clone)]
+pub struct TermCalendar {
+    pub starts_on: NaiveDate,
+    pub ends_on: NaiveDate,
+    /// inclusive date ranges without classes.
+    pub holidays: Vec<(NaiveDate, NaiveDate)>,
+}
+
+impl TermCalendar {
+    /// whether classes take place on `date`.
+    pub fn is_class_day(&self, date: NaiveDate) -> bool {
+        self.starts_on <= date
+            && date <= self.ends_on
+            && !self
+                .holidays
+                .iter()
+                .any(|(starts_on, ends_on)| *starts_on <= date && date <= *ends_on)
+    }
+
+    /// whether a session that isn't cancelled takes place under the given
+    /// calendar. sessions generated before their day became a holiday or left
+    /// the term don't, unless an instructor explicitly moved them.
+    pub(crate) fn holds(calendar: Option<&Self>, session: &class_sessions::Model) -> bool {
+        session.status == ClassSessionStatus::Rescheduled
+            || calendar.is_none_or(|calendar| calendar.is_class_day(session.occurrence_date))
+    }
+
+    pub(crate) async fn of_term<C: ConnectionTrait>(
+        conn: &C,
+        term: &terms::Model,
+    ) -> Result<Self, RepoError> {
+        let holidays = term
+            .find_related(term_holidays::Entity)
+            .all(conn)
+            .await?
+            .into_iter()
+            .map(|holiday| (holiday.starts_on, holiday.ends_on))
+            .collect();
+
+        Ok(Self {
+            starts_on: term.starts_on,
+            ends_on: term.ends_on,
+            holidays,
+        })
+    }
+
+    /// the calendar of the subject's term, subjects without a term have none.
+    pub(crate) async fn of_subject<C: ConnectionTrait>(
+        conn: &C,
+        subject: &subjects::Model,
+    ) -> Result<Option<Self>, RepoError> {
+        let Some(term) = subject.find_related(terms::Entity).one(conn).await? else {
+            return Ok(None);
+        };
+
+        Ok(Some(Self::of_term(conn, &term).await?))
+    }
+}
@@ -0,0 +1,330 @@
+use std::sync::Arc;
+
+use chrono::{NaiveDate, Utc};
+use itertools::Itertools;
+use sea_orm::{
+    prelude::{async_trait::async_trait, *},
+    sea_query::IntoCondition,
+    Condition, QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+
+use super::*;
+
+use crate::audit_log::{self, AuditChange};
+use crate::class_sessions::ClassSession;
+use crate::entity::{
+    class_sessions,
+    sea_orm_active_enums::{ClassSessionCancelReason, ClassSessionStatus},
+    subjects,
+};
+use crate::error::MapDuplicateExt;
+use crate::pagination::Listable;
+
+pub struct TermsRepo(pub Arc<DatabaseConnection>);
+
+impl Listable for terms::Entity {
+    const SORTS: &'static [(&'static str, terms::Column)] = &[
+        ("name", terms::Column::Name),
+        ("startsOn", terms::Column::StartsOn),
+        ("createdAt", terms::Column::CreateAt),
+    ];
+    const DEFAULT_SORT: &'static str = "-startsOn";
+    const ID: terms::Column = terms::Column::Id;
+    const NAME: Option<terms::Column> = Some(terms::Column::Name);
+    const CREATED_AT: Option<terms::Column> = Some(terms::Column::CreateAt);
+}
+
+impl AsRef<DatabaseConnection> for TermsRepo {
+    fn as_ref(&self) -> &DatabaseConnection {
+        &self.0
+    }
+}
+
+fn check_range(starts_on: NaiveDate, ends_on: NaiveDate) -> Result<(), RepoError> {
+    if starts_on > ends_on {
+        return Err(RepoError::InvalidDateRange { starts_on, ends_on });
+    }
+    Ok(())
+}
+
+impl TermsRepo {
+    /// locks the term until the transaction ends.
+    async fn lock<C: ConnectionTrait>(conn: &C, id: Uuid) -> Result<terms::Model, RepoError> {
+        terms::Entity::find_by_id(id)
+            .lock_exclusive()
+            .one(conn)
+            .await?
+            .ok_or(RepoError::NotFound("terms".to_owned()))
+    }
+
+    /// the term along with its holidays.
+    async fn load<C: ConnectionTrait>(conn: &C, term: terms::Model) -> Result<Term, RepoError> {
+        let holidays = term
+            .find_related(term_holidays::Entity)
+            .order_by_asc(term_holidays::Column::StartsOn)
+            .all(conn)
+            .await?
+            .into_iter()
+            .map_into()
+            .collect_vec();
+
+        Ok((term, holidays).into())
+    }
+}
+
+/// cancels the upcoming sessions of the `subjects` that don't fall on a class
+/// day of their `calendar` and schedules the ones it cancelled before again once
+/// their day is a class day again, every day is one without a calendar.
+/// rescheduled sessions and the ones cancelled by an instructor are left as they
+/// are. every changed session is recorded in the audit log.
+pub(crate) async fn sync_sessions<C: ConnectionTrait>(
+    conn: &C,
+    subjects: impl IntoCondition,
+    calendar: Option<&TermCalendar>,
+) -> Result<(), RepoError> {
+    let (excluded, restored): (Vec<_>, Vec<_>) = class_sessions::Entity::find()
+        .inner_join(subjects::Entity)
+        .filter(subjects.into_condition())
+        .filter(
+            Condition::any()
+                .add(class_sessions::Column::Status.eq(ClassSessionStatus::Scheduled))
+                .add(class_sessions::Column::CancelReason.eq(ClassSessionCancelReason::Calendar)),
+        )
+        .filter(class_sessions::Column::StartsAt.gt(Utc::now()))
+        .all(conn)
+        .await?
+        .into_iter()
+        .filter(|session| {
+            let cancelled = session.status == ClassSessionStatus::Cancelled;
+            let class_day =
+                calendar.is_none_or(|calendar| calendar.is_class_day(session.occurrence_date));
+            cancelled == class_day
+        })
+        .partition(|session| session.status == ClassSessionStatus::Scheduled);
+
+    for (sessions, status, cancel_reason) in [
+        (
+            excluded,
+            ClassSessionStatus::Cancelled,
+            Some(ClassSessionCancelReason::Calendar),
+        ),
+        (restored, ClassSessionStatus::Scheduled, None),
+    ] {
+        if sessions.is_empty() {
+            continue;
+        }
+
+        let updated_at = Utc::now().into();
+        class_sessions::Entity::update_many()
+            .set(class_sessions::ActiveModel {
+                status: Set(status),
+                cancel_reason: Set(cancel_reason),
+                updated_at: Set(updated_at),
+                ..Default::default()
+            })
+            .filter(class_sessions::Column::Id.is_in(sessions.iter().map(|session| session.id)))
+            .exec(conn)
+            .await?;
+
+        for before in sessions {
+            let after = class_sessions::Model {
+                status,
+                cancel_reason,
+                updated_at,
+                ..before.clone()
+            };
+            audit_log::record(
+                conn,
+                Some(before.id),
+                AuditChange::Updated(&ClassSession::from(before), &ClassSession::from(after)),
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[async_trait]
+impl TermsRepoTrait for TermsRepo {
+    async fn create(
+        &self,
+        CreateTerm {
+            name,
+            starts_on,
+            ends_on,
+        }: CreateTerm,
+    ) -> Result<Term, RepoError> {
+        check_range(starts_on, ends_on)?;
+        let txn = self.as_ref().begin().await?;
+
+        let term = terms::ActiveModel {
+            name: Set(name),
+            starts_on: Set(starts_on),
+            ends_on: Set(ends_on),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await
+        .map_unique_violation("uk_term_name_must_be_unique", RepoError::DuplicateTerm)?;
+        let term: Term = (term, vec![]).into();
+        audit_log::record(&txn, Some(term.id), AuditChange::Created(&term)).await?;
+        txn.commit().await?;
+
+        Ok(term)
+    }
+
+    async fn get(&self, query: ListQuery) -> Result<Page<Term>, RepoError> {
+        let mut page = query.page(self.as_ref(), terms::Entity::find()).await?;
+        let terms = std::mem::take(&mut page.items);
+
+        let holidays = terms
+            .load_many(term_holidays::Entity, self.as_ref())
+            .await?
+            .into_iter()
+            .map(|holidays| holidays.into_iter().map_into().collect_vec())
+            .collect_vec();
+
+        Ok(page.with_items(terms.into_iter().zip(holidays).map_into().collect_vec()))
+    }
+
+    async fn get_by_id(&self, id: Uuid) -> Result<Term, RepoError> {
+        let term = terms::Entity::find_by_id(id)
+            .one(self.as_ref())
+            .await?
+            .ok_or(RepoError::NotFound("terms".to_owned()))?;
+
+        Self::load(self.as_ref(), term).await
+    }
+
+    async fn update(
+        &self,
+        id: Uuid,
+        UpdateTerm {
+            name,
+            starts_on,
+            ends_on,
+        }: UpdateTerm,
+    ) -> Result<Term, RepoError> {
+        let txn = self.as_ref().begin().await?;
+
+        let before = Self::lock(&txn, id).await?;
+        check_range(
+            starts_on.unwrap_or(before.starts_on),
+            ends_on.unwrap_or(before.ends_on),
+        )?;
+
+        let mut term: terms::ActiveModel = before.clone().into();
+
+        if let Some(name) = name {
+            term.name = Set(name);
+        }
+        if let Some(starts_on) = starts_on {
+            term.starts_on = Set(starts_on);
+        }
+        if let Some(ends_on) = ends_on {
+            term.ends_on = Set(ends_on);
+        }
+        term.updated_at = Set(Utc::now().into());
+
+        let term = term
+            .update(&txn)
+            .await
+            .map_unique_violation("uk_term_name_must_be_unique", RepoError::DuplicateTerm)?;
+
+        let calendar = TermCalendar::of_term(&txn, &term).await?;
+        sync_sessions(&txn, subjects::Column::TermId.eq(term.id), Some(&calendar)).await?;
+
+        let before = Self::load(&txn, before).await?;
+        let term = Self::load(&txn, term).await?;
+        audit_log::record(&txn, Some(id), AuditChange::Updated(&before, &term)).await?;
+        txn.commit().await?;
+
+        Ok(term)
+    }
+
+    async fn delete_by_id(&self, id: Uuid) -> Result<(), RepoError> {
+        let txn = self.as_ref().begin().await?;
+
+        let term = terms::Entity::find_by_id(id)
+            .lock_exclusive()
+            .one(&txn)
+            .await?;
+        if let Some(term) = term {
+            // the subjects are left without a calendar.
+            sync_sessions(&txn, subjects::Column::TermId.eq(id), None).await?;
+            let term = Self::load(&txn, term).await?;
+            terms::Entity::delete_by_id(id).exec(&txn).await?;
+            audit_log::record(&txn, Some(id), AuditChange::Deleted(&term)).await?;
+        }
+        txn.commit().await?;
+
+        Ok(())
+    }
+
+    async fn add_holiday(
+        &self,
+        term_id: Uuid,
+        CreateTermHoliday {
+            name,
+            starts_on,
+            ends_on,
+        }: CreateTermHoliday,
+    ) -> Result<TermHoliday, RepoError> {
+        check_range(starts_on, ends_on)?;
+
+        let txn = self.as_ref().begin().await?;
+
+        let term = Self::lock(&txn, term_id).await?;
+        if starts_on < term.starts_on || ends_on > term.ends_on {
+            return Err(RepoError::InvalidDateRange { starts_on, ends_on });
+        }
+
+        let holiday: TermHoliday = term_holidays::ActiveModel {
+            term_id: Set(term_id),
+            name: Set(name),
+            starts_on: Set(starts_on),
+            ends_on: Set(ends_on),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?
+        .into();
+
+        let calendar = TermCalendar::of_term(&txn, &term).await?;
+        sync_sessions(&txn, subjects::Column::TermId.eq(term.id), Some(&calendar)).await?;
+        audit_log::record(&txn, Some(holiday.id), AuditChange::Created(&holiday)).await?;
+        txn.commit().await?;
+
+        Ok(holiday)
+    }
+
+    async fn remove_holiday(&self, term_id: Uuid, holiday_id: Uuid) -> Result<(), RepoError> {
+        let txn = self.as_ref().begin().await?;
+
+        let term = Self::lock(&txn, term_id).await?;
+        let holiday = term_holidays::Entity::find()
+            .filter(
+                term_holidays::Column::Id
+                    .eq(holiday_id)
+                    .and(term_holidays::Column::TermId.eq(term_id)),
+            )
+            .one(&txn)
+            .await?
+            .ok_or(RepoError::NotFound("term_holidays".to_owned()))?;
+
+        holiday.clone().delete(&txn).await?;
+
+        let calendar = TermCalendar::of_term(&txn, &term).await?;
+        sync_sessions(&txn, subjects::Column::TermId.eq(term.id), Some(&calendar)).await?;
+        audit_log::record(
+            &txn,
+            Some(holiday_id),
+            AuditChange::Deleted(&TermHoliday::from(holiday)),
+        )
+        .await?;
+        txn.commit().await?;
+
+        Ok(())
+    }
+}
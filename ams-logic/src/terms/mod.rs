@@ -0,0 +1,32 @@
+mod calendar;
+mod impls;
+mod models;
+
+pub use calendar::*;
+pub use impls::*;
+pub use models::*;
+
+use sea_orm::prelude::async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::{
+    error::RepoError,
+    pagination::{ListQuery, Page},
+};
+
+use crate::entity::{term_holidays, terms};
+
+#[async_trait]
+pub trait TermsRepoTrait {
+    async fn create(&self, term: CreateTerm) -> Result<Term, RepoError>;
+    async fn get(&self, query: ListQuery) -> Result<Page<Term>, RepoError>;
+    async fn get_by_id(&self, id: Uuid) -> Result<Term, RepoError>;
+    async fn update(&self, id: Uuid, update_term: UpdateTerm) -> Result<Term, RepoError>;
+    async fn delete_by_id(&self, id: Uuid) -> Result<(), RepoError>;
+    async fn add_holiday(
+        &self,
+        term_id: Uuid,
+        holiday: CreateTermHoliday,
+    ) -> Result<TermHoliday, RepoError>;
+    async fn remove_holiday(&self, term_id: Uuid, holiday_id: Uuid) -> Result<(), RepoError>;
+}
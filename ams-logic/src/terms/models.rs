@@ -0,0 +1,115 @@
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{term_holidays, terms};
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TermHoliday {
+    pub id: Uuid,
+    pub name: String,
+    #[schema(value_type = String, format = Date)]
+    pub starts_on: NaiveDate,
+    #[schema(value_type = String, format = Date)]
+    pub ends_on: NaiveDate,
+    pub create_at: DateTime<FixedOffset>,
+    pub updated_at: DateTime<FixedOffset>,
+}
+
+impl From<term_holidays::Model> for TermHoliday {
+    fn from(
+        term_holidays::Model {
+            id,
+            name,
+            starts_on,
+            ends_on,
+            create_at,
+            updated_at,
+            ..
+        }: term_holidays::Model,
+    ) -> Self {
+        Self {
+            id,
+            name,
+            starts_on,
+            ends_on,
+            create_at,
+            updated_at,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Term {
+    pub id: Uuid,
+    pub name: String,
+    #[schema(value_type = String, format = Date)]
+    pub starts_on: NaiveDate,
+    #[schema(value_type = String, format = Date)]
+    pub ends_on: NaiveDate,
+    pub holidays: Vec<TermHoliday>,
+    pub create_at: DateTime<FixedOffset>,
+    pub updated_at: DateTime<FixedOffset>,
+}
+
+impl From<(terms::Model, Vec<TermHoliday>)> for Term {
+    fn from(
+        (
+            terms::Model {
+                id,
+                name,
+                starts_on,
+                ends_on,
+                create_at,
+                updated_at,
+            },
+            holidays,
+        ): (terms::Model, Vec<TermHoliday>),
+    ) -> Self {
+        Self {
+            id,
+            name,
+            starts_on,
+            ends_on,
+            holidays,
+            create_at,
+            updated_at,
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateTerm {
+    #[schema(example = "fall 2023")]
+    pub name: String,
+    #[schema(value_type = String, format = Date)]
+    pub starts_on: NaiveDate,
+    #[schema(value_type = String, format = Date)]
+    pub ends_on: NaiveDate,
+}
+
+#[derive(Deserialize, Default, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateTerm {
+    pub name: Option<String>,
+    #[schema(value_type = Option<String>, format = Date)]
+    pub starts_on: Option<NaiveDate>,
+    #[schema(value_type = Option<String>, format = Date)]
+    pub ends_on: Option<NaiveDate>,
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateTermHoliday {
+    #[schema(example = "mid term break")]
+    pub name: String,
+    #[schema(value_type = String, format = Date)]
+    pub starts_on: NaiveDate,
+    /// the last day of the holiday, inclusive.
+    #[schema(value_type = String, format = Date)]
+    pub ends_on: NaiveDate,
+}
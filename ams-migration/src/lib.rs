@@ -0,0 +1,42 @@
+pub use sea_orm_migration::prelude::*;
+
+mod m0001_initial_schema;
+mod m0002_sessions;
+mod m0003_class_sessions;
+mod m0004_terms;
+mod m0005_vector_embeddings;
+mod m0006_face_samples;
+mod m0007_match_settings;
+mod m0008_classifiers;
+mod m0009_settings;
+mod m0010_subject_capacity;
+mod m0011_attendance_status;
+mod m0012_audit_log;
+mod m0013_class_session_cancel_reason;
+mod m0014_face_sample_indexes;
+mod m0015_system_audit_entries;
+
+pub struct Migrator;
+
+#[async_trait::async_trait]
+impl MigratorTrait for Migrator {
+    fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+        vec![
+            Box::new(m0001_initial_schema::Migration),
+            Box::new(m0002_sessions::Migration),
+            Box::new(m0003_class_sessions::Migration),
+            Box::new(m0004_terms::Migration),
+            Box::new(m0005_vector_embeddings::Migration),
+            Box::new(m0006_face_samples::Migration),
+            Box::new(m0007_match_settings::Migration),
+            Box::new(m0008_classifiers::Migration),
+            Box::new(m0009_settings::Migration),
+            Box::new(m0010_subject_capacity::Migration),
+            Box::new(m0011_attendance_status::Migration),
+            Box::new(m0012_audit_log::Migration),
+            Box::new(m0013_class_session_cancel_reason::Migration),
+            Box::new(m0014_face_sample_indexes::Migration),
+            Box::new(m0015_system_audit_entries::Migration),
+        ]
+    }
+}
@@ -0,0 +1,26 @@
+use sea_orm_migration::prelude::*;
+
+/// the schema of the former `ams-db-schema/init.sql`, without the seeded admins.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(include_str!("sql/0001_initial_schema.up.sql"))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(include_str!("sql/0001_initial_schema.down.sql"))
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,26 @@
+use sea_orm_migration::prelude::*;
+
+/// the dated class sessions of the subject dates, attendances are taken in one.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(include_str!("sql/0003_class_sessions.up.sql"))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(include_str!("sql/0003_class_sessions.down.sql"))
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,26 @@
+use sea_orm_migration::prelude::*;
+
+/// academic terms and their holidays, which confine the sessions of subjects.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(include_str!("sql/0004_terms.up.sql"))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(include_str!("sql/0004_terms.down.sql"))
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,26 @@
+use sea_orm_migration::prelude::*;
+
+/// attendee embeddings as pgvector vectors searched through an index.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(include_str!("sql/0005_vector_embeddings.up.sql"))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(include_str!("sql/0005_vector_embeddings.down.sql"))
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,26 @@
+use sea_orm_migration::prelude::*;
+
+/// the match metric and threshold, with per-subject overrides.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(include_str!("sql/0007_match_settings.up.sql"))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(include_str!("sql/0007_match_settings.down.sql"))
+            .await?;
+
+        Ok(())
+    }
+}
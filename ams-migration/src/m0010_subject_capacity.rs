@@ -0,0 +1,26 @@
+use sea_orm_migration::prelude::*;
+
+/// limits how many attendees a subject can enroll.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(include_str!("sql/0010_subject_capacity.up.sql"))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(include_str!("sql/0010_subject_capacity.down.sql"))
+            .await?;
+
+        Ok(())
+    }
+}
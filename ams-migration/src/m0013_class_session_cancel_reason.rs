@@ -0,0 +1,28 @@
+use sea_orm_migration::prelude::*;
+
+/// why class sessions were cancelled.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(include_str!("sql/0013_class_session_cancel_reason.up.sql"))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(include_str!(
+                "sql/0013_class_session_cancel_reason.down.sql"
+            ))
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,26 @@
+use sea_orm_migration::prelude::*;
+
+/// the indexes of face samples for the cosine and dot metrics.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(include_str!("sql/0014_face_sample_indexes.up.sql"))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(include_str!("sql/0014_face_sample_indexes.down.sql"))
+            .await?;
+
+        Ok(())
+    }
+}
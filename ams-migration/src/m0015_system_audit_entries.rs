@@ -0,0 +1,26 @@
+use sea_orm_migration::prelude::*;
+
+/// audit entries for the changes the server makes on its own.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(include_str!("sql/0015_system_audit_entries.up.sql"))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(include_str!("sql/0015_system_audit_entries.down.sql"))
+            .await?;
+
+        Ok(())
+    }
+}
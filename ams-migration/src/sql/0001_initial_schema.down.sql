@@ -0,0 +1,9 @@
+-- pgcrypto is kept, other schemas in the database may use it.
+DROP TABLE IF EXISTS attendances;
+DROP FUNCTION IF EXISTS is_current_time_within_subject_date(UUID);
+DROP TABLE IF EXISTS attendees_subjects;
+DROP TABLE IF EXISTS subject_dates;
+DROP TABLE IF EXISTS subjects;
+DROP TABLE IF EXISTS instructors;
+DROP TABLE IF EXISTS attendees;
+DROP TABLE IF EXISTS admins;
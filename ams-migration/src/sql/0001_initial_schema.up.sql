@@ -1,4 +1,6 @@
-SET TIMEZONE TO 'Africa/Cairo';
+-- the schema of the former `ams-db-schema/init.sql`. every statement is
+-- idempotent so databases created from it pass through unchanged, the later
+-- migrations upgrade them where the schema has changed since.
 CREATE EXTENSION IF NOT EXISTS "pgcrypto";
 
 CREATE TABLE IF NOT EXISTS admins (
@@ -82,8 +84,3 @@ CREATE TABLE IF NOT EXISTS attendances (
     create_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
     CONSTRAINT ck_current_time_within_subject_date CHECK (is_current_time_within_subject_date(subject_id))
 );
-
-INSERT INTO admins (name, email, password)
-VALUES ('Mina Saad', 'mina@saad.com', '474747'),
-    ('Mina Emil', 'mina@emil.com', '393939'),
-    ('Mina Girgs', 'mina@girgs.com', '717171');
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS sessions;
+DROP TYPE IF EXISTS user_kind;
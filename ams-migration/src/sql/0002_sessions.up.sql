@@ -0,0 +1,18 @@
+DO $$ BEGIN
+    CREATE TYPE user_kind AS ENUM ('admin', 'instructor', 'attendee');
+EXCEPTION WHEN duplicate_object THEN NULL;
+END $$;
+
+-- the refresh tokens handed out at login, rotated on every refresh.
+CREATE TABLE IF NOT EXISTS sessions (
+    id UUID NOT NULL PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_kind user_kind NOT NULL,
+    user_id UUID NOT NULL,
+    refresh_token_hash VARCHAR NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    revoked_at TIMESTAMPTZ,
+    create_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS ix_sessions_user ON sessions (user_kind, user_id);
@@ -0,0 +1,37 @@
+-- attendances are checked against the subject dates again, except the ones
+-- already taken.
+ALTER TABLE attendances DROP CONSTRAINT IF EXISTS uk_attendance_per_occurrence;
+
+DO $$ BEGIN
+    INSERT INTO attendances (id, subject_id, attendee_id, create_at, class_session_id)
+        SELECT id, subject_id, attendee_id, create_at, class_session_id
+        FROM legacy_duplicate_attendances;
+    DROP TABLE legacy_duplicate_attendances;
+EXCEPTION WHEN undefined_table THEN NULL;
+END $$;
+
+ALTER TABLE attendances DROP COLUMN IF EXISTS class_session_id;
+DROP TABLE IF EXISTS class_sessions;
+DROP TYPE IF EXISTS class_session_status;
+
+CREATE OR REPLACE FUNCTION is_current_time_within_subject_date(in_subject_id UUID)
+    RETURNS BOOLEAN AS $$
+    DECLARE
+        current_day_of_week INTEGER := EXTRACT(ISODOW FROM CURRENT_TIMESTAMP);
+        current_time TIME := CURRENT_TIME;
+    BEGIN
+        RETURN EXISTS (
+            SELECT 1 FROM subject_dates sd
+            WHERE sd.subject_id = in_subject_id
+            AND current_day_of_week = sd.day_of_week
+            AND current_time >= sd.start_time
+            AND current_time <= sd.end_time
+        );
+    END;
+$$ LANGUAGE plpgsql;
+
+DO $$ BEGIN
+    ALTER TABLE attendances ADD CONSTRAINT ck_current_time_within_subject_date
+        CHECK (is_current_time_within_subject_date(subject_id)) NOT VALID;
+EXCEPTION WHEN duplicate_object THEN NULL;
+END $$;
@@ -0,0 +1,121 @@
+DO $$ BEGIN
+    CREATE TYPE class_session_status AS ENUM ('scheduled', 'cancelled', 'rescheduled');
+EXCEPTION WHEN duplicate_object THEN NULL;
+END $$;
+
+-- dated occurrences of the weekly subject dates, `occurrence_date` is the
+-- local date the subject date falls on and stays the same after a reschedule.
+CREATE TABLE IF NOT EXISTS class_sessions (
+    id UUID NOT NULL PRIMARY KEY DEFAULT gen_random_uuid(),
+    subject_id UUID NOT NULL REFERENCES subjects(id) ON DELETE CASCADE,
+    subject_date_id UUID REFERENCES subject_dates(id) ON DELETE SET NULL,
+    occurrence_date DATE NOT NULL,
+    starts_at TIMESTAMPTZ NOT NULL,
+    ends_at TIMESTAMPTZ NOT NULL,
+    status class_session_status NOT NULL DEFAULT 'scheduled',
+    note VARCHAR(256),
+    create_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT ck_class_session_start_end CHECK (starts_at < ends_at),
+    CONSTRAINT uk_class_session_per_occurrence UNIQUE (subject_date_id, occurrence_date)
+);
+
+CREATE INDEX IF NOT EXISTS ix_class_sessions_subject ON class_sessions (subject_id, starts_at);
+
+-- attendances were checked against the subject dates when they were taken and
+-- had no session. the check is dropped and every attendance is put in the
+-- session of the subject date it was taken in, in the time zone of the database
+-- like the check did. nothing is removed: the attendances taken outside every
+-- subject date get a session of their own day without a subject date, and the
+-- duplicates the check allowed are moved to `legacy_duplicate_attendances`.
+ALTER TABLE attendances DROP CONSTRAINT IF EXISTS ck_current_time_within_subject_date;
+DROP FUNCTION IF EXISTS is_current_time_within_subject_date(UUID);
+
+ALTER TABLE attendances
+    ADD COLUMN IF NOT EXISTS class_session_id UUID REFERENCES class_sessions(id) ON DELETE CASCADE;
+
+INSERT INTO class_sessions (subject_id, subject_date_id, occurrence_date, starts_at, ends_at)
+SELECT DISTINCT
+    subject_dates.subject_id,
+    subject_dates.id,
+    taken.local_date,
+    (taken.local_date + subject_dates.start_time) AT TIME ZONE current_setting('TimeZone'),
+    (taken.local_date + subject_dates.end_time) AT TIME ZONE current_setting('TimeZone')
+FROM attendances
+CROSS JOIN LATERAL (
+    SELECT
+        (attendances.create_at AT TIME ZONE current_setting('TimeZone'))::DATE AS local_date,
+        (attendances.create_at AT TIME ZONE current_setting('TimeZone'))::TIME AS local_time
+) AS taken
+JOIN subject_dates ON subject_dates.subject_id = attendances.subject_id
+    AND subject_dates.day_of_week = EXTRACT(ISODOW FROM taken.local_date)
+    AND taken.local_time BETWEEN subject_dates.start_time AND subject_dates.end_time
+WHERE attendances.class_session_id IS NULL
+ON CONFLICT (subject_date_id, occurrence_date) DO NOTHING;
+
+UPDATE attendances SET class_session_id = class_sessions.id
+FROM class_sessions
+JOIN subject_dates ON subject_dates.id = class_sessions.subject_date_id
+WHERE attendances.class_session_id IS NULL
+    AND class_sessions.subject_id = attendances.subject_id
+    AND class_sessions.occurrence_date = (attendances.create_at AT TIME ZONE current_setting('TimeZone'))::DATE
+    AND (attendances.create_at AT TIME ZONE current_setting('TimeZone'))::TIME
+        BETWEEN subject_dates.start_time AND subject_dates.end_time;
+
+-- the check may have been dropped or the subject dates changed since.
+INSERT INTO class_sessions (subject_id, occurrence_date, starts_at, ends_at, note)
+SELECT DISTINCT
+    attendances.subject_id,
+    taken.local_date,
+    taken.local_date::TIMESTAMP AT TIME ZONE current_setting('TimeZone'),
+    (taken.local_date + 1)::TIMESTAMP AT TIME ZONE current_setting('TimeZone'),
+    'attendances taken outside the subject dates'
+FROM attendances
+CROSS JOIN LATERAL (
+    SELECT (attendances.create_at AT TIME ZONE current_setting('TimeZone'))::DATE AS local_date
+) AS taken
+WHERE attendances.class_session_id IS NULL;
+
+UPDATE attendances SET class_session_id = class_sessions.id
+FROM class_sessions
+WHERE attendances.class_session_id IS NULL
+    AND class_sessions.subject_date_id IS NULL
+    AND class_sessions.subject_id = attendances.subject_id
+    AND class_sessions.occurrence_date = (attendances.create_at AT TIME ZONE current_setting('TimeZone'))::DATE;
+
+ALTER TABLE attendances ALTER COLUMN class_session_id SET NOT NULL;
+
+DO $$
+DECLARE
+    detached BIGINT := (SELECT count(*) FROM class_sessions WHERE subject_date_id IS NULL);
+    duplicates BIGINT;
+BEGIN
+    IF detached > 0 THEN
+        RAISE NOTICE '% class sessions without a subject date were added for the attendances '
+            'taken outside the subject dates', detached;
+    END IF;
+
+    CREATE TEMPORARY TABLE duplicate_attendances ON COMMIT DROP AS
+    SELECT id FROM (
+        SELECT id, row_number() OVER (
+            PARTITION BY attendee_id, class_session_id ORDER BY create_at, id
+        ) AS taken
+        FROM attendances
+    ) AS numbered
+    WHERE taken > 1;
+
+    duplicates := (SELECT count(*) FROM duplicate_attendances);
+    IF duplicates > 0 THEN
+        CREATE TABLE IF NOT EXISTS legacy_duplicate_attendances (LIKE attendances);
+        INSERT INTO legacy_duplicate_attendances
+            SELECT * FROM attendances WHERE id IN (SELECT id FROM duplicate_attendances);
+        DELETE FROM attendances WHERE id IN (SELECT id FROM duplicate_attendances);
+        RAISE NOTICE '% duplicate attendances were moved to legacy_duplicate_attendances', duplicates;
+    END IF;
+END $$;
+
+DO $$ BEGIN
+    ALTER TABLE attendances
+        ADD CONSTRAINT uk_attendance_per_occurrence UNIQUE (attendee_id, class_session_id);
+EXCEPTION WHEN duplicate_table OR duplicate_object THEN NULL;
+END $$;
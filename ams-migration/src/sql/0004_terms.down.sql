@@ -0,0 +1,3 @@
+ALTER TABLE subjects DROP COLUMN IF EXISTS term_id;
+DROP TABLE IF EXISTS term_holidays;
+DROP TABLE IF EXISTS terms;
@@ -0,0 +1,24 @@
+CREATE TABLE IF NOT EXISTS terms (
+    id UUID NOT NULL PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(32) NOT NULL CONSTRAINT uk_term_name_must_be_unique UNIQUE,
+    starts_on DATE NOT NULL,
+    ends_on DATE NOT NULL,
+    create_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT ck_term_start_end CHECK (starts_on <= ends_on)
+);
+
+-- days within a term without classes, both ends inclusive.
+CREATE TABLE IF NOT EXISTS term_holidays (
+    id UUID NOT NULL PRIMARY KEY DEFAULT gen_random_uuid(),
+    term_id UUID NOT NULL REFERENCES terms(id) ON DELETE CASCADE,
+    name VARCHAR(64) NOT NULL,
+    starts_on DATE NOT NULL,
+    ends_on DATE NOT NULL,
+    create_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT ck_term_holiday_start_end CHECK (starts_on <= ends_on)
+);
+
+-- the term a subject is taught in, subjects without one follow no calendar.
+ALTER TABLE subjects ADD COLUMN IF NOT EXISTS term_id UUID REFERENCES terms(id) ON DELETE SET NULL;
@@ -0,0 +1,14 @@
+-- the extension is kept, other schemas in the database may use it.
+DROP INDEX IF EXISTS ix_attendees_embedding_dot;
+DROP INDEX IF EXISTS ix_attendees_embedding_cosine;
+DROP INDEX IF EXISTS ix_attendees_embedding;
+
+ALTER TABLE attendees ALTER COLUMN embedding TYPE DOUBLE PRECISION [] USING embedding::REAL[];
+
+DO $$ BEGIN
+    UPDATE attendees SET embedding = legacy_attendee_embeddings.embedding
+    FROM legacy_attendee_embeddings
+    WHERE attendees.id = legacy_attendee_embeddings.attendee_id AND attendees.embedding IS NULL;
+    DROP TABLE legacy_attendee_embeddings;
+EXCEPTION WHEN undefined_table THEN NULL;
+END $$;
@@ -0,0 +1,35 @@
+CREATE EXTENSION IF NOT EXISTS "vector";
+
+-- embeddings were double precision arrays. the ones that don't have 512 values
+-- come from another model, they're moved to `legacy_attendee_embeddings` and
+-- computed again once the attendee's image is uploaded as a face sample.
+DO $$
+DECLARE
+    moved BIGINT;
+BEGIN
+    IF (SELECT data_type FROM information_schema.columns
+        WHERE table_name = 'attendees' AND column_name = 'embedding') = 'ARRAY' THEN
+        CREATE TABLE IF NOT EXISTS legacy_attendee_embeddings (
+            attendee_id UUID NOT NULL PRIMARY KEY REFERENCES attendees(id) ON DELETE CASCADE,
+            embedding DOUBLE PRECISION [] NOT NULL
+        );
+        INSERT INTO legacy_attendee_embeddings (attendee_id, embedding)
+            SELECT id, embedding FROM attendees
+            WHERE embedding IS NOT NULL AND array_length(embedding, 1) IS DISTINCT FROM 512;
+        GET DIAGNOSTICS moved = ROW_COUNT;
+
+        ALTER TABLE attendees ALTER COLUMN embedding TYPE VECTOR(512) USING
+            CASE WHEN array_length(embedding, 1) = 512 THEN embedding::REAL[]::VECTOR(512) END;
+
+        IF moved > 0 THEN
+            RAISE NOTICE '% attendee embeddings without 512 values were moved to '
+                'legacy_attendee_embeddings, upload the attendees'' images again', moved;
+        ELSE
+            DROP TABLE legacy_attendee_embeddings;
+        END IF;
+    END IF;
+END $$;
+
+CREATE INDEX IF NOT EXISTS ix_attendees_embedding ON attendees USING hnsw (embedding vector_l2_ops);
+CREATE INDEX IF NOT EXISTS ix_attendees_embedding_cosine ON attendees USING hnsw (embedding vector_cosine_ops);
+CREATE INDEX IF NOT EXISTS ix_attendees_embedding_dot ON attendees USING hnsw (embedding vector_ip_ops);
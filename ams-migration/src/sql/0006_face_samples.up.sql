@@ -0,0 +1,13 @@
+-- the faces enrolled for an attendee, photos are matched against their embeddings.
+CREATE TABLE IF NOT EXISTS face_samples (
+    id UUID NOT NULL PRIMARY KEY DEFAULT gen_random_uuid(),
+    attendee_id UUID NOT NULL REFERENCES attendees(id) ON DELETE CASCADE,
+    embedding VECTOR(512) NOT NULL,
+    image VARCHAR(256) NOT NULL,
+    quality DOUBLE PRECISION NOT NULL,
+    captured_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS ix_face_samples_attendee ON face_samples(attendee_id);
+CREATE INDEX IF NOT EXISTS ix_face_samples_embedding ON face_samples USING hnsw (embedding vector_l2_ops);
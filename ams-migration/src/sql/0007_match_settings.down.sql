@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS match_settings;
+DROP TYPE IF EXISTS match_metric;
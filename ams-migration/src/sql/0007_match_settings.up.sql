@@ -0,0 +1,19 @@
+DO $$ BEGIN
+    CREATE TYPE match_metric AS ENUM ('l2', 'cosine', 'dot');
+EXCEPTION WHEN duplicate_object THEN NULL;
+END $$;
+
+-- how faces are matched, the row without a subject is the deployment default
+-- and the others override it for their subject.
+CREATE TABLE IF NOT EXISTS match_settings (
+    id UUID NOT NULL PRIMARY KEY DEFAULT gen_random_uuid(),
+    subject_id UUID CONSTRAINT uk_match_settings_per_subject UNIQUE REFERENCES subjects(id) ON DELETE CASCADE,
+    metric match_metric NOT NULL,
+    threshold DOUBLE PRECISION NOT NULL,
+    create_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_default_match_settings ON match_settings ((subject_id IS NULL)) WHERE subject_id IS NULL;
+
+INSERT INTO match_settings (metric, threshold) VALUES ('l2', 0.6) ON CONFLICT DO NOTHING;
@@ -0,0 +1,16 @@
+-- every uploaded classifier, the active one is the one the face recognition
+-- service classifies with.
+CREATE TABLE IF NOT EXISTS classifiers (
+    id UUID NOT NULL PRIMARY KEY DEFAULT gen_random_uuid(),
+    version SERIAL NOT NULL CONSTRAINT uk_classifier_version_must_be_unique UNIQUE,
+    checksum VARCHAR(64) NOT NULL,
+    size BIGINT NOT NULL,
+    path VARCHAR(256) NOT NULL,
+    uploaded_by UUID REFERENCES admins(id) ON DELETE SET NULL,
+    notes TEXT,
+    active BOOLEAN NOT NULL DEFAULT FALSE,
+    activated_at TIMESTAMPTZ,
+    create_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_single_active_classifier ON classifiers (active) WHERE active;
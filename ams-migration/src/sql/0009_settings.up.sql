@@ -0,0 +1,7 @@
+-- runtime settings by their camelCase name, the ones never changed keep the
+-- defaults of the api.
+CREATE TABLE IF NOT EXISTS settings (
+    key VARCHAR(64) NOT NULL PRIMARY KEY,
+    value JSONB NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
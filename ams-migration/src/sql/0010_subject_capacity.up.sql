@@ -0,0 +1,3 @@
+-- the most attendees a subject can enroll, unlimited when null.
+ALTER TABLE subjects ADD COLUMN IF NOT EXISTS capacity INTEGER
+    CONSTRAINT ck_subject_capacity_must_be_positive CHECK (capacity > 0);
@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS attendance_audit;
+ALTER TABLE attendances DROP COLUMN IF EXISTS status;
+DROP TYPE IF EXISTS attendance_status;
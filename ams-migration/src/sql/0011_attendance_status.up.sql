@@ -0,0 +1,29 @@
+DO $$ BEGIN
+    CREATE TYPE attendance_status AS ENUM ('present', 'late', 'excused', 'absent');
+EXCEPTION WHEN duplicate_object THEN NULL;
+END $$;
+
+-- attendances taken before statuses existed were all present.
+ALTER TABLE attendances
+    ADD COLUMN IF NOT EXISTS status attendance_status NOT NULL DEFAULT 'present';
+
+-- every change of an attendance, who made it and why. the attendance and its
+-- session aren't referenced so the history outlives them.
+CREATE TABLE IF NOT EXISTS attendance_audit (
+    id UUID NOT NULL PRIMARY KEY DEFAULT gen_random_uuid(),
+    attendance_id UUID NOT NULL,
+    subject_id UUID NOT NULL REFERENCES subjects(id) ON DELETE CASCADE,
+    attendee_id UUID NOT NULL REFERENCES attendees(id) ON DELETE CASCADE,
+    class_session_id UUID NOT NULL,
+    changed_by_kind user_kind NOT NULL,
+    changed_by UUID NOT NULL,
+    -- null when the attendance was created
+    old_status attendance_status,
+    -- null when the attendance was deleted
+    new_status attendance_status,
+    reason VARCHAR(256),
+    create_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS ix_attendance_audit_subject ON attendance_audit (subject_id, create_at);
+CREATE INDEX IF NOT EXISTS ix_attendance_audit_attendance ON attendance_audit (attendance_id);
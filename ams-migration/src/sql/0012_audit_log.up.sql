@@ -0,0 +1,32 @@
+-- every create, update and delete made through the api, appended in the
+-- transaction that makes the change. rows are never changed or removed, the
+-- actor and the entity aren't referenced so they outlive both.
+CREATE TABLE IF NOT EXISTS audit_log (
+    id UUID NOT NULL PRIMARY KEY DEFAULT gen_random_uuid(),
+    actor_kind user_kind NOT NULL,
+    actor_id UUID NOT NULL,
+    method VARCHAR(8) NOT NULL,
+    -- the documented route, e.g. `/subjects/{subject_id}`
+    route VARCHAR(256) NOT NULL,
+    path TEXT NOT NULL,
+    entity_id UUID,
+    -- only the fields that changed on updates
+    before JSONB,
+    after JSONB,
+    create_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS ix_audit_log_create_at ON audit_log (create_at);
+CREATE INDEX IF NOT EXISTS ix_audit_log_actor ON audit_log (actor_id, create_at);
+CREATE INDEX IF NOT EXISTS ix_audit_log_entity ON audit_log (entity_id, create_at);
+
+CREATE OR REPLACE FUNCTION audit_log_append_only() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS tr_audit_log_append_only ON audit_log;
+CREATE TRIGGER tr_audit_log_append_only
+    BEFORE UPDATE OR DELETE ON audit_log
+    FOR EACH ROW EXECUTE FUNCTION audit_log_append_only();
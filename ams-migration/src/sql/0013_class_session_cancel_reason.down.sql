@@ -0,0 +1,2 @@
+ALTER TABLE class_sessions DROP COLUMN IF EXISTS cancel_reason;
+DROP TYPE IF EXISTS class_session_cancel_reason;
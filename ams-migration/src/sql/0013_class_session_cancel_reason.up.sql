@@ -0,0 +1,13 @@
+DO $$ BEGIN
+    CREATE TYPE class_session_cancel_reason AS ENUM ('instructor', 'calendar');
+EXCEPTION WHEN duplicate_object THEN NULL;
+END $$;
+
+-- why a cancelled session was cancelled, sessions cancelled by the term
+-- calendar are scheduled again once their day is a class day again.
+ALTER TABLE class_sessions
+    ADD COLUMN IF NOT EXISTS cancel_reason class_session_cancel_reason;
+
+-- sessions cancelled before reasons existed are kept cancelled.
+UPDATE class_sessions SET cancel_reason = 'instructor'
+    WHERE status = 'cancelled' AND cancel_reason IS NULL;
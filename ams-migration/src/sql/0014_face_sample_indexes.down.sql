@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS ix_face_samples_embedding_dot;
+DROP INDEX IF EXISTS ix_face_samples_embedding_cosine;
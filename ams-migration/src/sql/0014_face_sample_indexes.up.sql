@@ -0,0 +1,3 @@
+-- every metric searches the nearest face samples through an index, not only l2.
+CREATE INDEX IF NOT EXISTS ix_face_samples_embedding_cosine ON face_samples USING hnsw (embedding vector_cosine_ops);
+CREATE INDEX IF NOT EXISTS ix_face_samples_embedding_dot ON face_samples USING hnsw (embedding vector_ip_ops);
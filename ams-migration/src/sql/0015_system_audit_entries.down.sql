@@ -0,0 +1,19 @@
+-- entries without a user can't stay in the log, they're moved to
+-- `legacy_system_audit_log` and put back when migrating up again.
+DO $$ BEGIN
+    IF EXISTS (SELECT 1 FROM audit_log WHERE actor_id IS NULL) THEN
+        CREATE TABLE IF NOT EXISTS legacy_system_audit_log (LIKE audit_log);
+        INSERT INTO legacy_system_audit_log
+            SELECT * FROM audit_log WHERE actor_id IS NULL;
+        ALTER TABLE audit_log DISABLE TRIGGER tr_audit_log_append_only;
+        DELETE FROM audit_log WHERE actor_id IS NULL;
+        ALTER TABLE audit_log ENABLE TRIGGER tr_audit_log_append_only;
+    END IF;
+END $$;
+
+ALTER TABLE audit_log
+    ALTER COLUMN actor_kind SET NOT NULL,
+    ALTER COLUMN actor_id SET NOT NULL,
+    ALTER COLUMN method SET NOT NULL,
+    ALTER COLUMN route SET NOT NULL,
+    ALTER COLUMN path SET NOT NULL;